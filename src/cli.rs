@@ -31,6 +31,21 @@ pub enum Commands {
         #[arg(short, long)]
         force: bool,
     },
+    /// Apply configuration changes to the running session
+    Apply {
+        /// Keep watching the configuration and apply every change
+        #[arg(short, long)]
+        watch: bool,
+        /// Seconds between checks in watch mode
+        #[arg(long, default_value = "2")]
+        interval: u64,
+        /// Tear down removed agents without confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would change without touching the session
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Manage agents
     Agents {
         #[command(subcommand)]
@@ -54,16 +69,29 @@ pub enum Commands {
         /// Kill all sessions
         #[arg(long)]
         all: bool,
+        /// Stop only the selected agents' panes (e.g. 3, @frontend, idle)
+        #[arg(long, conflicts_with_all = ["session_name", "all"])]
+        agents: Option<String>,
     },
 
     /// Send command to specific agent
     Hey {
-        /// Agent number (1, 2, 3, etc.)
+        /// Agent selector: IDs, ranges, globs, @tags/groups, idle/busy/error, !exclusions
         agent: String,
-        /// Command to execute
-        command: String,
+        /// Command or prompt to send ("-" reads it from stdin)
+        #[arg(required_unless_present_any = ["file", "template"])]
+        command: Option<String>,
         /// Command arguments
         args: Vec<String>,
+        /// Read the text to send from a file ("-" for stdin)
+        #[arg(short, long, conflicts_with_all = ["command", "template"])]
+        file: Option<String>,
+        /// Send a prompt template from agents/prompts (e.g. review)
+        #[arg(long, conflicts_with = "command")]
+        template: Option<String>,
+        /// Template variable (KEY=VALUE)
+        #[arg(long = "var", value_name = "KEY=VALUE", requires = "template")]
+        vars: Vec<String>,
         /// Command timeout in seconds
         #[arg(short, long, default_value = "30")]
         timeout: u64,
@@ -79,7 +107,7 @@ pub enum Commands {
     },
     /// Synchronize workspaces
     Sync {
-        /// Sync specific agent
+        /// Sync the worktrees of the selected agents (e.g. 2, 1-3, @backend+idle)
         #[arg(short, long)]
         agent: Option<String>,
         /// Force sync even if conflicts
@@ -106,10 +134,27 @@ pub enum Commands {
         #[arg(long)]
         merge_branch: bool,
     },
+    /// Collect agent branches into an integration branch
+    Integrate {
+        /// Agents to integrate, in order (default: all agents)
+        agents: Vec<String>,
+        /// Integration branch to create or update
+        #[arg(long)]
+        into: String,
+        /// Base ref for a new integration branch (default: the trunk)
+        #[arg(long)]
+        base: Option<String>,
+        /// Replay agent commits on top of the integration branch instead of merging
+        #[arg(long)]
+        rebase: bool,
+        /// Command to run after each agent is integrated (e.g. "cargo test")
+        #[arg(long, value_name = "COMMAND")]
+        verify: Option<String>,
+    },
 
     /// Focus on agent pane
     Zoom {
-        /// Agent number to zoom on
+        /// Agent to zoom on (an ID or a selector matching one agent)
         agent: Option<String>,
         /// Return to normal layout
         #[arg(short = 'u', long)]
@@ -118,6 +163,80 @@ pub enum Commands {
         #[arg(short, long)]
         list: bool,
     },
+    /// Show what an agent's pane printed, from its recorded transcript
+    Logs {
+        /// Agent ID
+        agent: String,
+        /// Keep printing new output as it arrives
+        #[arg(short, long)]
+        follow: bool,
+        /// Only show output since a time: 30m, 2h, 1d, 08:00 or 2026-10-17
+        #[arg(long)]
+        since: Option<String>,
+        /// Only show lines containing this text
+        #[arg(long)]
+        grep: Option<String>,
+        /// Record pane output from stdin for the project at this root (used by pipe-pane)
+        #[arg(long, hide = true, value_name = "ROOT")]
+        capture: Option<std::path::PathBuf>,
+    },
+    /// Print the environment an agent runs with
+    Env {
+        /// Agent ID
+        agent: String,
+        /// Print shell exports or a JSON object
+        #[arg(long, value_enum, default_value = "shell")]
+        format: EnvFormat,
+    },
+    /// Report and prune the build caches shared by agents
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// Check the tools that sandbox agent shells
+    Sandbox {
+        #[command(subcommand)]
+        command: SandboxCommands,
+    },
+    /// Show the port ranges allocated to agents
+    Ports {
+        /// Include the agents of every project on this machine
+        #[arg(long)]
+        all: bool,
+    },
+    /// Show which agent wrote each commit, or each line of the given files
+    BlameAgents {
+        /// Files to annotate line by line; without any, attribute commits
+        paths: Vec<String>,
+        /// Revision or range to look at (e.g. main, main..integration)
+        #[arg(long, default_value = "HEAD")]
+        rev: String,
+        /// Only count lines per agent for each file
+        #[arg(long)]
+        summary: bool,
+        /// List each agent commit before the totals
+        #[arg(long)]
+        commits: bool,
+    },
+    /// Show files and hunks changed by more than one agent
+    Overlap {
+        /// Branch the agents started from (default: the trunk)
+        #[arg(long)]
+        base: Option<String>,
+        /// Skip merge conflict prediction
+        #[arg(long)]
+        no_predict: bool,
+    },
+    /// Manage Sprite projects on this machine
+    Projects {
+        #[command(subcommand)]
+        command: ProjectsCommands,
+    },
+    /// List and preview prompt templates
+    Prompts {
+        #[command(subcommand)]
+        command: PromptsCommands,
+    },
     /// Check session status and health
     Status {
         /// Check specific session
@@ -128,6 +247,9 @@ pub enum Commands {
         /// Show detailed health information
         #[arg(long)]
         detailed: bool,
+        /// Show the state of the selected agents (e.g. all, @backend, busy)
+        #[arg(long, conflicts_with = "session_name")]
+        agents: Option<String>,
     },
     /// Show extended help and documentation
     Guide {
@@ -152,6 +274,46 @@ pub enum Commands {
         #[arg(long, value_enum)]
         category: Option<HelpCategory>,
     },
+    /// Snapshot agent worktrees (untracked files included) into hidden refs
+    #[command(args_conflicts_with_subcommands = true)]
+    Checkpoint {
+        #[command(subcommand)]
+        command: Option<CheckpointCommands>,
+        /// Agents to checkpoint (e.g. 2, 1-3, @backend); all agents by default
+        #[arg(short, long)]
+        agents: Option<String>,
+        /// Note stored with the checkpoint
+        #[arg(short, long)]
+        message: Option<String>,
+        /// Keep taking checkpoints until interrupted
+        #[arg(short, long)]
+        watch: bool,
+        /// Seconds between checkpoints in watch mode
+        #[arg(short, long, default_value = "600")]
+        interval: u64,
+        /// Checkpoints to keep per agent; older ones are deleted
+        #[arg(long, default_value = "50")]
+        keep: usize,
+    },
+    /// Manage the git hooks sprite installs in agent worktrees
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
+    },
+    /// Move work between agent worktrees
+    Patch {
+        #[command(subcommand)]
+        command: PatchCommands,
+    },
+    /// React to agent output matching the triggers in agents.yaml
+    Watch {
+        /// Seconds between checks
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
+        /// Check once and exit
+        #[arg(long)]
+        once: bool,
+    },
     /// Update sprite to the latest version
     Update {
         /// Only check for updates without installing
@@ -166,6 +328,15 @@ pub enum Commands {
     },
 }
 
+/// Output format of `sprite env`.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum EnvFormat {
+    /// `export NAME=value` lines, ready for `eval`
+    Shell,
+    /// A JSON object of names to values
+    Json,
+}
+
 /// Help system command categories.
 #[derive(ValueEnum, Debug, Clone)]
 pub enum HelpCategory {
@@ -184,27 +355,170 @@ pub enum HelpCategory {
 #[derive(Subcommand)]
 pub enum ConfigCommands {
     /// Show current configuration
-    Show,
-    /// Validate configuration
+    Show {
+        /// Show every effective value and the layer it came from
+        #[arg(long)]
+        origin: bool,
+    },
+    /// Validate configuration, reporting every problem with its line and column
     Validate,
-    /// Set configuration value
+    /// Print the JSON Schema for agents.yaml (for editor autocompletion)
+    Schema,
+    /// Set configuration value (e.g. agents.2.config.env_vars.RUST_LOG debug)
     Set {
-        /// Configuration key
+        /// Dotted configuration key
         key: String,
         /// Configuration value
         value: String,
     },
     /// Get configuration value
     Get {
-        /// Configuration key
+        /// Dotted configuration key
         key: String,
     },
+    /// Remove a configuration key or list entry
+    Unset {
+        /// Dotted configuration key
+        key: String,
+    },
+    /// Append a value to a configuration list
+    Add {
+        /// Dotted key of the list
+        key: String,
+        /// Value to append
+        value: String,
+    },
+    /// Remove a value from a configuration list
+    Remove {
+        /// Dotted key of the list
+        key: String,
+        /// Value to remove
+        value: String,
+    },
     /// Edit configuration file
     Edit,
     /// Check for configuration changes
     Status,
 }
 
+#[derive(Subcommand)]
+pub enum ProjectsCommands {
+    /// List known projects with their sessions and agent health
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum CheckpointCommands {
+    /// List an agent's checkpoints
+    List {
+        /// Agent ID
+        agent: String,
+    },
+    /// Show how an agent's worktree changed since a checkpoint
+    Diff {
+        /// Agent ID
+        agent: String,
+        /// Checkpoint ID or prefix (default: the latest)
+        id: Option<String>,
+        /// Only summarize the changed files
+        #[arg(long)]
+        stat: bool,
+    },
+    /// Restore an agent's files to a checkpoint (HEAD and the index are kept)
+    Restore {
+        /// Agent ID
+        agent: String,
+        /// Checkpoint ID or prefix
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show the disk use of each agent and of the shared caches
+    Stats,
+    /// Delete agents' Cargo target directories (and those of removed agents)
+    Clean {
+        /// Agents whose target directories to clean (e.g. 2, 1-3, @backend, all);
+        /// by default only those of removed agents
+        #[arg(short, long)]
+        agents: Option<String>,
+        /// Also delete the shared caches (sccache, npm, pnpm, yarn, pip)
+        #[arg(long)]
+        shared: bool,
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SandboxCommands {
+    /// Check that bubblewrap or unshare works here and how each agent is sandboxed
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum HooksCommands {
+    /// Install or refresh the managed hooks (pre-commit, commit-msg, pre-push)
+    Install {
+        /// Agents to install for (e.g. 2, 1-3, @backend); all agents by default
+        #[arg(short, long)]
+        agents: Option<String>,
+    },
+    /// Run the checks of a managed hook (called by the hook scripts)
+    #[command(hide = true)]
+    Run {
+        /// Hook name
+        hook: String,
+        /// Agent whose worktree is committing or pushing
+        #[arg(long)]
+        agent: String,
+        /// Project root holding agents/agents.yaml
+        #[arg(long)]
+        root: std::path::PathBuf,
+        /// Arguments git passed to the hook
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PatchCommands {
+    /// Apply one agent's uncommitted work (or a commit range) to another agent's files
+    Send {
+        /// Agent to take the changes from
+        from: String,
+        /// Agent to apply them to
+        to: String,
+        /// Only send these paths (relative to the worktree root)
+        paths: Vec<String>,
+        /// Send the changes of a commit range instead (e.g. HEAD~2..HEAD, or one commit)
+        #[arg(short, long)]
+        range: Option<String>,
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PromptsCommands {
+    /// List the templates in agents/prompts
+    List,
+    /// Show a template, or render it for an agent
+    Show {
+        /// Template name
+        name: String,
+        /// Render the template for this agent
+        #[arg(short, long)]
+        agent: Option<String>,
+        /// Template variable (KEY=VALUE)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum AgentsCommands {
     /// List all configured agents
@@ -214,21 +528,68 @@ pub enum AgentsCommands {
         /// Agent ID (e.g., "3", "4")
         agent_id: String,
         /// Branch name
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "task")]
         branch: Option<String>,
         /// Workspace path
         #[arg(short, long)]
         workspace: Option<String>,
-        /// Model type
-        #[arg(short, long, default_value = "claude-sonnet-4")]
-        model: String,
+        /// Base ref the new branch starts from (default: the trunk)
+        #[arg(long)]
+        base: Option<String>,
+        /// Task slug; the branch becomes agents/<agent_id>/<task>
+        #[arg(long)]
+        task: Option<String>,
+        /// Model type [default: claude-sonnet-4]
+        #[arg(short, long)]
+        model: Option<String>,
         /// Description
         #[arg(short, long)]
         description: Option<String>,
+        /// Start from a template in agents/templates/<name>.yaml
+        #[arg(long, value_name = "NAME")]
+        from_template: Option<String>,
         /// Skip workspace creation
         #[arg(long)]
         no_workspace: bool,
     },
+    /// Create a new agent by copying an existing one
+    Clone {
+        /// Agent ID to copy
+        source_id: String,
+        /// ID for the new agent
+        agent_id: String,
+        /// New branch name, created from the source branch (defaults to agents/<agent_id>)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Workspace path (defaults to agents/<agent_id>)
+        #[arg(short, long)]
+        workspace: Option<String>,
+        /// Skip workspace creation
+        #[arg(long)]
+        no_workspace: bool,
+    },
+    /// Archive an agent's branch and start a fresh task branch from the base
+    Retask {
+        /// Agent ID
+        agent_id: String,
+        /// Task slug; the new branch becomes agents/<agent_id>/<task>
+        task: String,
+        /// Base ref the new branch starts from (default: the trunk)
+        #[arg(long)]
+        base: Option<String>,
+    },
+    /// List available agent templates
+    Templates,
+    /// Save an agent's configuration as a reusable template
+    SaveTemplate {
+        /// Agent ID to save
+        agent_id: String,
+        /// Template name
+        name: String,
+        /// Overwrite an existing template
+        #[arg(short, long)]
+        force: bool,
+    },
     /// Remove an agent
     Remove {
         /// Agent ID to remove
@@ -252,4 +613,15 @@ pub enum AgentsCommands {
         /// Specific agent ID (or leave blank for all agents)
         agent_id: Option<String>,
     },
+    /// Grow or shrink the number of agents, updating the running session
+    Scale {
+        /// Number of agents to end up with
+        count: usize,
+        /// Retire agents without confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would change without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
@@ -1,10 +1,28 @@
 use crate::cli::AgentsCommands;
-use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::commands::config::{AgentConfig, AgentConfigDetails, SpriteConfig};
 use crate::error::SpriteError;
 use crate::utils::git;
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the project root) holding agent templates
+pub const TEMPLATES_DIR: &str = "agents/templates";
+
+/// Model used when neither the command line nor a template picks one
+const DEFAULT_MODEL: &str = "claude-sonnet-4";
+
+/// A reusable agent setup stored in `agents/templates/<name>.yaml`.
+///
+/// Every field is optional so a template only needs to list what it changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AgentTemplate {
+    pub description: Option<String>,
+    pub model: Option<String>,
+    pub config: AgentConfigDetails,
+}
 
 /// Execute agents command with subcommands
 pub fn execute(command: AgentsCommands) -> Result<()> {
@@ -14,17 +32,41 @@ pub fn execute(command: AgentsCommands) -> Result<()> {
             agent_id,
             branch,
             workspace,
+            base,
+            task,
             model,
             description,
+            from_template,
             no_workspace,
         } => create_agent(
             agent_id,
             branch,
             workspace,
+            base,
+            task,
             model,
             description,
+            from_template,
             no_workspace,
         ),
+        AgentsCommands::Retask {
+            agent_id,
+            task,
+            base,
+        } => retask_agent(agent_id, task, base),
+        AgentsCommands::Clone {
+            source_id,
+            agent_id,
+            branch,
+            workspace,
+            no_workspace,
+        } => clone_agent(source_id, agent_id, branch, workspace, no_workspace),
+        AgentsCommands::Templates => list_agent_templates(),
+        AgentsCommands::SaveTemplate {
+            agent_id,
+            name,
+            force,
+        } => save_agent_template(agent_id, name, force),
         AgentsCommands::Remove {
             agent_id,
             force,
@@ -33,6 +75,11 @@ pub fn execute(command: AgentsCommands) -> Result<()> {
         AgentsCommands::Show { agent_id } => show_agent(agent_id),
         AgentsCommands::Validate => validate_agents(),
         AgentsCommands::Provision { agent_id } => provision_agents(agent_id),
+        AgentsCommands::Scale {
+            count,
+            yes,
+            dry_run,
+        } => scale_agents(count, yes, dry_run),
     }
 }
 
@@ -87,20 +134,8 @@ fn list_agents() -> Result<()> {
     Ok(())
 }
 
-/// Create a new agent
-fn create_agent(
-    agent_id: String,
-    branch: Option<String>,
-    workspace: Option<String>,
-    model: String,
-    description: Option<String>,
-    no_workspace: bool,
-) -> Result<()> {
-    println!("🚀 Creating new agent: {}", agent_id);
-
-    // Load existing configuration
-    let mut config = SpriteConfig::load().context("Failed to load configuration")?;
-
+/// Make sure a new agent ID is well formed and not already taken
+fn check_new_agent_id(config: &SpriteConfig, agent_id: &str) -> Result<()> {
     // Check if agent already exists
     if config.agents.iter().any(|a| a.id == agent_id) {
         return Err(SpriteError::config(format!(
@@ -111,17 +146,116 @@ fn create_agent(
     }
 
     // Validate agent ID format
-    if agent_id.is_empty() || agent_id.contains('/') || agent_id.contains("..") {
+    if !is_simple_name(agent_id) {
         return Err(SpriteError::config(
             "Invalid agent ID. Must be a simple identifier without slashes or '..'.",
         )
         .into());
     }
 
+    Ok(())
+}
+
+/// Check that a name is usable as an agent ID or template file name
+fn is_simple_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains('/') && !name.contains('\\') && !name.contains("..")
+}
+
+/// Validate a task slug used in `agents/<id>/<task>` branch names
+fn validate_task_slug(task: &str) -> Result<()> {
+    let valid = !task.is_empty()
+        && !task.starts_with(['.', '-'])
+        && !task.ends_with(".lock")
+        && !task.contains("..")
+        && task
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if !valid {
+        return Err(SpriteError::validation(
+            format!(
+                "Invalid task slug '{}'. Use letters, digits, '-', '_' or '.'",
+                task
+            ),
+            Some("task".to_string()),
+            Some(task.to_string()),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Branch name for an agent working on a task
+fn task_branch_name(agent_id: &str, task: &str) -> String {
+    format!("agents/{}/{}", agent_id, task)
+}
+
+/// Tag name used to archive a branch when an agent is retasked
+fn archive_tag_name(branch: &str, timestamp: &chrono::DateTime<chrono::Local>) -> String {
+    format!("archive/{}/{}", branch, timestamp.format("%Y%m%d-%H%M%S"))
+}
+
+/// Make sure a base ref resolves before creating branches from it
+fn check_base_ref(base: &str) -> Result<()> {
+    if !git::ref_exists(base)? {
+        return Err(SpriteError::git(format!(
+            "Base ref '{}' does not exist. Use --base to pick another branch, tag or commit.",
+            base
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Create a new agent
+#[allow(clippy::too_many_arguments)]
+fn create_agent(
+    agent_id: String,
+    branch: Option<String>,
+    workspace: Option<String>,
+    base: Option<String>,
+    task: Option<String>,
+    model: Option<String>,
+    description: Option<String>,
+    from_template: Option<String>,
+    no_workspace: bool,
+) -> Result<()> {
+    println!("🚀 Creating new agent: {}", agent_id);
+
+    // Load existing configuration
+    let mut config = SpriteConfig::load_project().context("Failed to load configuration")?;
+    check_new_agent_id(&config, &agent_id)?;
+    let base = base.unwrap_or_else(|| config.trunk());
+
+    // Load the template, if any; explicit flags still win over template values
+    let template = match &from_template {
+        Some(name) => {
+            let template = load_template(&templates_dir(&config), name)?;
+            println!("  📋 Using template: {}", name);
+            template
+        }
+        None => AgentTemplate::default(),
+    };
+
+    if let Some(task) = &task {
+        validate_task_slug(task)?;
+    }
+
     // Set default values
-    let branch_name = branch.unwrap_or_else(|| format!("agents/{}", agent_id));
+    let branch_name = match (&branch, &task) {
+        (Some(branch), _) => branch.clone(),
+        (None, Some(task)) => task_branch_name(&agent_id, task),
+        (None, None) => format!("agents/{}", agent_id),
+    };
     let workspace_path = workspace.unwrap_or_else(|| format!("agents/{}", agent_id));
-    let agent_description = description.unwrap_or_else(|| format!("Agent {} workspace", agent_id));
+    let agent_description = description
+        .or(template.description)
+        .unwrap_or_else(|| format!("Agent {} workspace", agent_id));
+    let model = model
+        .or(template.model)
+        .or_else(crate::commands::config::user_default_model)
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
 
     // Create agent configuration
     let agent_config = AgentConfig {
@@ -130,10 +264,19 @@ fn create_agent(
         worktree_path: workspace_path.clone(),
         model,
         description: agent_description,
+        tags: Vec::new(),
         status: "inactive".to_string(),
-        config: Default::default(),
+        config: template.config,
     };
 
+    // Check git state before touching the configuration
+    if !no_workspace {
+        git::validate_git_repository().context("Failed to validate git repository")?;
+        if !git::branch_exists(&branch_name)? {
+            check_base_ref(&base)?;
+        }
+    }
+
     // Add agent to configuration
     config.agents.push(agent_config);
 
@@ -148,14 +291,11 @@ fn create_agent(
     if !no_workspace {
         println!("  🔧 Creating workspace...");
 
-        // Validate git repository first
-        git::validate_git_repository().context("Failed to validate git repository")?;
-
         // Create branch if it doesn't exist
         if !git::branch_exists(&branch_name)? {
-            git::create_branch_from(&branch_name, "main") // Assuming main is the base branch
+            git::create_branch_at(&branch_name, &base)
                 .with_context(|| format!("Failed to create branch: {}", branch_name))?;
-            println!("    🌿 Created branch: {}", branch_name);
+            println!("    🌿 Created branch: {} (from {})", branch_name, base);
         } else {
             println!("    🌿 Using existing branch: {}", branch_name);
         }
@@ -164,6 +304,7 @@ fn create_agent(
         let workspace_path_buf = PathBuf::from(&workspace_path);
         git::create_worktree(&workspace_path_buf, &branch_name)
             .with_context(|| format!("Failed to create worktree: {}", workspace_path))?;
+        crate::commands::hooks::setup_worktree(&config, &agent_id);
 
         println!("    ✅ Created workspace: {}", workspace_path);
     } else {
@@ -180,12 +321,417 @@ fn create_agent(
     Ok(())
 }
 
+/// Create a new agent from an existing one, branching from its current head
+fn clone_agent(
+    source_id: String,
+    agent_id: String,
+    branch: Option<String>,
+    workspace: Option<String>,
+    no_workspace: bool,
+) -> Result<()> {
+    println!("🧬 Cloning agent {} into agent {}", source_id, agent_id);
+
+    let mut config = SpriteConfig::load_project().context("Failed to load configuration")?;
+    let source = config
+        .get_agent(&source_id)
+        .cloned()
+        .ok_or_else(|| SpriteError::config(format!("Agent {} does not exist.", source_id)))?;
+    check_new_agent_id(&config, &agent_id)?;
+
+    let branch_name = branch.unwrap_or_else(|| format!("agents/{}", agent_id));
+    let workspace_path = workspace.unwrap_or_else(|| format!("agents/{}", agent_id));
+
+    // Check git state before touching the configuration
+    if !no_workspace {
+        git::validate_git_repository().context("Failed to validate git repository")?;
+        if !git::branch_exists(&source.branch)? {
+            return Err(SpriteError::git(format!(
+                "Source branch '{}' of agent {} does not exist",
+                source.branch, source_id
+            ))
+            .into());
+        }
+        // Reusing a branch would start the clone from unrelated work
+        if git::branch_exists(&branch_name)? {
+            return Err(SpriteError::git(format!(
+                "Branch '{}' already exists. Pick another with --branch or delete it first.",
+                branch_name
+            ))
+            .into());
+        }
+    }
+
+    let mut details = source.config.clone();
+    // A working directory pointing into the source workspace should follow the clone
+    if details.work_dir.as_deref() == Some(source.worktree_path.as_str()) {
+        details.work_dir = Some(workspace_path.clone());
+    }
+
+    config.agents.push(AgentConfig {
+        id: agent_id.clone(),
+        branch: branch_name.clone(),
+        worktree_path: workspace_path.clone(),
+        model: source.model.clone(),
+        description: source.description.clone(),
+        tags: Vec::new(),
+        status: "inactive".to_string(),
+        config: details,
+    });
+
+    config
+        .save()
+        .context("Failed to save updated configuration")?;
+
+    println!("  ✅ Agent configuration copied from agent {}", source_id);
+
+    if !no_workspace {
+        println!("  🔧 Creating workspace...");
+
+        git::create_branch_at(&branch_name, &source.branch)
+            .with_context(|| format!("Failed to create branch: {}", branch_name))?;
+        println!(
+            "    🌿 Created branch: {} (from {})",
+            branch_name, source.branch
+        );
+
+        let workspace_path_buf = PathBuf::from(&workspace_path);
+        git::create_worktree(&workspace_path_buf, &branch_name)
+            .with_context(|| format!("Failed to create worktree: {}", workspace_path))?;
+        crate::commands::hooks::setup_worktree(&config, &agent_id);
+
+        println!("    ✅ Created workspace: {}", workspace_path);
+    } else {
+        println!("  ⏭️  Skipping workspace creation (use --no-workspace)");
+    }
+
+    println!("✅ Agent {} cloned from agent {}!", agent_id, source_id);
+    if !no_workspace {
+        println!("   Workspace: {}", workspace_path);
+        println!("   Branch: {}", branch_name);
+    }
+
+    Ok(())
+}
+
+/// Archive an agent's current branch and move its workspace to a fresh task branch
+fn retask_agent(agent_id: String, task: String, base: Option<String>) -> Result<()> {
+    validate_task_slug(&task)?;
+
+    let mut config = SpriteConfig::load_project().context("Failed to load configuration")?;
+    let base = base.unwrap_or_else(|| config.trunk());
+    let agent = config
+        .get_agent(&agent_id)
+        .cloned()
+        .ok_or_else(|| SpriteError::config(format!("Agent {} does not exist.", agent_id)))?;
+
+    git::validate_git_repository().context("Failed to validate git repository")?;
+
+    let new_branch = task_branch_name(&agent_id, &task);
+    if new_branch == agent.branch {
+        return Err(SpriteError::config(format!(
+            "Agent {} is already on branch {}",
+            agent_id, new_branch
+        ))
+        .into());
+    }
+    if git::branch_exists(&new_branch)? {
+        return Err(SpriteError::git(format!(
+            "Branch '{}' already exists. Pick another task name or delete the branch first.",
+            new_branch
+        ))
+        .into());
+    }
+
+    let workspace_path = agent_workspace_path(&config, &agent);
+    if !workspace_path.exists() || !git::is_git_repository_at(&workspace_path)? {
+        return Err(SpriteError::config(format!(
+            "Workspace for agent {} does not exist: {}. Run 'sprite agents provision {}' first.",
+            agent_id, agent.worktree_path, agent_id
+        ))
+        .into());
+    }
+
+    let status = git::get_status_string_at(&workspace_path)?;
+    if !status.is_empty() {
+        return Err(SpriteError::git(format!(
+            "Workspace for agent {} has uncommitted changes. Commit or stash them before retasking.",
+            agent_id
+        ))
+        .into());
+    }
+
+    // Settle where the new branch starts before touching the old one
+    let (start, start_commit) = resolve_updated_base(&workspace_path, &base)?;
+
+    println!("🔁 Retasking agent {} to '{}'", agent_id, task);
+
+    let archived = switch_task_branch(&workspace_path, &agent.branch, &new_branch, &start_commit)?;
+    println!("  🌿 Created branch: {} (from {})", new_branch, start);
+    if let Some(archive_tag) = archived {
+        println!("  📦 Archived {} as tag {}", agent.branch, archive_tag);
+    }
+
+    if let Some(entry) = config.agents.iter_mut().find(|a| a.id == agent_id) {
+        entry.branch = new_branch.clone();
+    }
+    config
+        .save()
+        .context("Failed to save updated configuration")?;
+
+    println!("✅ Agent {} is now on {}", agent_id, new_branch);
+    // SPRITE_BRANCH changed with the branch
+    crate::commands::env::refresh_after_edit();
+
+    Ok(())
+}
+
+/// Check out a new task branch in a workspace, then archive the old branch as a
+/// tag so branch listings stay clean.
+///
+/// The checkout comes first: if it fails, the old branch and the workspace are
+/// left as they were. Returns the archive tag, if the old branch was archived.
+fn switch_task_branch(
+    workspace: &Path,
+    old_branch: &str,
+    new_branch: &str,
+    start_commit: &str,
+) -> Result<Option<String>> {
+    git::checkout_new_branch_at(workspace, new_branch, start_commit)?;
+
+    if git::rev_parse_at(workspace, &format!("refs/heads/{}", old_branch)).is_err() {
+        return Ok(None);
+    }
+    let archive_tag = archive_tag_name(old_branch, &chrono::Local::now());
+    // The workspace has moved on, so the agent must still be recorded on the new branch
+    if let Err(e) = git::archive_branch_at(workspace, old_branch, &archive_tag) {
+        println!("⚠️  Could not archive {}: {}", old_branch, e);
+        return Ok(None);
+    }
+    Ok(Some(archive_tag))
+}
+
+/// Fetch the base and resolve it to the commit a retasked branch starts from.
+///
+/// A remote-tracking base is fetched first. A local branch that tracks a remote
+/// starts from its freshly fetched upstream, unless it has commits of its own.
+/// Fetch failures only warn, so retasking still works offline.
+fn resolve_updated_base(workspace: &Path, base: &str) -> Result<(String, String)> {
+    let upstream = git::upstream_of_at(workspace, base);
+    let remote_ref = upstream.clone().unwrap_or_else(|| base.to_string());
+    let remotes = git::remote_names_at(workspace).unwrap_or_default();
+    if let Some((remote, branch)) = remote_ref.split_once('/') {
+        if remotes.iter().any(|r| r == remote) {
+            match git::fetch_branch_at(workspace, remote, branch) {
+                Ok(()) => println!("  📥 Fetched {}", remote_ref),
+                Err(e) => println!(
+                    "⚠️  Could not fetch {}, using the local copy: {}",
+                    remote_ref, e
+                ),
+            }
+        }
+    }
+
+    let start = match upstream {
+        Some(upstream)
+            if git::rev_parse_at(workspace, &upstream).is_ok()
+                && git::is_ancestor_at(workspace, base, &upstream)? =>
+        {
+            upstream
+        }
+        _ => base.to_string(),
+    };
+    let commit = git::rev_parse_at(workspace, &format!("{}^{{commit}}", start)).map_err(|_| {
+        SpriteError::git(format!(
+            "Base ref '{}' does not exist. Use --base to pick another branch, tag or commit.",
+            start
+        ))
+    })?;
+    Ok((start, commit))
+}
+
+/// Resolve an agent's workspace path against the project root
+fn agent_workspace_path(config: &SpriteConfig, agent: &AgentConfig) -> PathBuf {
+    let path = PathBuf::from(&agent.worktree_path);
+    if path.is_absolute() {
+        return path;
+    }
+    match config.project_root() {
+        Some(root) => root.join(path),
+        None => path,
+    }
+}
+
+/// Get the templates directory for the current project
+fn templates_dir(config: &SpriteConfig) -> PathBuf {
+    config
+        .project_root()
+        .map(|root| root.join(TEMPLATES_DIR))
+        .unwrap_or_else(|| PathBuf::from(TEMPLATES_DIR))
+}
+
+/// Path of a named template inside a templates directory
+fn template_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.yaml", name))
+}
+
+/// Load a named template from a templates directory
+pub fn load_template(dir: &Path, name: &str) -> Result<AgentTemplate> {
+    if !is_simple_name(name) {
+        return Err(SpriteError::validation(
+            format!("Invalid template name '{}'", name),
+            Some("template".to_string()),
+            Some(name.to_string()),
+        )
+        .into());
+    }
+
+    let path = template_path(dir, name);
+    if !path.exists() {
+        return Err(SpriteError::config(format!(
+            "Template '{}' not found at {}. Use 'sprite agents templates' to list available templates.",
+            name,
+            path.display()
+        ))
+        .into());
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read template: {}", path.display()))?;
+    serde_yaml::from_str(&content).map_err(|e| {
+        SpriteError::config(format!(
+            "Failed to parse template {}: {}",
+            path.display(),
+            e
+        ))
+        .into()
+    })
+}
+
+/// Load every template in a templates directory, sorted by name
+pub fn load_templates(dir: &Path) -> Result<Vec<(String, AgentTemplate)>> {
+    let mut templates = Vec::new();
+    if !dir.exists() {
+        return Ok(templates);
+    }
+
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read templates directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|n| n.to_str()) {
+            templates.push((name.to_string(), load_template(dir, name)?));
+        }
+    }
+
+    templates.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(templates)
+}
+
+/// Write a template into a templates directory
+pub fn save_template(dir: &Path, name: &str, template: &AgentTemplate) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create templates directory: {}", dir.display()))?;
+
+    let path = template_path(dir, name);
+    let content = serde_yaml::to_string(template)
+        .map_err(|e| SpriteError::config(format!("Failed to serialize template: {}", e)))?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write template: {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// List available agent templates
+fn list_agent_templates() -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let dir = templates_dir(&config);
+    let templates = load_templates(&dir)?;
+
+    if templates.is_empty() {
+        println!("📝 No agent templates found in {}", dir.display());
+        println!("Use 'sprite agents save-template <id> <name>' to create one.");
+        return Ok(());
+    }
+
+    println!("📋 Agent Templates ({}):", templates.len());
+    println!();
+    for (name, template) in templates {
+        println!("  {}", name);
+        if let Some(description) = &template.description {
+            println!("    📝 Description: {}", description);
+        }
+        if let Some(model) = &template.model {
+            println!("    🤖 Model: {}", model);
+        }
+        if !template.config.env_vars.is_empty() {
+            println!("    🔧 Env vars: {}", template.config.env_vars.len());
+        }
+        if !template.config.startup_commands.is_empty() {
+            println!(
+                "    ▶️  Startup commands: {}",
+                template.config.startup_commands.len()
+            );
+        }
+        println!();
+    }
+    println!("💡 Use 'sprite agents create <id> --from-template <name>' to use one.");
+
+    Ok(())
+}
+
+/// Save an agent's configuration as a template
+fn save_agent_template(agent_id: String, name: String, force: bool) -> Result<()> {
+    let config = SpriteConfig::load_project().context("Failed to load configuration")?;
+    let agent = config
+        .get_agent(&agent_id)
+        .ok_or_else(|| SpriteError::config(format!("Agent {} does not exist.", agent_id)))?;
+
+    if !is_simple_name(&name) {
+        return Err(SpriteError::validation(
+            format!("Invalid template name '{}'", name),
+            Some("template".to_string()),
+            Some(name.clone()),
+        )
+        .into());
+    }
+
+    let dir = templates_dir(&config);
+    if template_path(&dir, &name).exists() && !force {
+        return Err(SpriteError::config(format!(
+            "Template '{}' already exists. Use --force to overwrite it.",
+            name
+        ))
+        .into());
+    }
+
+    let mut details = agent.config.clone();
+    // Workspace-specific paths do not carry over to other agents
+    if details.work_dir.as_deref() == Some(agent.worktree_path.as_str()) {
+        details.work_dir = None;
+    }
+
+    let template = AgentTemplate {
+        description: Some(agent.description.clone()),
+        model: Some(agent.model.clone()),
+        config: details,
+    };
+    let path = save_template(&dir, &name, &template)?;
+
+    println!("✅ Saved agent {} as template '{}'", agent_id, name);
+    println!("   Template: {}", path.display());
+
+    Ok(())
+}
+
 /// Remove an agent
 fn remove_agent(agent_id: String, force: bool, keep_workspace: bool) -> Result<()> {
     println!("🗑️  Removing agent: {}", agent_id);
 
     // Load configuration
-    let mut config = SpriteConfig::load().context("Failed to load configuration")?;
+    let mut config = SpriteConfig::load_project().context("Failed to load configuration")?;
 
     // Find the agent
     let agent_index = config
@@ -408,6 +954,7 @@ fn provision_agents(agent_id: Option<String>) -> Result<()> {
 
     // Validate git repository first
     git::validate_git_repository().context("Failed to validate git repository")?;
+    let trunk = config.trunk();
 
     let agents_to_provision: Vec<AgentConfig> = if let Some(id) = agent_id {
         // Provision specific agent
@@ -460,12 +1007,13 @@ fn provision_agents(agent_id: Option<String>) -> Result<()> {
             // Create branch if it doesn't exist
             if !git::branch_exists(&agent_config.branch)? {
                 println!("    🌿 Creating branch: {}", agent_config.branch);
-                git::create_branch_from(&agent_config.branch, "main")?;
+                git::create_branch_at(&agent_config.branch, &trunk)?;
             }
 
             // Create worktree
             println!("    📁 Creating workspace: {}", agent_config.worktree_path);
             git::create_worktree(&workspace_path, &agent_config.branch)?;
+            crate::commands::hooks::setup_worktree(&config, &agent_config.id);
             println!("    ✅ Workspace created");
         }
     }
@@ -474,3 +1022,429 @@ fn provision_agents(agent_id: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+/// Scale the number of agents, updating the running session if there is one
+fn scale_agents(count: usize, yes: bool, dry_run: bool) -> Result<()> {
+    if count == 0 {
+        return Err(SpriteError::validation(
+            "Cannot scale to 0 agents. Use 'sprite kill' to stop the session.",
+            Some("count".to_string()),
+            Some("0".to_string()),
+        )
+        .into());
+    }
+
+    git::validate_git_repository().context("Must be run from within a git repository")?;
+    let config = SpriteConfig::load_project().context("Failed to load configuration")?;
+
+    let current = config.agents.len();
+    if count == current {
+        println!("✅ Already at {} agent(s)", count);
+        return Ok(());
+    }
+
+    if count > current {
+        scale_up(config, count, dry_run)
+    } else {
+        scale_down(config, count, yes, dry_run)
+    }
+}
+
+/// Provision new numbered agents and open them in the running session
+fn scale_up(mut config: SpriteConfig, count: usize, dry_run: bool) -> Result<()> {
+    let ids = next_agent_ids(&config.agents, count - config.agents.len());
+    println!(
+        "📈 Scaling from {} to {} agents: adding agent(s) {}",
+        config.agents.len(),
+        count,
+        ids.join(", ")
+    );
+    if dry_run {
+        println!("💡 Dry run: nothing was changed");
+        return Ok(());
+    }
+
+    let session = running_session()?;
+    let model =
+        crate::commands::config::user_default_model().unwrap_or_else(|| DEFAULT_MODEL.to_string());
+
+    let mut added = Vec::new();
+    for id in &ids {
+        let agent = AgentConfig {
+            id: id.clone(),
+            branch: format!("agents/{}", id),
+            worktree_path: format!("agents/{}", id),
+            model: model.clone(),
+            description: format!("Agent {} workspace", id),
+            tags: Vec::new(),
+            status: if session.is_some() {
+                "active"
+            } else {
+                "inactive"
+            }
+            .to_string(),
+            config: AgentConfigDetails::default(),
+        };
+
+        let workspace = agent_workspace_path(&config, &agent);
+        if !workspace.exists() {
+            git::create_worktree_from(&workspace, &agent.branch, &config.trunk())
+                .with_context(|| format!("Failed to create worktree for agent {}", id))?;
+        }
+        println!(
+            "  📁 Agent {}: {} ({})",
+            id, agent.worktree_path, agent.branch
+        );
+
+        config.agents.push(agent.clone());
+        crate::commands::hooks::setup_worktree(&config, id);
+        added.push(agent);
+    }
+
+    config
+        .save()
+        .context("Failed to save updated configuration")?;
+
+    match session {
+        Some(session) => {
+            // Open windows with the effective config so env overrides apply
+            let effective = SpriteConfig::load().context("Failed to load configuration")?;
+            let root = effective
+                .project_root()
+                .unwrap_or_else(|| PathBuf::from("."));
+            for agent in &added {
+                let agent = effective.get_agent(&agent.id).unwrap_or(agent);
+                crate::commands::apply::open_agent_window(&session, &effective, agent, &root)?;
+            }
+            crate::commands::apply::record_agent_changes(&added, &[]);
+        }
+        None => println!("ℹ️  No running session; new agents start with the next 'sprite start'"),
+    }
+
+    println!("✅ Scaled to {} agents", config.agents.len());
+    Ok(())
+}
+
+/// Retire the highest-numbered agents that have no running work and nothing to lose
+fn scale_down(mut config: SpriteConfig, count: usize, yes: bool, dry_run: bool) -> Result<()> {
+    let wanted = config.agents.len() - count;
+    let session = running_session()?;
+    let panes = match &session {
+        Some(session) => crate::utils::tmux::list_agent_panes(session)?,
+        None => Vec::new(),
+    };
+    let root = config.project_root().unwrap_or_else(|| PathBuf::from("."));
+    let trunk = config.trunk();
+
+    let mut retiring: Vec<AgentConfig> = Vec::new();
+    let mut busy: Vec<(String, Vec<String>)> = Vec::new();
+    for agent in retirement_order(&config.agents) {
+        if retiring.len() == wanted {
+            break;
+        }
+
+        let workspace = agent_workspace_path(&config, agent);
+        let mut reasons: Vec<String> =
+            crate::commands::remove::check_removal_safety(&workspace, &agent.branch, &trunk)?
+                .into_iter()
+                .map(|issue| match issue {
+                    crate::commands::remove::SafetyIssue::UncommittedChanges => {
+                        "uncommitted changes".to_string()
+                    }
+                    crate::commands::remove::SafetyIssue::UnmergedBranch => {
+                        format!("'{}' not merged into {}", agent.branch, trunk)
+                    }
+                })
+                .collect();
+        if let Some(pane) = crate::commands::apply::find_agent_pane(&panes, agent, &root) {
+            if !pane.is_idle() {
+                reasons.push(format!(
+                    "running {}",
+                    pane.current_command.as_deref().unwrap_or("a command")
+                ));
+            }
+        }
+
+        if reasons.is_empty() {
+            retiring.push(agent.clone());
+        } else {
+            busy.push((agent.id.clone(), reasons));
+        }
+    }
+
+    for (id, reasons) in &busy {
+        println!("  ⏭️  Agent {} is busy: {}", id, reasons.join(", "));
+    }
+    if retiring.is_empty() {
+        return Err(SpriteError::agent(
+            "No idle agents can be retired. Commit and merge their work, or use 'sprite agents remove --force'.",
+            None::<String>,
+        )
+        .into());
+    }
+
+    let ids: Vec<String> = retiring.iter().map(|a| a.id.clone()).collect();
+    let target = config.agents.len() - retiring.len();
+    println!(
+        "📉 Scaling from {} to {} agents: retiring agent(s) {}",
+        config.agents.len(),
+        target,
+        ids.join(", ")
+    );
+    if target > count {
+        println!(
+            "⚠️  Only {} idle agent(s) could be retired; {} requested",
+            retiring.len(),
+            wanted
+        );
+    }
+    if dry_run {
+        println!("💡 Dry run: nothing was changed");
+        return Ok(());
+    }
+
+    if !yes {
+        print!("❓ Close their panes and delete their worktrees and merged branches? [y/N] ");
+        io::stdout().flush().context("Failed to flush stdout")?;
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read user input")?;
+        let input = input.trim().to_lowercase();
+        if input != "y" && input != "yes" {
+            println!("❌ Scaling cancelled.");
+            return Ok(());
+        }
+    }
+
+    for agent in &retiring {
+        if let Some(pane) = crate::commands::apply::find_agent_pane(&panes, agent, &root) {
+            crate::utils::tmux::kill_pane(&pane.pane_id)?;
+        }
+
+        let workspace = agent_workspace_path(&config, agent);
+        if workspace.exists() {
+            git::remove_worktree(&workspace)
+                .with_context(|| format!("Failed to remove worktree for agent {}", agent.id))?;
+        }
+        if git::branch_exists(&agent.branch)? {
+            if let Err(e) = git::delete_branch(&agent.branch, false) {
+                println!(
+                    "  ⚠️  Agent {}: kept branch '{}': {}",
+                    agent.id, agent.branch, e
+                );
+            }
+        }
+        println!("  ➖ Agent {} retired", agent.id);
+    }
+
+    config.agents.retain(|a| !ids.contains(&a.id));
+    config
+        .save()
+        .context("Failed to save updated configuration")?;
+    if session.is_some() {
+        crate::commands::apply::record_agent_changes(&[], &ids);
+    }
+
+    println!("✅ Scaled to {} agents", config.agents.len());
+    Ok(())
+}
+
+/// The project's session, if it is running
+fn running_session() -> Result<Option<String>> {
+    let session = crate::commands::config::current_session_name()?;
+    Ok(crate::utils::tmux::session_exists(&session)?.then_some(session))
+}
+
+/// The lowest unused numeric IDs, so scaling up fills gaps first
+fn next_agent_ids(agents: &[AgentConfig], count: usize) -> Vec<String> {
+    (1..)
+        .map(|n: u32| n.to_string())
+        .filter(|id| !agents.iter().any(|a| &a.id == id))
+        .take(count)
+        .collect()
+}
+
+/// Numbered agents from highest to lowest; named agents are never retired by scaling
+fn retirement_order(agents: &[AgentConfig]) -> Vec<&AgentConfig> {
+    let mut numbered: Vec<(u32, &AgentConfig)> = agents
+        .iter()
+        .filter_map(|a| a.id.parse::<u32>().ok().map(|n| (n, a)))
+        .collect();
+    numbered.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+    numbered.into_iter().map(|(_, a)| a).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_task_slug_validation() {
+        assert!(validate_task_slug("fix-login").is_ok());
+        assert!(validate_task_slug("issue_42.v2").is_ok());
+        assert!(validate_task_slug("").is_err());
+        assert!(validate_task_slug("-flag").is_err());
+        assert!(validate_task_slug("a/b").is_err());
+        assert!(validate_task_slug("a..b").is_err());
+        assert!(validate_task_slug("topic.lock").is_err());
+        assert!(validate_task_slug("has space").is_err());
+    }
+
+    #[test]
+    fn test_task_and_archive_names() {
+        use chrono::TimeZone;
+
+        assert_eq!(task_branch_name("3", "fix-login"), "agents/3/fix-login");
+
+        let timestamp = chrono::Local
+            .with_ymd_and_hms(2024, 5, 1, 9, 30, 0)
+            .unwrap();
+        assert_eq!(
+            archive_tag_name("agents/3", &timestamp),
+            "archive/agents/3/20240501-093000"
+        );
+    }
+
+    #[test]
+    fn test_retask_base_is_fetched_and_resolved() {
+        let dir = TempDir::new().unwrap();
+        let git = |repo: &Path, args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        let origin = dir.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "main"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "one"]);
+        git(dir.path(), &["clone", "-q", "origin", "clone"]);
+        let clone = dir.path().join("clone");
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "two"]);
+        let latest = git(&origin, &["rev-parse", "HEAD"]);
+
+        // The local main is behind; the new branch starts from what was fetched
+        let (start, commit) = resolve_updated_base(&clone, "main").unwrap();
+        assert_eq!(start, "origin/main");
+        assert_eq!(commit, latest);
+
+        assert!(resolve_updated_base(&clone, "mian").is_err());
+    }
+
+    #[test]
+    fn test_failed_retask_checkout_keeps_old_branch() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        git(&["checkout", "-q", "-b", "agents/1"]);
+        git(&["branch", "agents/1-taken"]);
+        let start = git(&["rev-parse", "main"]);
+
+        // The new branch name clashes, so nothing may change
+        assert!(switch_task_branch(repo, "agents/1", "agents/1-taken", &start).is_err());
+        assert_eq!(git(&["branch", "--show-current"]), "agents/1");
+        assert!(git(&["tag"]).is_empty());
+
+        let archived = switch_task_branch(repo, "agents/1", "agents/1-next", &start)
+            .unwrap()
+            .unwrap();
+        assert_eq!(git(&["branch", "--show-current"]), "agents/1-next");
+        assert_eq!(git(&["tag"]), archived);
+        assert!(git(&["branch", "--list", "agents/1"]).is_empty());
+    }
+
+    #[test]
+    fn test_partial_template_uses_defaults() {
+        let template: AgentTemplate = serde_yaml::from_str(
+            "model: claude-opus-4\nconfig:\n  env_vars:\n    ROLE: reviewer\n  startup_commands:\n    - cargo check\n",
+        )
+        .unwrap();
+
+        assert_eq!(template.model.as_deref(), Some("claude-opus-4"));
+        assert!(template.description.is_none());
+        assert_eq!(
+            template.config.env_vars.get("ROLE").map(String::as_str),
+            Some("reviewer")
+        );
+        assert_eq!(template.config.startup_commands, vec!["cargo check"]);
+        assert_eq!(
+            template.config.default_timeout_secs,
+            AgentConfigDetails::default().default_timeout_secs
+        );
+    }
+
+    #[test]
+    fn test_template_roundtrip_and_listing() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("templates");
+
+        let mut template = AgentTemplate {
+            description: Some("Reviews pull requests".to_string()),
+            model: Some("claude-sonnet-4".to_string()),
+            ..Default::default()
+        };
+        template.config.auto_sync = true;
+
+        save_template(&dir, "reviewer", &template).unwrap();
+        save_template(&dir, "tester", &AgentTemplate::default()).unwrap();
+        std::fs::write(dir.join("README.md"), "not a template").unwrap();
+
+        assert_eq!(load_template(&dir, "reviewer").unwrap(), template);
+
+        let names: Vec<String> = load_templates(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["reviewer", "tester"]);
+
+        assert!(load_template(&dir, "missing").is_err());
+        assert!(load_template(&dir, "../reviewer").is_err());
+    }
+
+    #[test]
+    fn test_scale_ids_and_retirement_order() {
+        let agent = |id: &str| AgentConfig {
+            id: id.to_string(),
+            branch: format!("agents/{}", id),
+            worktree_path: format!("agents/{}", id),
+            model: DEFAULT_MODEL.to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            status: "active".to_string(),
+            config: AgentConfigDetails::default(),
+        };
+        let agents = vec![agent("1"), agent("3"), agent("reviewer"), agent("10")];
+
+        assert_eq!(next_agent_ids(&agents, 3), vec!["2", "4", "5"]);
+
+        let order: Vec<&str> = retirement_order(&agents)
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        assert_eq!(order, vec!["10", "3", "1"]);
+    }
+}
@@ -0,0 +1,675 @@
+//! Apply command - Bring a running session in line with the configuration
+//!
+//! `sprite start` records the configuration it launched the session with.
+//! `sprite apply` diffs the current effective configuration against that
+//! record and changes only what differs: new agents get a worktree and a
+//! window running their startup commands, removed agents are torn down,
+//! changed environment variables are re-exported and pane titles are
+//! refreshed. Every other agent keeps running.
+
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::commands::env::agent_env;
+use crate::commands::logs;
+use crate::error::SpriteError;
+use crate::utils::ports::PortLock;
+use crate::utils::{git, project, secrets, tmux};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// File recording the configuration the running session was built from
+const APPLIED_FILE: &str = "applied.yaml";
+
+/// Configuration last applied to a running session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppliedState {
+    session: String,
+    config: SpriteConfig,
+}
+
+/// Environment changes for one agent pane
+#[derive(Debug, Clone, Default, PartialEq)]
+struct EnvUpdate {
+    agent_id: String,
+    set: Vec<(String, String)>,
+    unset: Vec<String>,
+}
+
+/// Everything `apply` would change in the running session
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Plan {
+    rename_session: Option<(String, String)>,
+    added: Vec<String>,
+    removed: Vec<String>,
+    env: Vec<EnvUpdate>,
+    retitled: Vec<String>,
+    /// Agents with changes that only take effect when the agent is relaunched
+    needs_restart: Vec<(String, Vec<&'static str>)>,
+    global_env: EnvUpdate,
+}
+
+impl Plan {
+    fn is_empty(&self) -> bool {
+        self.rename_session.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.env.is_empty()
+            && self.retitled.is_empty()
+            && self.needs_restart.is_empty()
+            && self.global_env.set.is_empty()
+            && self.global_env.unset.is_empty()
+    }
+}
+
+/// Execute the apply command
+pub fn execute(watch: bool, interval: u64, yes: bool, dry_run: bool) -> Result<()> {
+    project::execute_from_project_root(|| {
+        if !watch {
+            return apply_once(yes, dry_run, true).map(|_| ());
+        }
+
+        println!(
+            "👀 Watching configuration every {}s (Ctrl+C to stop)...",
+            interval
+        );
+        let mut last_seen = None;
+        loop {
+            let fingerprint = config_fingerprint();
+            if fingerprint.is_some() && fingerprint != last_seen {
+                if last_seen.is_some() {
+                    println!();
+                    println!("🔄 Configuration changed");
+                }
+                // Removals are never confirmed interactively while watching
+                if let Err(e) = apply_once(yes, dry_run, false) {
+                    eprintln!("❌ Failed to apply configuration: {}", e);
+                }
+                last_seen = fingerprint;
+            }
+            std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+        }
+    })
+}
+
+/// Record the configuration a freshly started session was built from
+pub fn record_applied(session: &str, config: &SpriteConfig) {
+    save_state(&AppliedState {
+        session: session.to_string(),
+        config: config.clone(),
+    });
+}
+
+/// Update the applied record after agents were added to or retired from the session directly
+pub fn record_agent_changes(added: &[AgentConfig], removed: &[String]) {
+    if let Some(mut state) = load_state() {
+        state.config.agents.retain(|a| !removed.contains(&a.id));
+        state.config.agents.extend(added.iter().cloned());
+        save_state(&state);
+    }
+}
+
+/// Title shown on an agent's pane
+pub fn pane_title(agent: &AgentConfig) -> String {
+    format!("🤖 Agent {} - {}", agent.id, agent.description)
+}
+
+/// Diff the configuration against the running session and apply the changes
+///
+/// Returns whether the session was changed.
+fn apply_once(yes: bool, dry_run: bool, interactive: bool) -> Result<bool> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let new_session = config.effective_session_name();
+    if !dry_run {
+        crate::commands::env::refresh_envrcs(&config);
+    }
+
+    let previous = load_state();
+    let live_session = previous
+        .as_ref()
+        .map(|state| state.session.clone())
+        .unwrap_or_else(|| new_session.clone());
+
+    if !tmux::session_exists(&live_session)? {
+        println!(
+            "ℹ️  No running session '{}'. Changes take effect on the next 'sprite start'.",
+            live_session
+        );
+        return Ok(false);
+    }
+
+    let root = config.project_root().unwrap_or_else(|| PathBuf::from("."));
+    let panes = tmux::list_agent_panes(&live_session)?;
+
+    let applied = match previous {
+        Some(state) => state.config,
+        None => {
+            println!("ℹ️  No record of the applied configuration; comparing with running panes.");
+            let mut baseline = config.clone();
+            baseline
+                .agents
+                .retain(|agent| find_agent_pane(&panes, agent, &root).is_some());
+            baseline
+        }
+    };
+
+    let plan = build_plan(&applied, &live_session, &config, &new_session);
+    if plan.is_empty() {
+        println!(
+            "✅ Session '{}' already matches the configuration",
+            live_session
+        );
+        return Ok(false);
+    }
+
+    print_plan(&plan);
+    if dry_run {
+        println!();
+        println!("💡 Dry run: nothing was changed");
+        return Ok(false);
+    }
+    println!();
+
+    let session = match &plan.rename_session {
+        Some((old, new)) => {
+            tmux::rename_session(old, new)?;
+            println!("🎮 Renamed session '{}' → '{}'", old, new);
+            new.clone()
+        }
+        None => live_session,
+    };
+
+    if !plan.global_env.set.is_empty() {
+        tmux::set_environment(&session, &plan.global_env.set.iter().cloned().collect())?;
+    }
+    for key in &plan.global_env.unset {
+        tmux::unset_environment(&session, key)?;
+    }
+
+    for agent_id in &plan.added {
+        let agent = config
+            .get_agent(agent_id)
+            .ok_or_else(|| SpriteError::agent_not_found(agent_id.as_str()))?;
+        open_agent_window(&session, &config, agent, &root)?;
+    }
+
+    for update in &plan.env {
+        let pane = match config
+            .get_agent(&update.agent_id)
+            .and_then(|agent| find_agent_pane(&panes, agent, &root))
+        {
+            Some(pane) => pane,
+            None => {
+                println!(
+                    "⚠️  Agent {}: no pane found, skipping environment",
+                    update.agent_id
+                );
+                continue;
+            }
+        };
+        // Typed into anything but a shell, the exports would become input to
+        // the program running there (usually the agent's CLI)
+        if !pane.is_idle() {
+            println!(
+                "⚠️  Agent {}: {} is running, restart the agent to pick up environment changes",
+                update.agent_id,
+                pane.current_command.as_deref().unwrap_or("a program")
+            );
+            continue;
+        }
+        export_env(&session, &pane.pane_id, &update.set, &update.unset)?;
+        println!(
+            "🔧 Agent {}: updated {} environment variable(s)",
+            update.agent_id,
+            update.set.len() + update.unset.len()
+        );
+    }
+
+    for agent_id in &plan.retitled {
+        if let Some(agent) = config.get_agent(agent_id) {
+            if let Some(pane) = find_agent_pane(&panes, agent, &root) {
+                tmux::tag_agent_pane(&pane.pane_id, &agent.id, &pane_title(agent))?;
+                println!("🏷️  Agent {}: {}", agent.id, agent.description);
+            }
+        }
+    }
+
+    for (agent_id, fields) in &plan.needs_restart {
+        println!(
+            "⚠️  Agent {}: {} changed; restart the agent in its pane to pick this up",
+            agent_id,
+            fields.join(", ")
+        );
+    }
+
+    // Agents whose removal is declined stay in the record, so the next run asks again
+    let mut kept = Vec::new();
+    if !plan.removed.is_empty() {
+        let confirmed = yes || (interactive && confirm_removal(&plan.removed)?);
+        if !confirmed {
+            println!(
+                "⏭️  Kept removed agent(s) {} running (use --yes to tear them down)",
+                plan.removed.join(", ")
+            );
+        }
+        for agent_id in &plan.removed {
+            let agent = match applied.get_agent(agent_id) {
+                Some(agent) => agent,
+                None => continue,
+            };
+            if confirmed {
+                remove_agent(&panes, agent, &root)?;
+            } else {
+                kept.push(agent.clone());
+            }
+        }
+    }
+
+    let mut recorded = config.clone();
+    recorded.agents.extend(kept);
+    save_state(&AppliedState {
+        session,
+        config: recorded,
+    });
+
+    println!("✅ Configuration applied");
+    Ok(true)
+}
+
+/// Work out what differs between the applied and the current configuration
+fn build_plan(
+    applied: &SpriteConfig,
+    live_session: &str,
+    config: &SpriteConfig,
+    new_session: &str,
+) -> Plan {
+    let changes = applied.diff(config);
+    let mut plan = Plan {
+        added: changes.added_agents,
+        removed: changes.removed_agents,
+        ..Plan::default()
+    };
+
+    if live_session != new_session {
+        plan.rename_session = Some((live_session.to_string(), new_session.to_string()));
+    }
+
+    let (set, unset) = diff_env(
+        &applied
+            .settings
+            .global_env_vars
+            .clone()
+            .into_iter()
+            .collect(),
+        &config
+            .settings
+            .global_env_vars
+            .clone()
+            .into_iter()
+            .collect(),
+    );
+    plan.global_env = EnvUpdate {
+        agent_id: String::new(),
+        set,
+        unset,
+    };
+
+    // Global env changes reach every agent, so look at all of them, not just modified ones
+    let lock = PortLock::load().unwrap_or_default();
+    for new in &config.agents {
+        let old = match applied.get_agent(&new.id) {
+            Some(old) => old,
+            None => continue,
+        };
+
+        let (set, unset) = diff_env(
+            &agent_env(applied, old, &lock),
+            &agent_env(config, new, &lock),
+        );
+        if !set.is_empty() || !unset.is_empty() {
+            plan.env.push(EnvUpdate {
+                agent_id: new.id.clone(),
+                set,
+                unset,
+            });
+        }
+
+        if old.description != new.description {
+            plan.retitled.push(new.id.clone());
+        }
+
+        let mut fields = Vec::new();
+        if old.branch != new.branch {
+            fields.push("branch");
+        }
+        if old.worktree_path != new.worktree_path {
+            fields.push("worktree_path");
+        }
+        if old.model != new.model {
+            fields.push("model");
+        }
+        if old.config.shell != new.config.shell {
+            fields.push("shell");
+        }
+        if old.config.sandbox != new.config.sandbox {
+            fields.push("sandbox");
+        }
+        if old.config.startup_commands != new.config.startup_commands {
+            fields.push("startup_commands");
+        }
+        if !fields.is_empty() {
+            plan.needs_restart.push((new.id.clone(), fields));
+        }
+    }
+
+    plan
+}
+
+/// Variables to set (new or changed) and to unset between two environments
+fn diff_env(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> (Vec<(String, String)>, Vec<String>) {
+    let set = new
+        .iter()
+        .filter(|(key, value)| old.get(*key) != Some(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let unset = old
+        .keys()
+        .filter(|key| !new.contains_key(*key))
+        .cloned()
+        .collect();
+    (set, unset)
+}
+
+fn print_plan(plan: &Plan) {
+    println!("📋 Changes to apply:");
+    if let Some((old, new)) = &plan.rename_session {
+        println!("  🎮 Rename session '{}' → '{}'", old, new);
+    }
+    for agent_id in &plan.added {
+        println!("  ➕ Add agent {}", agent_id);
+    }
+    for agent_id in &plan.removed {
+        println!("  ➖ Remove agent {}", agent_id);
+    }
+    if !plan.global_env.set.is_empty() || !plan.global_env.unset.is_empty() {
+        println!(
+            "  🌐 Session environment: {} set, {} unset",
+            plan.global_env.set.len(),
+            plan.global_env.unset.len()
+        );
+    }
+    for update in &plan.env {
+        let mut keys: Vec<&str> = update.set.iter().map(|(k, _)| k.as_str()).collect();
+        keys.extend(update.unset.iter().map(|k| k.as_str()));
+        println!(
+            "  🔧 Agent {}: re-export {}",
+            update.agent_id,
+            keys.join(", ")
+        );
+    }
+    for agent_id in &plan.retitled {
+        println!("  🏷️  Agent {}: new description", agent_id);
+    }
+    for (agent_id, fields) in &plan.needs_restart {
+        println!(
+            "  ⚠️  Agent {}: {} (needs an agent restart)",
+            agent_id,
+            fields.join(", ")
+        );
+    }
+}
+
+/// Find the pane running an agent, by tag first and working directory second
+pub fn find_agent_pane<'a>(
+    panes: &'a [tmux::AgentPane],
+    agent: &AgentConfig,
+    root: &Path,
+) -> Option<&'a tmux::AgentPane> {
+    if let Some(pane) = panes
+        .iter()
+        .find(|p| p.agent_id.as_deref() == Some(agent.id.as_str()))
+    {
+        return Some(pane);
+    }
+
+    let workspace = root.join(&agent.worktree_path);
+    panes.iter().find(|p| {
+        p.agent_id.is_none()
+            && p.current_path
+                .as_deref()
+                .map(|path| Path::new(path).starts_with(&workspace))
+                .unwrap_or(false)
+    })
+}
+
+/// Create an agent's worktree (if missing), open a window for it and launch its startup commands
+pub fn open_agent_window(
+    session: &str,
+    config: &SpriteConfig,
+    agent: &AgentConfig,
+    root: &Path,
+) -> Result<()> {
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        git::create_worktree_from(&workspace, &agent.branch, &config.trunk())
+            .with_context(|| format!("Failed to create worktree for agent {}", agent.id))?;
+        crate::commands::hooks::setup_worktree(config, &agent.id);
+        println!(
+            "📁 Agent {}: created worktree {}",
+            agent.id, agent.worktree_path
+        );
+    }
+
+    let pane = tmux::create_agent_window(
+        session,
+        &format!("agent-{}", agent.id),
+        &workspace.to_string_lossy(),
+    )?;
+    if let Err(e) = crate::commands::sandbox::launch(&pane, config, agent) {
+        return Err(e.context(format!(
+            "Agent {}: sandbox unavailable, pane not started",
+            agent.id
+        )));
+    }
+    tmux::tag_agent_pane(&pane, &agent.id, &pane_title(agent))?;
+    if let Err(e) = logs::start_capture(&pane, &agent.id, root) {
+        eprintln!("⚠️  Agent {}: transcript not recorded: {}", agent.id, e);
+    }
+
+    let lock = PortLock::load().unwrap_or_default();
+    let env: Vec<(String, String)> = agent_env(config, agent, &lock).into_iter().collect();
+    export_env(session, &pane, &env, &[])?;
+    tmux::send_keys(
+        session,
+        &pane,
+        &format!("echo '🤖 Agent {} - {}'", agent.id, agent.description),
+    )?;
+    for command in &agent.config.startup_commands {
+        tmux::send_keys(session, &pane, command)?;
+    }
+
+    println!("➕ Agent {}: opened window 'agent-{}'", agent.id, agent.id);
+    Ok(())
+}
+
+/// Close a removed agent's pane and drop its worktree when it has no local changes
+fn remove_agent(panes: &[tmux::AgentPane], agent: &AgentConfig, root: &Path) -> Result<()> {
+    if let Some(pane) = find_agent_pane(panes, agent, root) {
+        tmux::kill_pane(&pane.pane_id)?;
+    }
+
+    let workspace = root.join(&agent.worktree_path);
+    if workspace.exists() {
+        if !git::get_status_string_at(&workspace)?.is_empty() {
+            println!(
+                "⚠️  Agent {}: kept worktree {} (uncommitted changes)",
+                agent.id, agent.worktree_path
+            );
+        } else {
+            git::remove_worktree(&workspace)?;
+        }
+    }
+
+    println!("➖ Agent {}: removed", agent.id);
+    Ok(())
+}
+
+/// Export and unset variables in an agent's shell
+fn export_env(session: &str, pane: &str, set: &[(String, String)], unset: &[String]) -> Result<()> {
+    for (key, value) in set {
+        let command = format!("export {}={}", key, secrets::shell_value(value));
+        tmux::send_keys(session, pane, &command)?;
+    }
+    for key in unset {
+        tmux::send_keys(session, pane, &format!("unset {}", key))?;
+    }
+    Ok(())
+}
+
+fn confirm_removal(agent_ids: &[String]) -> Result<bool> {
+    print!(
+        "❓ Close the panes of removed agent(s) {} and delete their clean worktrees? [y/N] ",
+        agent_ids.join(", ")
+    );
+    io::stdout().flush().context("Failed to flush stdout")?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read user input")?;
+
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Serialized effective configuration, used by watch mode to notice edits to any layer
+fn config_fingerprint() -> Option<String> {
+    let config = SpriteConfig::load().ok()?;
+    serde_yaml::to_string(&config).ok()
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(git::get_common_dir()?.join("sprite").join(APPLIED_FILE))
+}
+
+fn load_state() -> Option<AppliedState> {
+    let content = std::fs::read_to_string(state_path().ok()?).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+/// Remember what the session was built from; failures only cost a less precise diff
+fn save_state(state: &AppliedState) {
+    let path = match state_path() {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_yaml::to_string(state) {
+        let _ = std::fs::write(path, content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str, env: &[(&str, &str)]) -> AgentConfig {
+        let mut agent = AgentConfig {
+            id: id.to_string(),
+            branch: format!("agents/{}", id),
+            worktree_path: format!("agents/{}", id),
+            model: "claude-sonnet-4".to_string(),
+            description: format!("Agent {}", id),
+            tags: Vec::new(),
+            status: "active".to_string(),
+            config: Default::default(),
+        };
+        for (k, v) in env {
+            agent.config.env_vars.insert(k.to_string(), v.to_string());
+        }
+        agent
+    }
+
+    #[test]
+    fn test_build_plan() {
+        let mut applied = SpriteConfig::default();
+        applied.agents = vec![
+            agent("1", &[("RUST_LOG", "info")]),
+            agent("2", &[("TOKEN", "a")]),
+            agent("3", &[]),
+        ];
+
+        let mut config = applied.clone();
+        config.agents.retain(|a| a.id != "3");
+        config.agents.push(agent("4", &[]));
+        config.agents[0].description = "Reviewer".to_string();
+        config.agents[0].model = "claude-opus-4".to_string();
+        config.agents[1].config.env_vars.clear();
+        config
+            .settings
+            .global_env_vars
+            .insert("CI".to_string(), "1".to_string());
+
+        let plan = build_plan(&applied, "old", &config, "new");
+        assert_eq!(
+            plan.rename_session,
+            Some(("old".to_string(), "new".to_string()))
+        );
+        assert_eq!(plan.added, vec!["4".to_string()]);
+        assert_eq!(plan.removed, vec!["3".to_string()]);
+        assert_eq!(plan.retitled, vec!["1".to_string()]);
+        assert_eq!(plan.needs_restart, vec![("1".to_string(), vec!["model"])]);
+        assert_eq!(
+            plan.global_env.set,
+            vec![("CI".to_string(), "1".to_string())]
+        );
+        assert_eq!(
+            plan.env,
+            vec![
+                EnvUpdate {
+                    agent_id: "1".to_string(),
+                    set: vec![("CI".to_string(), "1".to_string())],
+                    unset: vec![],
+                },
+                EnvUpdate {
+                    agent_id: "2".to_string(),
+                    set: vec![("CI".to_string(), "1".to_string())],
+                    unset: vec!["TOKEN".to_string()],
+                },
+            ]
+        );
+
+        assert!(build_plan(&applied, "s", &applied, "s").is_empty());
+    }
+
+    #[test]
+    fn test_find_pane_prefers_tag() {
+        let panes = vec![
+            tmux::AgentPane {
+                pane_id: "%1".to_string(),
+                agent_id: None,
+                current_path: Some("/repo/agents/2/src".to_string()),
+                current_command: None,
+            },
+            tmux::AgentPane {
+                pane_id: "%2".to_string(),
+                agent_id: Some("1".to_string()),
+                current_path: Some("/tmp".to_string()),
+                current_command: None,
+            },
+        ];
+        let root = Path::new("/repo");
+
+        let pane_of =
+            |id: &str| find_agent_pane(&panes, &agent(id, &[]), root).map(|p| p.pane_id.as_str());
+        assert_eq!(pane_of("1"), Some("%2"));
+        assert_eq!(pane_of("2"), Some("%1"));
+        assert_eq!(pane_of("3"), None);
+    }
+}
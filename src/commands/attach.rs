@@ -97,13 +97,23 @@ fn attach_with_project_root_detection(session_name: Option<String>) -> Result<()
 
 /// Find the default sprite session to attach to
 fn find_default_session() -> Result<String> {
-    let sessions = tmux::list_sessions().context("Failed to list sessions")?;
-
-    // First try to find a session with "sprite-session" name
-    if let Some(session) = sessions.iter().find(|s| s.name.contains("sprite-session")) {
-        return Ok(session.name.clone());
+    // Inside a sprite project, only ever attach to that project's session
+    if let Ok(project_session) = crate::commands::config::current_session_name() {
+        if tmux::session_exists(&project_session)? {
+            return Ok(project_session);
+        }
+        return Err(SpriteError::session(
+            format!(
+                "No sprite session '{}' is running for this project. Use 'sprite start' to create it.",
+                project_session
+            ),
+            Some(project_session),
+        )
+        .into());
     }
 
+    let sessions = tmux::list_sessions().context("Failed to list sessions")?;
+
     // If no sprite session found, take the most recently created session
     if let Some(session) = sessions.first() {
         return Ok(session.name.clone());
@@ -137,6 +147,8 @@ fn list_available_sessions() -> Result<()> {
     println!("📋 Available tmux sessions:");
     println!();
 
+    let registry = crate::utils::registry::ProjectRegistry::load().unwrap_or_default();
+
     for session in sessions {
         let status = if session.attached {
             "🟢 Active".to_string()
@@ -146,9 +158,14 @@ fn list_available_sessions() -> Result<()> {
 
         let created_ago = format_time_ago(&session.created);
 
+        let project = registry
+            .find_by_session(&session.name)
+            .map(|p| format!(" [project: {}]", p.name))
+            .unwrap_or_default();
+
         println!(
-            "  {} {} ({} windows) - {}",
-            status, session.name, session.windows, created_ago
+            "  {} {}{} ({} windows) - {}",
+            status, session.name, project, session.windows, created_ago
         );
     }
 
@@ -0,0 +1,302 @@
+//! Blame-agents command - Attribute commits and lines to the agents that wrote them
+//!
+//! A commit belongs to the agent named in its `Sprite-Agent` trailer, or failing
+//! that, the agent whose identity authored it. Both survive merging, rebasing and
+//! cherry-picking the agent branches, so attribution still works on the trunk.
+
+use crate::commands::hooks::{self, AGENT_TRAILER};
+use crate::utils::git;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Commit ID git blame uses for lines that are not committed yet
+const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+/// One line of a blamed file
+#[derive(Debug, Clone, PartialEq)]
+struct BlameLine {
+    commit: String,
+    author_email: String,
+    number: usize,
+    text: String,
+}
+
+/// Commits and changed lines attributed to one agent
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AgentTotals {
+    commits: usize,
+    added: usize,
+    removed: usize,
+}
+
+/// A commit from `git log`, with its agent if known
+#[derive(Debug, Clone, PartialEq)]
+struct LoggedCommit {
+    commit: String,
+    agent: Option<String>,
+    subject: String,
+    added: usize,
+    removed: usize,
+}
+
+/// Execute the blame-agents command
+///
+/// With `paths`, attributes each line of those files; otherwise the commits of `rev`.
+pub fn execute(paths: &[String], rev: &str, summary: bool, commits: bool) -> Result<()> {
+    git::validate_git_repository().context("Must be run from within a git repository")?;
+    let dir = std::env::current_dir().context("Failed to read the current directory")?;
+
+    if paths.is_empty() {
+        return blame_commits(&dir, rev, commits);
+    }
+    for (index, path) in paths.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        blame_file(&dir, rev, path, summary)?;
+    }
+    Ok(())
+}
+
+/// Count the commits (and lines they changed) of each agent in `rev`'s history
+fn blame_commits(dir: &Path, rev: &str, list: bool) -> Result<()> {
+    let format = format!(
+        "%x1e%H%x00%ae%x00%(trailers:key={},valueonly,separator=%x2C)%x00%s",
+        AGENT_TRAILER
+    );
+    let log = parse_log(&git::log_numstat_at(dir, rev, &format)?);
+    if log.is_empty() {
+        println!("📭 No commits in '{}'.", rev);
+        return Ok(());
+    }
+
+    let mut totals: BTreeMap<Option<String>, AgentTotals> = BTreeMap::new();
+    for commit in &log {
+        let entry = totals.entry(commit.agent.clone()).or_default();
+        entry.commits += 1;
+        entry.added += commit.added;
+        entry.removed += commit.removed;
+    }
+
+    if list {
+        for commit in log.iter().filter(|c| c.agent.is_some()) {
+            println!(
+                "{}  {:<12} {}",
+                &commit.commit[..commit.commit.len().min(10)],
+                agent_label(commit.agent.as_deref()),
+                commit.subject
+            );
+        }
+        println!();
+    }
+
+    println!("🤖 Commits by agent in '{}' ({} total):", rev, log.len());
+    // Agents first, in ID order, then the commits no agent made
+    let mut rows: Vec<_> = totals.iter().filter(|(agent, _)| agent.is_some()).collect();
+    rows.extend(totals.iter().filter(|(agent, _)| agent.is_none()));
+    for (agent, totals) in rows {
+        println!(
+            "  {:<12} {:>5} commit(s)  +{} -{}",
+            agent_label(agent.as_deref()),
+            totals.commits,
+            totals.added,
+            totals.removed
+        );
+    }
+    if !totals.keys().any(|agent| agent.is_some()) {
+        println!(
+            "💡 No agent commits found. Agents get their own identity and a '{}' trailer from 'sprite hooks install'.",
+            AGENT_TRAILER
+        );
+    }
+    Ok(())
+}
+
+/// Show which agent last changed each line of a file
+fn blame_file(dir: &Path, rev: &str, path: &str, summary: bool) -> Result<()> {
+    let lines = parse_blame(&git::blame_porcelain_at(dir, rev, path)?);
+
+    let mut commits: Vec<String> = lines
+        .iter()
+        .map(|line| line.commit.clone())
+        .filter(|commit| commit != UNCOMMITTED)
+        .collect();
+    commits.sort();
+    commits.dedup();
+    let trailers = git::commit_trailers_at(dir, &commits, AGENT_TRAILER)?;
+
+    let agents: Vec<Option<String>> = lines
+        .iter()
+        .map(|line| commit_agent(trailers.get(&line.commit), &line.author_email))
+        .collect();
+
+    if summary {
+        let mut counts: BTreeMap<Option<String>, usize> = BTreeMap::new();
+        for agent in &agents {
+            *counts.entry(agent.clone()).or_default() += 1;
+        }
+        println!("📄 {} ({} line(s) at {})", path, lines.len(), rev);
+        for (agent, count) in counts.iter().filter(|(agent, _)| agent.is_some()) {
+            print_share(agent.as_deref(), *count, lines.len());
+        }
+        if let Some(count) = counts.get(&None) {
+            print_share(None, *count, lines.len());
+        }
+        return Ok(());
+    }
+
+    println!("📄 {} ({})", path, rev);
+    let width = agents
+        .iter()
+        .map(|agent| agent_label(agent.as_deref()).len())
+        .max()
+        .unwrap_or_default();
+    let number_width = lines.len().to_string().len();
+    for (line, agent) in lines.iter().zip(&agents) {
+        let label = if line.commit == UNCOMMITTED {
+            "uncommitted".to_string()
+        } else {
+            agent_label(agent.as_deref())
+        };
+        println!(
+            "  {:<width$}  {:>number_width$} │ {}",
+            label,
+            line.number,
+            line.text,
+            width = width.max("uncommitted".len()),
+            number_width = number_width
+        );
+    }
+    Ok(())
+}
+
+fn print_share(agent: Option<&str>, count: usize, total: usize) {
+    println!(
+        "  {:<12} {:>6}  ({:.1}%)",
+        agent_label(agent),
+        count,
+        count as f64 * 100.0 / total.max(1) as f64
+    );
+}
+
+fn agent_label(agent: Option<&str>) -> String {
+    match agent {
+        Some(agent) => format!("Agent {}", agent),
+        None => "no agent".to_string(),
+    }
+}
+
+/// The agent behind a commit: its trailer (the first, if several agents touched it),
+/// otherwise its author's agent identity
+fn commit_agent(trailer: Option<&String>, author_email: &str) -> Option<String> {
+    trailer
+        .and_then(|value| value.split(',').next())
+        .map(|agent| agent.trim().to_string())
+        .filter(|agent| !agent.is_empty())
+        .or_else(|| hooks::agent_from_email(author_email))
+}
+
+/// Parse `git blame --line-porcelain` output
+fn parse_blame(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut commit = String::new();
+    let mut number = 0;
+    let mut author_email = String::new();
+
+    for line in output.lines() {
+        if let Some(text) = line.strip_prefix('\t') {
+            lines.push(BlameLine {
+                commit: commit.clone(),
+                author_email: author_email.clone(),
+                number,
+                text: text.to_string(),
+            });
+        } else if let Some(email) = line.strip_prefix("author-mail ") {
+            author_email = email.trim_matches(|c| c == '<' || c == '>').to_string();
+        } else {
+            let mut fields = line.split(' ');
+            let first = fields.next().unwrap_or_default();
+            if first.len() >= 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+                commit = first.to_string();
+                number = fields.nth(1).and_then(|n| n.parse().ok()).unwrap_or(0);
+            }
+        }
+    }
+    lines
+}
+
+/// Parse `git log --numstat` output whose records start with `\x1e` and hold
+/// commit, author email, agent trailer and subject separated by NULs
+fn parse_log(output: &str) -> Vec<LoggedCommit> {
+    output
+        .split('\u{1e}')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.splitn(4, '\0');
+            let commit = fields.next()?.to_string();
+            let email = fields.next()?;
+            let trailer = fields
+                .next()
+                .map(|t| t.to_string())
+                .filter(|t| !t.is_empty());
+            let subject = fields.next().unwrap_or_default().to_string();
+
+            let (mut added, mut removed) = (0, 0);
+            for stat in lines {
+                let mut counts = stat.split('\t');
+                // Binary files show '-' instead of line counts
+                added += counts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+                removed += counts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            }
+
+            Some(LoggedCommit {
+                agent: commit_agent(trailer.as_ref(), email),
+                commit,
+                subject,
+                added,
+                removed,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blame_and_log() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let blame = format!(
+            "{a} 1 1 2\nauthor Agent 1 (claude-sonnet-4)\nauthor-mail <sprite-agent-1@localhost>\nfilename x.rs\n\tfn main() {{\n\
+             {a} 2 2\nauthor Agent 1 (claude-sonnet-4)\nauthor-mail <sprite-agent-1@localhost>\nfilename x.rs\n\t    run();\n\
+             {b} 3 3 1\nauthor Jane\nauthor-mail <jane@example.com>\nfilename x.rs\n\t}}\n"
+        );
+        let lines = parse_blame(&blame);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].number, 2);
+        assert_eq!(lines[1].text, "    run();");
+        assert_eq!(
+            commit_agent(None, &lines[0].author_email),
+            Some("1".to_string())
+        );
+        assert_eq!(commit_agent(None, &lines[2].author_email), None);
+        assert_eq!(
+            commit_agent(Some(&"3,1".to_string()), &lines[2].author_email),
+            Some("3".to_string())
+        );
+
+        let log = format!(
+            "\u{1e}{a}\0sprite-agent-2@localhost\0\0Add parser\n\n10\t2\tsrc/parse.rs\n-\t-\tlogo.png\n\
+             \u{1e}{b}\0jane@example.com\x001\0Fix typo\n\n1\t1\tREADME.md\n"
+        );
+        let commits = parse_log(&log);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].agent.as_deref(), Some("2"));
+        assert_eq!((commits[0].added, commits[0].removed), (10, 2));
+        assert_eq!(commits[1].agent.as_deref(), Some("1"));
+        assert_eq!(commits[1].subject, "Fix typo");
+    }
+}
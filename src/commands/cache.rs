@@ -0,0 +1,323 @@
+//! Cache command - Build caches shared between agent worktrees
+//!
+//! Agents building the same project in separate worktrees would each compile and
+//! download everything again. The `cache` policy in agents.yaml points every
+//! agent's build tools at one directory: Cargo builds in a target directory per
+//! agent (so one agent's build never waits on another's lock) with sccache
+//! sharing the compiled crates, while the npm, pnpm, yarn and pip caches are
+//! shared outright.
+
+use crate::cli::CacheCommands;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::commands::selector;
+use crate::error::SpriteError;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Subdirectory holding the per-agent Cargo target directories
+const CARGO_TARGETS: &str = "cargo";
+
+/// Caches shared by all agents: label and subdirectory
+const SHARED_CACHES: &[(&str, &str)] = &[
+    ("sccache", "sccache"),
+    ("npm", "npm"),
+    ("pnpm store", "pnpm-store"),
+    ("yarn", "yarn"),
+    ("pip", "pip"),
+];
+
+/// Execute cache command with subcommands
+pub fn execute(command: CacheCommands) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    match command {
+        CacheCommands::Stats => show_stats(&config),
+        CacheCommands::Clean {
+            agents,
+            shared,
+            dry_run,
+        } => clean(&config, agents.as_deref(), shared, dry_run),
+    }
+}
+
+/// Directory holding the project's caches
+pub fn cache_dir(config: &SpriteConfig) -> Option<PathBuf> {
+    config
+        .project_root()
+        .map(|root| root.join(&config.cache.dir))
+}
+
+/// Variables pointing an agent's build tools at the shared caches
+pub fn cache_env(config: &SpriteConfig, agent: &AgentConfig) -> Vec<(String, String)> {
+    let dir = match cache_dir(config) {
+        Some(dir) => dir,
+        None => return Vec::new(),
+    };
+    let path = |sub: &str| dir.join(sub).to_string_lossy().to_string();
+    let policy = &config.cache;
+
+    let mut env = Vec::new();
+    if policy.cargo {
+        env.push((
+            "CARGO_TARGET_DIR".to_string(),
+            dir.join(CARGO_TARGETS)
+                .join(&agent.id)
+                .to_string_lossy()
+                .to_string(),
+        ));
+    }
+    // Pointing rustc at a wrapper that is not installed would break every build
+    if policy.sccache && is_installed("sccache") {
+        env.push(("RUSTC_WRAPPER".to_string(), "sccache".to_string()));
+        env.push(("SCCACHE_DIR".to_string(), path("sccache")));
+    }
+    if policy.node {
+        env.push(("npm_config_cache".to_string(), path("npm")));
+        env.push(("npm_config_store_dir".to_string(), path("pnpm-store")));
+        env.push(("YARN_CACHE_FOLDER".to_string(), path("yarn")));
+    }
+    if policy.pip {
+        env.push(("PIP_CACHE_DIR".to_string(), path("pip")));
+    }
+    env
+}
+
+/// Create the cache directory, ignored by git wherever it lives
+pub fn prepare_dir(config: &SpriteConfig) -> Result<()> {
+    let dir = cache_dir(config)
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| SpriteError::io(format!("create {}", dir.display()), e))?;
+    let ignore = dir.join(".gitignore");
+    if !ignore.exists() {
+        std::fs::write(&ignore, "# Build caches shared by sprite agents\n*\n")
+            .map_err(|e| SpriteError::io(format!("write {}", ignore.display()), e))?;
+    }
+    Ok(())
+}
+
+/// Report the disk use of each agent and of the shared caches
+fn show_stats(config: &SpriteConfig) -> Result<()> {
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let dir = root.join(&config.cache.dir);
+
+    println!("💾 Disk use of agents and caches ({})", config.cache.dir);
+    println!(
+        "  {:<10} {:>10} {:>10} {:>10} {:>10}",
+        "AGENT", "WORKTREE", "TARGET", "TOTAL", "LIMIT"
+    );
+    let mut total = 0;
+    for agent in &config.agents {
+        let worktree = dir_size(&root.join(&agent.worktree_path));
+        let target = dir_size(&dir.join(CARGO_TARGETS).join(&agent.id));
+        let limit = agent.config.resource_limits.max_disk_mb * 1024 * 1024;
+        let over = limit > 0 && worktree + target > limit;
+        println!(
+            "  {:<10} {:>10} {:>10} {:>10} {:>10}{}",
+            agent.id,
+            format_size(worktree),
+            format_size(target),
+            format_size(worktree + target),
+            format_size(limit),
+            if over { "  ⚠️  over limit" } else { "" }
+        );
+        total += worktree + target;
+    }
+
+    println!();
+    println!("  Shared:");
+    for (label, sub) in SHARED_CACHES {
+        let size = dir_size(&dir.join(sub));
+        if size > 0 {
+            println!("  {:<21} {:>10}", label, format_size(size));
+        }
+        total += size;
+    }
+
+    let orphans = orphaned_targets(config, &dir);
+    if !orphans.is_empty() {
+        let size: u64 = orphans.iter().map(|path| dir_size(path)).sum();
+        println!(
+            "  {:<21} {:>10}",
+            format!("{} removed agent(s)", orphans.len()),
+            format_size(size)
+        );
+        total += size;
+    }
+
+    println!();
+    println!("📊 Total: {}", format_size(total));
+    if !orphans.is_empty() {
+        println!("💡 'sprite cache clean' removes the target directories of removed agents.");
+    }
+    Ok(())
+}
+
+/// Remove the Cargo target directories of removed agents, or of the selected
+/// agents, plus the shared caches with `shared`.
+///
+/// Configured agents are only touched when selected: their target directory
+/// may be in use by a build running in their pane.
+fn clean(config: &SpriteConfig, agents: Option<&str>, shared: bool, dry_run: bool) -> Result<()> {
+    let dir = cache_dir(config)
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let agent_ids = match agents {
+        Some(expression) => selector::select(config, expression)?,
+        None => Vec::new(),
+    };
+
+    let mut targets: Vec<(String, PathBuf)> = agent_ids
+        .iter()
+        .map(|id| {
+            (
+                format!("agent {} target", id),
+                dir.join(CARGO_TARGETS).join(id),
+            )
+        })
+        .collect();
+    if agents.is_none() {
+        targets.extend(orphaned_targets(config, &dir).into_iter().map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            (format!("removed agent {} target", name), path)
+        }));
+    }
+    if shared {
+        targets.extend(
+            SHARED_CACHES
+                .iter()
+                .map(|(label, sub)| (label.to_string(), dir.join(sub))),
+        );
+    }
+
+    let mut freed = 0;
+    for (label, path) in targets.iter().filter(|(_, path)| path.exists()) {
+        let size = dir_size(path);
+        if !dry_run {
+            std::fs::remove_dir_all(path)
+                .map_err(|e| SpriteError::io(format!("remove {}", path.display()), e))?;
+        }
+        println!("  🗑️  {:<28} {:>10}", label, format_size(size));
+        freed += size;
+    }
+
+    if dry_run {
+        println!("🔍 Dry run: would free {}", format_size(freed));
+    } else {
+        println!("🧹 Freed {}", format_size(freed));
+    }
+    Ok(())
+}
+
+/// Target directories left behind by agents that are no longer configured
+fn orphaned_targets(config: &SpriteConfig, dir: &Path) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(dir.join(CARGO_TARGETS)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut orphans: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            config.get_agent(&name).is_none()
+        })
+        .map(|entry| entry.path())
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Bytes used by the files under `path`, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| dir_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Whether a program can be found on `PATH`
+pub(crate) fn is_installed(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::config::AgentConfigDetails;
+
+    #[test]
+    fn test_format_and_measure() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("a/b")).unwrap();
+        std::fs::write(temp_dir.path().join("a/one"), vec![0u8; 100]).unwrap();
+        std::fs::write(temp_dir.path().join("a/b/two"), vec![0u8; 28]).unwrap();
+        assert_eq!(dir_size(&temp_dir.path().join("a")), 128);
+        assert_eq!(dir_size(&temp_dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn test_cache_env_follows_policy() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("agents").join("agents.yaml");
+        let mut config = SpriteConfig::new();
+        config.agents.push(AgentConfig {
+            id: "2".to_string(),
+            branch: "agents/2".to_string(),
+            worktree_path: "agents/2".to_string(),
+            model: "claude-sonnet".to_string(),
+            description: "Agent 2".to_string(),
+            tags: Vec::new(),
+            status: "inactive".to_string(),
+            config: AgentConfigDetails::default(),
+        });
+        config.cache.sccache = false;
+        config.cache.pip = false;
+        config.save_to_path(&config_path).unwrap();
+        let config = SpriteConfig::load_from_path(&config_path).unwrap();
+
+        let env: std::collections::BTreeMap<String, String> =
+            cache_env(&config, &config.agents[0]).into_iter().collect();
+        let cache = temp_dir.path().join("agents").join("cache");
+        assert_eq!(
+            env["CARGO_TARGET_DIR"],
+            cache.join("cargo").join("2").to_string_lossy()
+        );
+        assert_eq!(
+            env["YARN_CACHE_FOLDER"],
+            cache.join("yarn").to_string_lossy()
+        );
+        assert!(!env.contains_key("RUSTC_WRAPPER"));
+        assert!(!env.contains_key("PIP_CACHE_DIR"));
+    }
+}
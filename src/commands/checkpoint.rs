@@ -0,0 +1,405 @@
+//! Checkpoint command - Snapshot agent worktrees into hidden refs and restore them
+//!
+//! A checkpoint records an agent's whole working tree, untracked files included, as a
+//! commit under `refs/sprite/checkpoints/<agent>/<id>` whose parent is the agent's HEAD.
+//! Taking one never touches the agent's index, HEAD or files, and restoring one first
+//! checkpoints the current state, so a restore can itself be undone.
+
+use crate::cli::CheckpointCommands;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::commands::selector;
+use crate::error::SpriteError;
+use crate::utils::git::{self, RefInfo};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Namespace of the checkpoint refs
+const CHECKPOINT_REFS: &str = "refs/sprite/checkpoints";
+
+/// Options for taking checkpoints
+pub struct CheckpointOptions<'a> {
+    /// Agent selector, all agents when unset
+    pub agents: Option<&'a str>,
+    pub message: Option<&'a str>,
+    /// Keep taking checkpoints every `interval_secs`
+    pub watch: bool,
+    pub interval_secs: u64,
+    /// Checkpoints to keep per agent; older ones are deleted
+    pub keep: usize,
+}
+
+/// Execute checkpoint command with subcommands
+pub fn execute(command: Option<CheckpointCommands>, options: CheckpointOptions) -> Result<()> {
+    match command {
+        None => take_checkpoints(&options),
+        Some(CheckpointCommands::List { agent }) => list_checkpoints(&agent),
+        Some(CheckpointCommands::Diff { agent, id, stat }) => {
+            diff_checkpoint(&agent, id.as_deref(), stat)
+        }
+        Some(CheckpointCommands::Restore { agent, id }) => restore_checkpoint(&agent, &id),
+    }
+}
+
+/// Checkpoint the selected agents once, or repeatedly in watch mode.
+fn take_checkpoints(options: &CheckpointOptions) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let agent_ids = selector::select(&config, options.agents.unwrap_or("all"))?;
+    let root = project_root(&config)?;
+
+    if !options.watch {
+        for agent_id in &agent_ids {
+            let agent = config.get_agent(agent_id).expect("selected agents exist");
+            checkpoint_agent(&root, agent, options.message, options.keep, false)?;
+        }
+        return Ok(());
+    }
+
+    println!(
+        "📸 Checkpointing {} agent(s) every {}s... (Ctrl+C to stop)",
+        agent_ids.len(),
+        options.interval_secs
+    );
+    loop {
+        for agent_id in &agent_ids {
+            let agent = config.get_agent(agent_id).expect("selected agents exist");
+            if let Err(e) = checkpoint_agent(&root, agent, options.message, options.keep, true) {
+                eprintln!("⚠️  Agent {}: checkpoint failed: {}", agent_id, e);
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_secs(options.interval_secs.max(1)));
+    }
+}
+
+/// Snapshot one agent's worktree, skipping it when nothing changed since the last checkpoint.
+///
+/// Returns the new checkpoint ID, if one was taken.
+pub fn checkpoint_agent(
+    root: &Path,
+    agent: &AgentConfig,
+    message: Option<&str>,
+    keep: usize,
+    quiet: bool,
+) -> Result<Option<String>> {
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        if !quiet {
+            println!(
+                "⏭️  Agent {}: no worktree at {}",
+                agent.id, agent.worktree_path
+            );
+        }
+        return Ok(None);
+    }
+
+    let tree = git::snapshot_worktree_at(&workspace)?;
+    let existing = list_agent_checkpoints(&workspace, &agent.id)?;
+    if let Some(last) = existing.last() {
+        if git::rev_parse_at(&workspace, &format!("{}^{{tree}}", last.commit))? == tree {
+            if !quiet {
+                println!(
+                    "⏸️  Agent {}: unchanged since checkpoint {}",
+                    agent.id,
+                    checkpoint_id(last)
+                );
+            }
+            return Ok(None);
+        }
+    }
+
+    let head = git::get_head_commit_at(&workspace).ok();
+    let subject = match message {
+        Some(message) => format!("sprite checkpoint of agent {}: {}", agent.id, message),
+        None => format!("sprite checkpoint of agent {}", agent.id),
+    };
+    let commit = git::commit_tree_at(&workspace, &tree, head.as_deref(), &subject)?;
+
+    let id = unique_id(&existing);
+    git::update_ref_at(&workspace, &checkpoint_ref(&agent.id, &id), &commit)?;
+
+    let changed = match &head {
+        Some(head) => git::diff_paths_at(&workspace, head, &commit, "ACDMRT")?.len(),
+        None => 0,
+    };
+    println!(
+        "📸 Agent {}: checkpoint {} ({} file(s) differ from HEAD)",
+        agent.id, id, changed
+    );
+
+    // Drop the oldest checkpoints beyond the retention limit
+    let mut all = list_agent_checkpoints(&workspace, &agent.id)?;
+    let excess = all.len().saturating_sub(keep.max(1));
+    for old in all.drain(..excess) {
+        git::delete_ref_at(&workspace, &old.name)?;
+    }
+
+    Ok(Some(id))
+}
+
+/// List an agent's checkpoints, newest last
+fn list_checkpoints(agent_id: &str) -> Result<()> {
+    let (_, agent, workspace) = load_agent(agent_id)?;
+    let checkpoints = list_agent_checkpoints(&workspace, &agent.id)?;
+
+    if checkpoints.is_empty() {
+        println!("📭 No checkpoints for agent {} yet.", agent.id);
+        println!(
+            "💡 Take one with 'sprite checkpoint --agents {}'.",
+            agent.id
+        );
+        return Ok(());
+    }
+
+    println!(
+        "📸 Checkpoints of agent {} ({}):",
+        agent.id,
+        checkpoints.len()
+    );
+    for checkpoint in &checkpoints {
+        let when = chrono::DateTime::from_timestamp(checkpoint.timestamp, 0)
+            .map(|at| {
+                at.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let note = checkpoint
+            .subject
+            .split_once(": ")
+            .map(|(_, note)| format!(" - {}", note))
+            .unwrap_or_default();
+        println!(
+            "  • {}  {}  {}{}",
+            checkpoint_id(checkpoint),
+            &checkpoint.commit[..checkpoint.commit.len().min(10)],
+            when,
+            note
+        );
+    }
+    Ok(())
+}
+
+/// Show how the worktree differs from a checkpoint (the latest by default)
+fn diff_checkpoint(agent_id: &str, id: Option<&str>, stat: bool) -> Result<()> {
+    let (_, agent, workspace) = load_agent(agent_id)?;
+    let checkpoints = list_agent_checkpoints(&workspace, &agent.id)?;
+    let checkpoint = find_checkpoint(&checkpoints, &agent.id, id)?;
+
+    let current = git::snapshot_worktree_at(&workspace)?;
+    let diff = git::diff_objects_at(&workspace, &checkpoint.commit, &current, stat)?;
+    if diff.is_empty() {
+        println!(
+            "✅ Agent {}: worktree matches checkpoint {}",
+            agent.id,
+            checkpoint_id(checkpoint)
+        );
+    } else {
+        println!(
+            "🔍 Agent {}: changes since checkpoint {}",
+            agent.id,
+            checkpoint_id(checkpoint)
+        );
+        println!("{}", diff);
+    }
+    Ok(())
+}
+
+/// Put an agent's files back to a checkpoint, keeping the current state as a new checkpoint
+fn restore_checkpoint(agent_id: &str, id: &str) -> Result<()> {
+    let (root, agent, workspace) = load_agent(agent_id)?;
+    let checkpoints = list_agent_checkpoints(&workspace, &agent.id)?;
+    let checkpoint = find_checkpoint(&checkpoints, &agent.id, Some(id))?.clone();
+
+    if git::operation_in_progress_at(&workspace)? {
+        return Err(SpriteError::git(format!(
+            "Agent {} has a merge, rebase or cherry-pick in progress; finish or abort it first",
+            agent.id
+        ))
+        .into());
+    }
+
+    // Save what is there now so the restore can be undone; keep every checkpoint
+    let backup = checkpoint_agent(
+        &root,
+        &agent,
+        Some(&format!("before restoring {}", checkpoint_id(&checkpoint))),
+        usize::MAX,
+        true,
+    )?;
+
+    let current = git::snapshot_worktree_at(&workspace)?;
+    git::restore_worktree_at(&workspace, &checkpoint.commit)?;
+
+    // Files created after the checkpoint are not in it, so restore leaves them behind
+    let mut removed = 0;
+    for path in git::diff_paths_at(&workspace, &checkpoint.commit, &current, "A")? {
+        let file = workspace.join(&path);
+        if file.exists() {
+            std::fs::remove_file(&file)
+                .with_context(|| format!("Failed to remove {}", file.display()))?;
+            removed += 1;
+        }
+    }
+
+    println!(
+        "⏪ Agent {}: restored checkpoint {} ({} file(s) removed)",
+        agent.id,
+        checkpoint_id(&checkpoint),
+        removed
+    );
+    if let Some(backup) = backup {
+        println!(
+            "💡 The previous state is checkpoint {}: 'sprite checkpoint restore {} {}' undoes this.",
+            backup, agent.id, backup
+        );
+    }
+    println!("   HEAD and the index were not changed; review with 'git status' in the worktree.");
+    Ok(())
+}
+
+fn load_agent(agent_id: &str) -> Result<(PathBuf, AgentConfig, PathBuf)> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let agent = config
+        .get_agent(agent_id)
+        .ok_or_else(|| SpriteError::agent_not_found(agent_id))?
+        .clone();
+    let root = project_root(&config)?;
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        return Err(SpriteError::agent(
+            format!(
+                "Agent {} has no worktree at {}",
+                agent.id, agent.worktree_path
+            ),
+            Some(&agent.id),
+        )
+        .into());
+    }
+    Ok((root, agent, workspace))
+}
+
+fn project_root(config: &SpriteConfig) -> Result<PathBuf> {
+    config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root").into())
+}
+
+fn checkpoint_ref(agent_id: &str, id: &str) -> String {
+    format!("{}/{}/{}", CHECKPOINT_REFS, agent_id, id)
+}
+
+fn checkpoint_id(checkpoint: &RefInfo) -> &str {
+    checkpoint
+        .name
+        .rsplit('/')
+        .next()
+        .unwrap_or(&checkpoint.name)
+}
+
+fn list_agent_checkpoints(workspace: &Path, agent_id: &str) -> Result<Vec<RefInfo>> {
+    git::list_refs_at(workspace, &format!("{}/{}/", CHECKPOINT_REFS, agent_id))
+}
+
+/// A new checkpoint ID from the current time, suffixed if one was already taken this second.
+fn unique_id(existing: &[RefInfo]) -> String {
+    let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let taken = |id: &str| existing.iter().any(|c| checkpoint_id(c) == id);
+    let mut id = base.clone();
+    let mut n = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// Find a checkpoint by ID or unique ID prefix; the latest when `id` is unset.
+fn find_checkpoint<'a>(
+    checkpoints: &'a [RefInfo],
+    agent_id: &str,
+    id: Option<&str>,
+) -> Result<&'a RefInfo> {
+    let id = match id {
+        Some(id) => id,
+        None => {
+            return checkpoints.last().ok_or_else(|| {
+                SpriteError::validation(
+                    format!("Agent {} has no checkpoints", agent_id),
+                    Some("id"),
+                    None::<String>,
+                )
+                .into()
+            })
+        }
+    };
+
+    if let Some(exact) = checkpoints.iter().find(|c| checkpoint_id(c) == id) {
+        return Ok(exact);
+    }
+    let matches: Vec<&RefInfo> = checkpoints
+        .iter()
+        .filter(|c| checkpoint_id(c).starts_with(id))
+        .collect();
+    match matches.as_slice() {
+        [one] => Ok(one),
+        [] => Err(SpriteError::validation(
+            format!(
+                "Agent {} has no checkpoint '{}'; see 'sprite checkpoint list {}'",
+                agent_id, id, agent_id
+            ),
+            Some("id"),
+            Some(id),
+        )
+        .into()),
+        _ => Err(SpriteError::validation(
+            format!(
+                "Checkpoint '{}' is ambiguous for agent {} ({} matches)",
+                id,
+                agent_id,
+                matches.len()
+            ),
+            Some("id"),
+            Some(id),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(id: &str) -> RefInfo {
+        RefInfo {
+            name: checkpoint_ref("2", id),
+            commit: "abc".to_string(),
+            timestamp: 0,
+            subject: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_checkpoint() {
+        let checkpoints = vec![
+            checkpoint("20261017-230000"),
+            checkpoint("20261018-080000"),
+            checkpoint("20261018-080000-2"),
+        ];
+
+        let id = |found: Result<&RefInfo>| checkpoint_id(found.unwrap()).to_string();
+        assert_eq!(
+            id(find_checkpoint(&checkpoints, "2", None)),
+            "20261018-080000-2"
+        );
+        assert_eq!(
+            id(find_checkpoint(&checkpoints, "2", Some("20261018-080000"))),
+            "20261018-080000"
+        );
+        assert_eq!(
+            id(find_checkpoint(&checkpoints, "2", Some("20261017"))),
+            "20261017-230000"
+        );
+        assert!(find_checkpoint(&checkpoints, "2", Some("202610")).is_err());
+        assert!(find_checkpoint(&checkpoints, "2", Some("2025")).is_err());
+        assert!(find_checkpoint(&[], "2", None).is_err());
+    }
+}
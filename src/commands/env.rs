@@ -0,0 +1,257 @@
+//! Env command - The environment each agent runs with
+//!
+//! An agent's environment is `settings.global_env_vars`, overlaid with its own
+//! `env_vars`, plus the variables sprite provides (`SPRITE_AGENT_ID`,
+//! `SPRITE_BRANCH`, `SPRITE_SESSION`, `SPRITE_WORK_DIR` and the agent's ports,
+//! `SPRITE_PORT_BASE` and `PORT_<NAME>`), and the build cache variables of the
+//! `cache` policy. Sprite exports it into
+//! the agent's pane and writes it to a `.envrc` in the agent's worktree, so a
+//! shell opened there by hand (with direnv) sees the same variables.
+//!
+//! Values holding a `!secret` reference (see [`crate::utils::secrets`]) are
+//! exported as the shell lookup of the secret, never as the secret itself.
+
+use crate::cli::EnvFormat;
+use crate::commands::cache;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::ports::{self, PortLock};
+use crate::utils::{git, secrets};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// First line of every `.envrc` sprite writes; files without it are never overwritten
+const ENVRC_MARKER: &str =
+    "# Generated by sprite from agents/agents.yaml - edits here are overwritten.";
+
+/// Execute the env command: print an agent's effective environment
+pub fn execute(agent_id: &str, format: EnvFormat) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let agent = config
+        .get_agent(agent_id)
+        .ok_or_else(|| SpriteError::agent_not_found(agent_id))?;
+    prepare(&config);
+    let env = agent_env(&config, agent, &PortLock::load().unwrap_or_default());
+
+    match format {
+        EnvFormat::Shell => {
+            for (key, value) in &env {
+                println!("export {}={}", key, secrets::shell_value(value));
+            }
+        }
+        EnvFormat::Json => {
+            // JSON has no way to defer a lookup, so secrets are read now
+            let mut resolved = BTreeMap::new();
+            for (key, value) in env {
+                let value = secrets::resolve_value(&value)
+                    .with_context(|| format!("Failed to read the secret for {}", key))?;
+                resolved.insert(key, value);
+            }
+            let json = serde_json::to_string_pretty(&resolved)
+                .context("Failed to serialize the environment")?;
+            println!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+/// Environment an agent runs with: global variables, the agent's own, the cache
+/// policy's, then sprite's.
+///
+/// Port variables only appear once the agent has a range in `lock`; see [`prepare`].
+pub fn agent_env(
+    config: &SpriteConfig,
+    agent: &AgentConfig,
+    lock: &PortLock,
+) -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = config
+        .settings
+        .global_env_vars
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    env.extend(
+        agent
+            .config
+            .env_vars
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+    env.retain(|key, _| is_valid_name(key));
+    // The cache policy's variables give way to ones set explicitly
+    for (key, value) in cache::cache_env(config, agent) {
+        env.entry(key).or_insert(value);
+    }
+
+    let root = config.project_root().unwrap_or_default();
+    let workspace = root.join(&agent.worktree_path);
+    let work_dir = match &agent.config.work_dir {
+        Some(dir) => workspace.join(dir),
+        None => workspace,
+    };
+    env.insert("SPRITE_AGENT_ID".to_string(), agent.id.clone());
+    env.insert("SPRITE_BRANCH".to_string(), agent.branch.clone());
+    env.insert(
+        "SPRITE_SESSION".to_string(),
+        config.effective_session_name(),
+    );
+    env.insert(
+        "SPRITE_WORK_DIR".to_string(),
+        work_dir.to_string_lossy().to_string(),
+    );
+    for (key, port) in ports::agent_ports(config, lock, &agent.id) {
+        env.insert(key, port.to_string());
+    }
+    env
+}
+
+/// Regenerate the `.envrc` of every agent that has a worktree, warning about failures
+pub fn refresh_envrcs(config: &SpriteConfig) {
+    prepare(config);
+    let lock = PortLock::load().unwrap_or_default();
+    let mut written = 0;
+    for agent in &config.agents {
+        match write_envrc(config, agent, &lock) {
+            Ok(true) => written += 1,
+            Ok(false) => {}
+            Err(e) => eprintln!("⚠️  Agent {}: .envrc not updated: {}", agent.id, e),
+        }
+    }
+    if written > 0 {
+        println!(
+            "💡 Updated .envrc for {} agent(s); with direnv, review it and run 'direnv allow' in the worktree to load it",
+            written
+        );
+    }
+}
+
+/// Regenerate the `.envrc` files from the effective configuration after a
+/// command changed it
+pub fn refresh_after_edit() {
+    match SpriteConfig::load() {
+        Ok(config) => refresh_envrcs(&config),
+        Err(e) => eprintln!("⚠️  .envrc files not updated: {}", e),
+    }
+}
+
+/// Give agents without a port range one and create the shared cache directory,
+/// warning about failures
+pub fn prepare(config: &SpriteConfig) {
+    if let Err(e) = ports::allocate_project(config) {
+        eprintln!("⚠️  Ports not allocated: {}", e);
+    }
+    if let Err(e) = cache::prepare_dir(config) {
+        eprintln!("⚠️  Cache directory not created: {}", e);
+    }
+}
+
+/// Write an agent's environment to `.envrc` in its worktree.
+///
+/// Returns whether the file changed. A `.envrc` sprite did not write is left alone.
+pub fn write_envrc(config: &SpriteConfig, agent: &AgentConfig, lock: &PortLock) -> Result<bool> {
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        return Ok(false);
+    }
+
+    let path = workspace.join(".envrc");
+    let content = render_envrc(&agent_env(config, agent, lock));
+    match std::fs::read_to_string(&path) {
+        Ok(existing) if existing == content => return Ok(false),
+        Ok(existing) if !existing.starts_with(ENVRC_MARKER) => {
+            return Err(SpriteError::agent(
+                format!(
+                    "{} was not written by sprite; move it to .envrc.local to keep it",
+                    path.display()
+                ),
+                Some(&agent.id),
+            )
+            .into());
+        }
+        _ => {}
+    }
+
+    // Generated per machine, so keep it out of commits, checkpoints and patches
+    exclude_envrc(&workspace)?;
+    std::fs::write(&path, content)
+        .map_err(|e| SpriteError::io(format!("write {}", path.display()), e))?;
+
+    // Not `direnv allow`ed here: the file sources the agent-writable
+    // .envrc.local, so trusting it is the user's call
+    Ok(true)
+}
+
+/// Shell script exporting `env`, in the form direnv loads
+fn render_envrc(env: &BTreeMap<String, String>) -> String {
+    let mut content = format!(
+        "{}\n# Change agent environments with 'sprite config' and put local additions in .envrc.local.\n\n",
+        ENVRC_MARKER
+    );
+    for (key, value) in env {
+        content.push_str(&format!("export {}={}\n", key, secrets::shell_value(value)));
+    }
+    content.push_str("\nsource_env_if_exists .envrc.local\n");
+    content
+}
+
+/// Add `/.envrc` to the repository's `info/exclude`, shared by every worktree
+fn exclude_envrc(workspace: &Path) -> Result<()> {
+    let info = git::common_dir_at(workspace)?.join("info");
+    let exclude = info.join("exclude");
+    let existing = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if existing.lines().any(|line| line.trim() == "/.envrc") {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(&info)
+        .map_err(|e| SpriteError::io(format!("create {}", info.display()), e))?;
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str("# Agent environments generated by sprite\n/.envrc\n");
+    std::fs::write(&exclude, content)
+        .map_err(|e| SpriteError::io(format!("write {}", exclude.display()), e))?;
+    Ok(())
+}
+
+/// Whether a shell could export a variable under this name
+fn is_valid_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_envrc() {
+        let env: BTreeMap<String, String> = [
+            ("SPRITE_AGENT_ID", "1"),
+            ("GREETING", "it's a test"),
+            ("RUST_LOG", "debug"),
+            ("OPENAI_API_KEY", "!secret env:OPENAI_KEY"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let content = render_envrc(&env);
+        assert!(content.starts_with(ENVRC_MARKER));
+        assert!(content.contains("export GREETING='it'\\''s a test'\n"));
+        assert!(content.contains("export OPENAI_API_KEY=\"${OPENAI_KEY}\"\n"));
+        assert!(content.contains("export RUST_LOG=debug\nexport SPRITE_AGENT_ID=1\n"));
+        assert!(content.ends_with("source_env_if_exists .envrc.local\n"));
+
+        assert!(is_valid_name("_PATH2"));
+        assert!(!is_valid_name("2FAST"));
+        assert!(!is_valid_name("MY-VAR"));
+        assert!(!is_valid_name(""));
+    }
+}
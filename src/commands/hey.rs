@@ -1,21 +1,30 @@
 //! Hey command - Send command to specific agent(s)
 
+use crate::commands::apply;
 use crate::commands::config::SpriteConfig;
+use crate::commands::prompts::{self, PromptTemplate};
+use crate::commands::selector;
 use crate::error::SpriteError;
 use crate::utils::{accessibility::AccessibilityConfig, tmux};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Execute the hey command with the given parameters.
 ///
-/// Target formats:
-/// - "all" = broadcast to all active agents
-/// - "1,2,3" = send to specific agents
-/// - "1" = send to single agent
+/// Targets are agent selectors, e.g. "all", "1,2,3", "1-4", "@frontend+idle"
+/// or "all,!3"; only active agents receive the command. The text comes from
+/// `command` and `args`, or from `file` ("-" reads stdin, as does a command of "-"),
+/// and is pasted into each pane verbatim before pressing Enter. With `template`, a
+/// prompt from agents/prompts is rendered for each agent using `vars` instead.
+#[allow(clippy::too_many_arguments)]
 pub fn execute(
     agents: &str,
-    command: &str,
+    command: Option<&str>,
     args: &[String],
+    file: Option<&str>,
+    template: Option<&str>,
+    vars: &[String],
     _timeout: u64,
     work_dir: Option<&str>,
     env_vars: &[String],
@@ -29,39 +38,45 @@ pub fn execute(
     let target_agents = parse_agent_targets(agents)?;
 
     if target_agents.is_empty() {
-        println!("ℹ️  No active agents found. Command cancelled.");
+        println!(
+            "ℹ️  No active agents match '{}'. Command cancelled.",
+            agents
+        );
         return Ok(());
     }
 
-    // Validate all target agents exist and are active
-    for agent_id in &target_agents {
-        let agent_config = config
-            .get_agent(agent_id)
-            .ok_or_else(|| SpriteError::agent_not_found(agent_id.to_string()))?;
-
-        if agent_config.status.to_lowercase() != "active" {
-            return Err(SpriteError::agent_not_active(agent_id.to_string()).into());
-        }
+    // Find the session that belongs to this project
+    let session_name = config.effective_session_name();
+    if !tmux::session_exists(&session_name)? {
+        return Err(SpriteError::session_not_found(format!(
+            "No sprite session '{}' is running for this project. Use 'sprite start' first.",
+            session_name
+        ))
+        .into());
     }
 
-    // Find the active session
-    let sessions = tmux::list_sessions().context("Failed to list tmux sessions")?;
-
-    let active_session = sessions
-        .iter()
-        .find(|s| s.name.starts_with("sprite-") && s.attached)
-        .or_else(|| sessions.iter().find(|s| s.name.starts_with("sprite-")))
-        .ok_or_else(|| SpriteError::session_not_found("No active sprite session found"))?;
-
     // Get session panes to find the agent panes
-    let panes = tmux::get_session_panes(&active_session.name)
-        .with_context(|| format!("Failed to get panes for session '{}'", active_session.name))?;
-
-    // Prepare the command with arguments
-    let full_command = if args.is_empty() {
-        command.to_string()
-    } else {
-        format!("{} {}", command, args.join(" "))
+    let panes = tmux::list_agent_panes(&session_name)
+        .with_context(|| format!("Failed to get panes for session '{}'", session_name))?;
+    let root = config.project_root().unwrap_or_default();
+
+    // Prepare the text to deliver, rendering templates for every agent up front so a
+    // missing variable stops the command before anything is sent
+    let mut payloads = HashMap::new();
+    let full_command = match template {
+        Some(name) => {
+            let template = PromptTemplate::load(&config, name)?;
+            let vars = prompts::parse_vars(vars)?;
+            for agent_id in &target_agents {
+                let agent_config = config
+                    .get_agent(agent_id)
+                    .expect("Agent config should exist after validation");
+                let text = template.render_for_agent(&config, agent_config, &vars)?;
+                payloads.insert(agent_id.clone(), text);
+            }
+            format!("template '{}'", name)
+        }
+        None => read_payload(command, args, file)?,
     };
 
     // Set up environment variables if provided
@@ -74,83 +89,34 @@ pub fn execute(
             .expect("Agent config should exist after validation");
 
         // Find the pane for this agent
-        let agent_pane = find_agent_pane(&panes, agent_config, &active_session.name)?;
+        let agent_pane = find_agent_pane(&panes, agent_config, &root)?;
 
         // Change to working directory if specified
         if let Some(work_dir) = work_dir {
-            let work_command = format!("cd {}", work_dir);
-            std::process::Command::new("tmux")
-                .args([
-                    "send-keys",
-                    "-t",
-                    &format!("{}.{}", active_session.name, agent_pane),
-                    &work_command,
-                ])
-                .output()
-                .with_context(|| {
-                    format!(
-                        "Failed to change to working directory '{}' for agent '{}'",
-                        work_dir, agent_id
-                    )
-                })?;
-
-            // Small delay then send Enter to execute
-            std::thread::sleep(std::time::Duration::from_millis(100));
-
-            std::process::Command::new("tmux")
-                .args([
-                    "send-keys",
-                    "-t",
-                    &format!("{}.{}", active_session.name, agent_pane),
-                    "C-m",
-                ])
-                .output()
-                .with_context(|| format!("Failed to send Enter key to agent '{}'", agent_id))?;
+            let work_command = format!("cd {}", tmux::shell_quote(work_dir));
+            tmux::paste_text(&agent_pane, &work_command, true).with_context(|| {
+                format!(
+                    "Failed to change to working directory '{}' for agent '{}'",
+                    work_dir, agent_id
+                )
+            })?;
         }
 
         // Set environment variables
         for (key, value) in &env_map {
-            let env_command = format!("export {}='{}'", key, value.replace('\'', "'\"'\"'"));
-            std::process::Command::new("tmux")
-                .args([
-                    "send-keys",
-                    "-t",
-                    &format!("{}.{}", active_session.name, agent_pane),
-                    &env_command,
-                ])
-                .output()
-                .with_context(|| {
-                    format!(
-                        "Failed to set environment variable '{}' for agent '{}'",
-                        key, agent_id
-                    )
-                })?;
-            std::thread::sleep(std::time::Duration::from_millis(50));
+            let env_command = format!("export {}={}", key, tmux::shell_quote(value));
+            tmux::paste_text(&agent_pane, &env_command, true).with_context(|| {
+                format!(
+                    "Failed to set environment variable '{}' for agent '{}'",
+                    key, agent_id
+                )
+            })?;
         }
 
-        // Send the command to the agent pane using two-step approach
-        std::process::Command::new("tmux")
-            .args([
-                "send-keys",
-                "-t",
-                &format!("{}.{}", active_session.name, agent_pane),
-                &full_command,
-            ])
-            .output()
-            .with_context(|| format!("Failed to send command text to agent '{}'", agent_id))?;
-
-        // Small delay then send Enter to execute
-        std::thread::sleep(std::time::Duration::from_millis(100));
-
-        std::process::Command::new("tmux")
-            .args([
-                "send-keys",
-                "-t",
-                &format!("{}.{}", active_session.name, agent_pane),
-                "C-m",
-            ])
-            .output()
-            .with_context(|| format!("Failed to send Enter key to agent '{}'", agent_id))?;
+        // Paste the text in one piece so newlines and key names are not interpreted
+        let text = payloads.get(agent_id).unwrap_or(&full_command);
+        tmux::paste_text(&agent_pane, text, true)
+            .with_context(|| format!("Failed to send command to agent '{}'", agent_id))?;
     }
 
     let accessibility_config = AccessibilityConfig::default();
@@ -158,7 +124,7 @@ pub fn execute(
         &format!(
             "Command sent to agents {}: {}",
             target_agents.join(", "),
-            full_command
+            describe_payload(&full_command)
         ),
         &accessibility_config,
     );
@@ -169,101 +135,94 @@ pub fn execute(
             "Attaching to session for interactive mode...",
             &accessibility_config,
         );
-        tmux::attach_session(&active_session.name)?;
+        tmux::attach_session(&session_name)?;
     }
 
     Ok(())
 }
 
-/// Parse agent targets from string
-/// "all" -> returns all active agent IDs
-/// "1,2,3" -> returns ["1", "2", "3"]
-/// "1" -> returns ["1"]
+/// Resolve an agent selector (see [`crate::commands::selector`]) to the active agents it names.
+///
+/// Inactive agents picked up by the selector are skipped with a note.
 fn parse_agent_targets(agent_target: &str) -> Result<Vec<String>> {
     let config =
         SpriteConfig::load().map_err(|e| anyhow::anyhow!("Failed to load configuration: {}", e))?;
 
-    let active_agents: Vec<String> = config
-        .agents
-        .iter()
-        .filter(|agent| agent.status.to_lowercase() == "active")
-        .map(|agent| agent.id.clone())
-        .collect();
-
-    if agent_target.trim().to_lowercase() == "all" {
-        return Ok(active_agents);
-    }
-
-    // Parse comma-separated agent IDs
-    let agent_ids: Vec<String> = agent_target
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect();
-
-    // Validate each agent ID
-    for agent_id in &agent_ids {
-        if !active_agents.contains(agent_id) {
-            return Err(SpriteError::agent_not_found(format!(
-                "Agent '{}' not found or not active",
-                agent_id
-            ))
-            .into());
+    let mut targets = Vec::new();
+    for agent_id in selector::select(&config, agent_target)? {
+        match config.get_agent(&agent_id) {
+            Some(agent) if agent.status.to_lowercase() == "active" => targets.push(agent_id),
+            _ => println!("⏭️  Skipping inactive agent {}", agent_id),
         }
     }
 
-    Ok(agent_ids)
+    Ok(targets)
 }
 
-/// Find the pane that corresponds to the given agent.
+/// Find the pane that corresponds to the given agent, in any window of the session.
 fn find_agent_pane(
-    panes: &[tmux::PaneInfo],
+    panes: &[tmux::AgentPane],
     agent_config: &crate::commands::config::AgentConfig,
-    _session_name: &str,
+    root: &Path,
 ) -> Result<String> {
-    // Try to find the pane by matching the workspace path
-    for pane in panes {
-        if let Some(current_path) = &pane.current_path {
-            if current_path.contains(&agent_config.worktree_path)
-                || current_path.ends_with(&agent_config.id)
-            {
-                return Ok(pane.pane_id.clone());
-            }
-        }
-    }
+    apply::find_agent_pane(panes, agent_config, root)
+        .map(|pane| pane.pane_id.clone())
+        .ok_or_else(|| {
+            SpriteError::agent(
+                format!("Agent '{}' not found in any tmux pane", agent_config.id),
+                Some(&agent_config.id),
+            )
+            .into()
+        })
+}
 
-    // Fallback: try to find by agent ID in pane title or current command
-    for pane in panes {
-        if let Some(current_cmd) = &pane.current_command {
-            if current_cmd.contains(&agent_config.id) {
-                return Ok(pane.pane_id.clone());
-            }
+/// Build the text to deliver from the command line, a file, or stdin ("-").
+fn read_payload(command: Option<&str>, args: &[String], file: Option<&str>) -> Result<String> {
+    let source = match (file, command) {
+        (Some(path), _) => path,
+        (None, Some("-")) => "-",
+        (None, Some(command)) if args.is_empty() => return Ok(command.to_string()),
+        (None, Some(command)) => return Ok(format!("{} {}", command, args.join(" "))),
+        (None, None) => {
+            return Err(SpriteError::validation(
+                "Nothing to send: give a command, --file <path> or '-' for stdin",
+                Some("command".to_string()),
+                None::<String>,
+            )
+            .into())
         }
-    }
+    };
 
-    // Fallback: try to find by agent description
-    for pane in panes {
-        let agent_desc = &agent_config.description;
-        if let Some(current_cmd) = &pane.current_command {
-            if current_cmd.contains(agent_desc)
-                || current_cmd.contains(&format!("agent-{}", agent_config.id))
-            {
-                return Ok(pane.pane_id.clone());
-            }
-        }
-    }
+    let text = if source == "-" {
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)
+            .context("Failed to read command from stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read command file '{}'", source))?
+    };
 
-    // As a last resort, just return the first available pane if matches agent count
-    if panes.len() >= agent_config.id.parse::<usize>().unwrap_or(0) {
-        return Ok(panes[agent_config.id.parse::<usize>().unwrap_or(0)]
-            .pane_id
-            .clone());
+    if text.trim().is_empty() {
+        return Err(SpriteError::validation(
+            format!("No text to send from '{}'", source),
+            Some("file".to_string()),
+            Some(source.to_string()),
+        )
+        .into());
     }
 
-    Err(SpriteError::agent(
-        format!("Agent '{}' not found in any tmux pane", agent_config.id),
-        Some(agent_config.id.clone()),
-    )
-    .into())
+    Ok(text)
+}
+
+/// Short form of the delivered text for the confirmation message.
+fn describe_payload(text: &str) -> String {
+    let text = text.trim_end();
+    let lines = text.lines().count();
+    match text.lines().next() {
+        Some(first) if lines > 1 => format!("{} … ({} lines)", first, lines),
+        _ => text.to_string(),
+    }
 }
 
 /// Parse environment variables in KEY=VALUE format.
@@ -342,6 +301,24 @@ mod tests {
         assert_eq!(env_map.get("NODE_ENV"), Some(&"production".to_string()));
     }
 
+    #[test]
+    fn test_read_payload() {
+        let args = vec!["--watch".to_string()];
+        assert_eq!(
+            read_payload(Some("cargo test"), &args, None).unwrap(),
+            "cargo test --watch"
+        );
+        assert!(read_payload(None, &[], None).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.md");
+        std::fs::write(&path, "Fix the bug; then\nsay Enter C-c\n").unwrap();
+        let text = read_payload(None, &[], Some(path.to_str().unwrap())).unwrap();
+        assert_eq!(text, "Fix the bug; then\nsay Enter C-c\n");
+        assert_eq!(describe_payload(&text), "Fix the bug; then … (2 lines)");
+        assert_eq!(describe_payload("ls -la"), "ls -la");
+    }
+
     #[test]
     fn test_find_agent_pane() {
         // This test would require mocking tmux panes, which is complex
@@ -0,0 +1,502 @@
+//! Hooks command - Managed git hooks and identities for agent worktrees
+//!
+//! Each agent worktree commits as its own `user.name`/`user.email`, and its
+//! commit-msg hook adds a `Sprite-Agent: <id>` trailer, so `sprite blame-agents`
+//! can tell which agent wrote what after the branches are merged.
+//!
+//! Every agent worktree points its own `core.hooksPath` (a per-worktree setting) at
+//! `<git dir>/sprite/hooks/<agent>`. The scripts there only call `sprite hooks run`,
+//! so edits to the `hooks:` section of `agents.yaml` apply to the next commit without
+//! reinstalling. Hooks the repository already had still run once sprite's checks pass.
+
+use crate::cli::HooksCommands;
+use crate::commands::config::{AgentConfig, HooksConfig, SpriteConfig};
+use crate::commands::selector;
+use crate::error::SpriteError;
+use crate::utils::{git, tmux};
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+/// Hooks sprite installs in every agent worktree
+const MANAGED_HOOKS: [&str; 3] = ["pre-commit", "commit-msg", "pre-push"];
+
+/// Commit trailer naming the agent that made a commit
+pub const AGENT_TRAILER: &str = "Sprite-Agent";
+
+const AGENT_EMAIL_PREFIX: &str = "sprite-agent-";
+const AGENT_EMAIL_SUFFIX: &str = "@localhost";
+
+/// Execute hooks command with subcommands
+pub fn execute(command: HooksCommands) -> Result<()> {
+    match command {
+        HooksCommands::Install { agents } => install_command(agents.as_deref()),
+        HooksCommands::Run {
+            hook,
+            agent,
+            root,
+            args,
+        } => run_hook(&hook, &agent, &root, &args),
+    }
+}
+
+/// Refresh the git identity of the selected agents and install (or, when
+/// `hooks.enabled` is off, remove) their hooks
+fn install_command(agents: Option<&str>) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let agent_ids = selector::select(&config, agents.unwrap_or("all"))?;
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+
+    for agent_id in &agent_ids {
+        let agent = config.get_agent(agent_id).expect("selected agents exist");
+        let workspace = root.join(&agent.worktree_path);
+        if !workspace.exists() {
+            println!(
+                "⏭️  Agent {}: no worktree at {}",
+                agent.id, agent.worktree_path
+            );
+            continue;
+        }
+
+        set_agent_identity(&workspace, agent)?;
+        let (name, email) = agent_identity(agent);
+        println!("🪪 Agent {}: commits as {} <{}>", agent.id, name, email);
+        if config.hooks.enabled {
+            let dir = install_hooks(&root, &agent.id, &workspace)?;
+            println!(
+                "🪝 Agent {}: installed {} in {}",
+                agent.id,
+                MANAGED_HOOKS.join(", "),
+                dir.display()
+            );
+        } else {
+            git::set_worktree_config_at(&workspace, "core.hooksPath", None)?;
+            println!("🧹 Agent {}: managed hooks removed", agent.id);
+        }
+    }
+
+    if !config.hooks.enabled {
+        println!("💡 Managed hooks are off; set hooks.enabled: true in agents/agents.yaml to install them.");
+    }
+    Ok(())
+}
+
+/// Give a newly provisioned agent worktree its git identity, its `.envrc` and, if
+/// enabled, the managed hooks.
+///
+/// Failures are reported without stopping provisioning.
+pub fn setup_worktree(config: &SpriteConfig, agent_id: &str) {
+    let (root, agent) = match (config.project_root(), config.get_agent(agent_id)) {
+        (Some(root), Some(agent)) => (root, agent),
+        _ => return,
+    };
+    let workspace = root.join(&agent.worktree_path);
+
+    if let Err(e) = set_agent_identity(&workspace, agent) {
+        eprintln!("⚠️  Agent {}: git identity not set: {}", agent.id, e);
+    }
+    crate::commands::env::prepare(config);
+    let lock = crate::utils::ports::PortLock::load().unwrap_or_default();
+    match crate::commands::env::write_envrc(config, agent, &lock) {
+        Ok(true) => println!(
+            "💡 Agent {}: wrote .envrc; with direnv, review it and run 'direnv allow' there to load it",
+            agent.id
+        ),
+        Ok(false) => {}
+        Err(e) => eprintln!("⚠️  Agent {}: .envrc not written: {}", agent.id, e),
+    }
+    if config.hooks.enabled {
+        if let Err(e) = install_hooks(&root, &agent.id, &workspace) {
+            eprintln!("⚠️  Agent {}: git hooks not installed: {}", agent.id, e);
+        }
+    }
+}
+
+/// Name and email an agent's commits are recorded under
+pub fn agent_identity(agent: &AgentConfig) -> (String, String) {
+    let local: String = agent
+        .id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "._-".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    (
+        format!("Agent {} ({})", agent.id, agent.model),
+        format!("{}{}{}", AGENT_EMAIL_PREFIX, local, AGENT_EMAIL_SUFFIX),
+    )
+}
+
+/// The agent ID encoded in an email from [`agent_identity`]
+pub fn agent_from_email(email: &str) -> Option<String> {
+    email
+        .trim_matches(|c| c == '<' || c == '>')
+        .strip_prefix(AGENT_EMAIL_PREFIX)?
+        .strip_suffix(AGENT_EMAIL_SUFFIX)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+}
+
+/// Make the worktree commit as the agent, without changing any other worktree
+pub fn set_agent_identity(workspace: &Path, agent: &AgentConfig) -> Result<()> {
+    let (name, email) = agent_identity(agent);
+    git::set_worktree_config_at(workspace, "user.name", Some(&name))?;
+    git::set_worktree_config_at(workspace, "user.email", Some(&email))
+}
+
+/// Write the hook scripts for one agent and point its worktree at them.
+pub fn install_hooks(root: &Path, agent_id: &str, workspace: &Path) -> Result<PathBuf> {
+    let dir = git::common_dir_at(workspace)?
+        .join("sprite")
+        .join("hooks")
+        .join(agent_id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| SpriteError::io(format!("create {}", dir.display()), e))?;
+
+    let exe = std::env::current_exe().context("Failed to locate the sprite executable")?;
+    let previous = previous_hooks_dir(workspace)?.filter(|previous| *previous != dir);
+
+    for hook in MANAGED_HOOKS {
+        let path = dir.join(hook);
+        let script = hook_script(hook, &exe, agent_id, root, previous.as_deref());
+        std::fs::write(&path, script)
+            .map_err(|e| SpriteError::io(format!("write {}", path.display()), e))?;
+        #[cfg(unix)]
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| SpriteError::io(format!("make {} executable", path.display()), e))?;
+    }
+
+    git::set_worktree_config_at(workspace, "core.hooksPath", Some(&dir.to_string_lossy()))?;
+    Ok(dir)
+}
+
+/// The hooks directory the repository used before sprite's, to chain to it
+fn previous_hooks_dir(workspace: &Path) -> Result<Option<PathBuf>> {
+    let dir = match git::config_get_at(workspace, Some("--local"), "core.hooksPath")? {
+        // A relative hooksPath is resolved from the worktree root
+        Some(path) => workspace.join(path),
+        None => git::common_dir_at(workspace)?.join("hooks"),
+    };
+    Ok(dir.is_dir().then_some(dir))
+}
+
+/// Shell wrapper that runs sprite's checks, then the repository's own hook
+fn hook_script(
+    hook: &str,
+    exe: &Path,
+    agent_id: &str,
+    root: &Path,
+    previous: Option<&Path>,
+) -> String {
+    let check = format!(
+        "{} hooks run {} --agent {} --root {} -- \"$@\"",
+        tmux::shell_quote(&exe.to_string_lossy()),
+        hook,
+        tmux::shell_quote(agent_id),
+        tmux::shell_quote(&root.to_string_lossy())
+    );
+    let chained = previous.map(|dir| tmux::shell_quote(&dir.join(hook).to_string_lossy()));
+
+    let mut script =
+        String::from("#!/bin/sh\n# Managed by sprite; regenerate with 'sprite hooks install'\n");
+    // pre-push gets the refs being pushed on stdin, which both hooks need to read
+    if hook == "pre-push" {
+        script.push_str("input=$(cat)\n");
+        script.push_str(&format!(
+            "printf '%s\\n' \"$input\" | {} || exit $?\n",
+            check
+        ));
+        if let Some(chained) = chained {
+            script.push_str(&format!(
+                "if [ -x {0} ]; then printf '%s\\n' \"$input\" | exec {0} \"$@\"; fi\n",
+                chained
+            ));
+        }
+    } else {
+        script.push_str(&format!("{} || exit $?\n", check));
+        if let Some(chained) = chained {
+            script.push_str(&format!(
+                "if [ -x {0} ]; then exec {0} \"$@\"; fi\n",
+                chained
+            ));
+        }
+    }
+    script
+}
+
+/// Run the checks behind one managed hook, from the agent's worktree
+fn run_hook(hook: &str, agent_id: &str, root: &Path, args: &[String]) -> Result<()> {
+    let config_path = root.join("agents").join("agents.yaml");
+    // A broken configuration must not lock agents out of git
+    let config = match SpriteConfig::load_layered(&config_path) {
+        Ok((config, _)) => config,
+        Err(e) => {
+            eprintln!(
+                "⚠️  sprite hooks: configuration not loaded, checks skipped: {}",
+                e
+            );
+            return Ok(());
+        }
+    };
+    if !config.hooks.enabled {
+        return Ok(());
+    }
+    let workspace = std::env::current_dir().context("Failed to read the current directory")?;
+
+    // The trunk is protected whatever the configured list says
+    let mut hooks = config.hooks.clone();
+    let trunk = config.trunk();
+    if !hooks.is_protected(&trunk) {
+        hooks.protected_branches.push(trunk);
+    }
+
+    match hook {
+        "pre-commit" => {
+            let branch = git::get_current_branch_at(&workspace).unwrap_or_default();
+            check_commit_branch(&hooks, agent_id, &branch)?;
+
+            let agent_commands = config
+                .get_agent(agent_id)
+                .map(|agent| agent.config.pre_commit.clone())
+                .unwrap_or_default();
+            for command in hooks.pre_commit.iter().chain(&agent_commands) {
+                run_pre_commit_command(command, &workspace)?;
+            }
+            Ok(())
+        }
+        "commit-msg" => {
+            let file = args.first().ok_or_else(|| {
+                SpriteError::validation(
+                    "commit-msg hook needs the message file",
+                    None::<String>,
+                    None::<String>,
+                )
+            })?;
+            let message = std::fs::read_to_string(file)
+                .map_err(|e| SpriteError::io(format!("read {}", file), e))?;
+            check_commit_message(&hooks, &message)?;
+            git::add_trailer_at(
+                &workspace,
+                Path::new(file),
+                &format!("{}: {}", AGENT_TRAILER, agent_id),
+            )
+        }
+        "pre-push" => {
+            let remote = args.first().map(|s| s.as_str()).unwrap_or_default();
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read the refs being pushed")?;
+            check_push(&hooks, agent_id, remote, &input)
+        }
+        other => Err(SpriteError::validation(
+            format!("Unknown managed hook '{}'", other),
+            Some("hook"),
+            Some(other),
+        )
+        .into()),
+    }
+}
+
+fn check_commit_branch(hooks: &HooksConfig, agent_id: &str, branch: &str) -> Result<()> {
+    if hooks.is_protected(branch) {
+        return Err(SpriteError::validation(
+            format!(
+                "Agent {} may not commit on protected branch '{}'; commit on the agent branch instead",
+                agent_id, branch
+            ),
+            Some("hooks.protected_branches"),
+            Some(branch),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn run_pre_commit_command(command: &str, workspace: &Path) -> Result<()> {
+    eprintln!("🪝 pre-commit: {}", command);
+    let status = std::process::Command::new("sh")
+        .args(["-c", command])
+        .current_dir(workspace)
+        .status()
+        .with_context(|| format!("Failed to run pre-commit command '{}'", command))?;
+
+    if !status.success() {
+        return Err(SpriteError::process(command, status.code(), "", "").into());
+    }
+    Ok(())
+}
+
+/// Check the subject line of a commit message against the configured rules.
+fn check_commit_message(hooks: &HooksConfig, message: &str) -> Result<()> {
+    let subject = message
+        .lines()
+        .map(|line| line.trim_end())
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or_default();
+
+    // Messages git writes itself, or that autosquash will fold away, are left alone
+    if ["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "]
+        .iter()
+        .any(|prefix| subject.starts_with(prefix))
+    {
+        return Ok(());
+    }
+
+    if let Some(max) = hooks.max_subject_length {
+        let length = subject.chars().count();
+        if length > max {
+            return Err(SpriteError::validation(
+                format!(
+                    "Commit subject is {} characters long; the limit is {}",
+                    length, max
+                ),
+                Some("hooks.max_subject_length"),
+                Some(subject),
+            )
+            .into());
+        }
+    }
+    if let Some(pattern) = hooks.compile_message_pattern()? {
+        if !pattern.is_match(subject) {
+            return Err(SpriteError::validation(
+                format!(
+                    "Commit subject '{}' does not match the required pattern '{}'",
+                    subject,
+                    pattern.as_str()
+                ),
+                Some("hooks.commit_message_pattern"),
+                Some(subject),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Check a push: `refs` is the `<local ref> <local sha> <remote ref> <remote sha>` list git sends.
+fn check_push(hooks: &HooksConfig, agent_id: &str, remote: &str, refs: &str) -> Result<()> {
+    if !hooks.allowed_remotes.is_empty() && !hooks.allowed_remotes.iter().any(|r| r == remote) {
+        return Err(SpriteError::validation(
+            format!(
+                "Agent {} may not push to remote '{}' (allowed: {})",
+                agent_id,
+                remote,
+                hooks.allowed_remotes.join(", ")
+            ),
+            Some("hooks.allowed_remotes"),
+            Some(remote),
+        )
+        .into());
+    }
+
+    for line in refs.lines() {
+        let remote_ref = match line.split_whitespace().nth(2) {
+            Some(remote_ref) => remote_ref,
+            None => continue,
+        };
+        if let Some(branch) = remote_ref.strip_prefix("refs/heads/") {
+            if hooks.is_protected(branch) {
+                return Err(SpriteError::validation(
+                    format!(
+                        "Agent {} may not push to protected branch '{}' on '{}'",
+                        agent_id, branch, remote
+                    ),
+                    Some("hooks.protected_branches"),
+                    Some(branch),
+                )
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_checks() {
+        let hooks = HooksConfig {
+            protected_branches: vec!["main".to_string(), "release/*".to_string()],
+            commit_message_pattern: Some(r"^(feat|fix|chore): ".to_string()),
+            max_subject_length: Some(30),
+            allowed_remotes: vec!["origin".to_string()],
+            ..HooksConfig::default()
+        };
+
+        assert!(check_commit_branch(&hooks, "1", "agents/1").is_ok());
+        assert!(check_commit_branch(&hooks, "1", "main").is_err());
+        assert!(check_commit_branch(&hooks, "1", "release/2.0").is_err());
+
+        assert!(check_commit_message(&hooks, "# comment\n\nfix: handle empty input\n").is_ok());
+        assert!(check_commit_message(&hooks, "handle empty input").is_err());
+        assert!(check_commit_message(&hooks, "fix: a subject that is far too long").is_err());
+        assert!(check_commit_message(&hooks, "Merge branch 'main' into agents/1").is_ok());
+
+        let push = |remote_ref: &str| format!("refs/heads/agents/1 abc {} 000\n", remote_ref);
+        assert!(check_push(&hooks, "1", "origin", &push("refs/heads/agents/1")).is_ok());
+        assert!(check_push(&hooks, "1", "origin", &push("refs/heads/main")).is_err());
+        assert!(check_push(&hooks, "1", "upstream", &push("refs/heads/agents/1")).is_err());
+    }
+
+    #[test]
+    fn test_agent_identity_round_trip() {
+        let mut agent = AgentConfig {
+            id: "review-1".to_string(),
+            branch: "agents/review-1".to_string(),
+            worktree_path: "agents/review-1".to_string(),
+            model: "claude-sonnet-4".to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            status: "Inactive".to_string(),
+            config: Default::default(),
+        };
+        let (name, email) = agent_identity(&agent);
+        assert_eq!(name, "Agent review-1 (claude-sonnet-4)");
+        assert_eq!(email, "sprite-agent-review-1@localhost");
+        assert_eq!(
+            agent_from_email(&format!("<{}>", email)),
+            Some("review-1".to_string())
+        );
+        assert_eq!(agent_from_email("jane@example.com"), None);
+
+        agent.id = "a b".to_string();
+        assert_eq!(agent_identity(&agent).1, "sprite-agent-a-b@localhost");
+    }
+
+    #[test]
+    fn test_hook_script_chains_previous_hook() {
+        let script = hook_script(
+            "pre-push",
+            Path::new("/usr/bin/sprite"),
+            "2",
+            Path::new("/work/my project"),
+            Some(Path::new("/work/my project/.git/hooks")),
+        );
+        assert!(script.contains(
+            "| /usr/bin/sprite hooks run pre-push --agent 2 --root '/work/my project' -- \"$@\" || exit $?"
+        ));
+        assert!(script.contains("exec '/work/my project/.git/hooks/pre-push' \"$@\""));
+
+        let script = hook_script(
+            "commit-msg",
+            Path::new("sprite"),
+            "2",
+            Path::new("/w"),
+            None,
+        );
+        assert!(!script.contains("input=$(cat)"));
+        assert!(!script.contains("if [ -x"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::SpriteError;
+use crate::utils::git;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -46,6 +47,19 @@ pub fn execute(options: InitOptions) -> Result<()> {
     // Step 5: Generate configuration file (AFTER worktrees to avoid nested copies)
     generate_agents_config(&config_file, options.agents)?;
 
+    // Give each agent worktree its git identity and managed hooks
+    let config_path = std::env::current_dir()?.join(&config_file);
+    if let Ok(config) = crate::commands::config::SpriteConfig::load_from_path(&config_path) {
+        for agent in &config.agents {
+            crate::commands::hooks::setup_worktree(&config, &agent.id);
+        }
+
+        // Remember this project so 'sprite projects list' can find it
+        if let Some(root) = config.project_root() {
+            crate::utils::registry::register_project(&root, &config.effective_session_name());
+        }
+    }
+
     // Step 5: Create shell script templates
     create_shell_script_templates(&agents_dir)?;
 
@@ -398,6 +412,12 @@ fn create_base_directory_structure(agents_dir: &Path) -> Result<()> {
 # Ignore agent worktree directories (numbered directories)
 /[0-9]*/
 
+# Machine-specific configuration overrides
+/agents.local.yaml
+
+# Agent pane transcripts
+/logs/*/
+
 # Keep everything else (configs, scripts, profiles, logs)
 !agents.yaml
 !scripts/
@@ -433,7 +453,8 @@ fn generate_agents_config(config_file: &Path, agent_count: u32) -> Result<()> {
 
     let config = if agent_count == 0 {
         // Empty configuration template with updated schema
-        include_str!("../templates/empty_agents.yaml").to_string()
+        let trunk = git::default_branch().unwrap_or_else(|| "main".to_string());
+        include_str!("../templates/empty_agents.yaml").replace("{{trunk}}", &trunk)
     } else {
         // Generate configuration with agents
         generate_config_content(agent_count)?
@@ -483,9 +504,10 @@ fn generate_config_content(agent_count: u32) -> Result<String> {
     config.push_str("  auto_sync: false\n");
     config.push_str("  default_interval_secs: 300\n");
     config.push_str("  conflict_resolution: Manual\n");
+    // Exclude the repository's own trunk rather than assuming main/master
+    let trunk = git::default_branch().unwrap_or_else(|| "main".to_string());
     config.push_str("  exclude_branches:\n");
-    config.push_str("  - main\n");
-    config.push_str("  - master\n");
+    config.push_str(&format!("  - {}\n", trunk));
     config.push_str("  pre_sync_hooks: []\n");
     config.push_str("  post_sync_hooks: []\n");
 
@@ -0,0 +1,398 @@
+//! Integrate command - Collect agent branches into an integration branch
+//!
+//! Work happens in a dedicated worktree so the user's own checkout never
+//! switches branches. Conflicts leave that worktree mid-merge for the user to
+//! resolve; re-running the command afterwards picks up where it stopped. An
+//! agent that fails verification is taken back out, so a re-run tries it again.
+
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::git;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Result of integrating a single agent branch
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    /// New commits landed on the integration branch
+    Integrated { commits: usize },
+    /// Nothing new to bring in
+    UpToDate,
+    /// The agent was not integrated and the run continued
+    Skipped { reason: String },
+    /// Git stopped on conflicts in these files
+    Conflict { files: Vec<String> },
+    /// The verification command failed, so the agent's changes were reset away
+    VerifyFailed,
+}
+
+impl Outcome {
+    /// Whether the run must stop after this outcome
+    fn is_blocking(&self) -> bool {
+        matches!(self, Outcome::Conflict { .. } | Outcome::VerifyFailed)
+    }
+
+    /// One-line summary shown in the per-agent report
+    fn describe(&self, rebase: bool) -> String {
+        match self {
+            Outcome::Integrated { commits } => format!(
+                "✅ {} {} commit(s)",
+                if rebase { "replayed" } else { "merged" },
+                commits
+            ),
+            Outcome::UpToDate => "✅ already up to date".to_string(),
+            Outcome::Skipped { reason } => format!("⏭️  skipped ({})", reason),
+            Outcome::Conflict { files } => {
+                format!("❌ conflicts in {} file(s)", files.len())
+            }
+            Outcome::VerifyFailed => "❌ verification failed, changes reset".to_string(),
+        }
+    }
+}
+
+/// Execute the integrate command
+pub fn execute(
+    agent_ids: Vec<String>,
+    into: &str,
+    base: Option<&str>,
+    rebase: bool,
+    verify: Option<&str>,
+) -> Result<()> {
+    git::validate_git_repository().context("Must be run from within a git repository")?;
+
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let base = base.map(str::to_string).unwrap_or_else(|| config.trunk());
+    let agents = select_agents(&config, &agent_ids)?;
+    if agents.is_empty() {
+        println!("📝 No agents to integrate.");
+        return Ok(());
+    }
+
+    if agents.iter().any(|a| a.branch == into) {
+        return Err(SpriteError::config(format!(
+            "Integration branch '{}' is also an agent branch. Pick a different --into branch.",
+            into
+        ))
+        .into());
+    }
+
+    let worktree = prepare_integration_worktree(into, &base)?;
+
+    println!(
+        "🔗 Integrating {} agent(s) into '{}' ({})",
+        agents.len(),
+        into,
+        if rebase { "rebase" } else { "merge" }
+    );
+    println!("   Worktree: {}", worktree.display());
+    println!();
+
+    let mut results: Vec<(&AgentConfig, Outcome)> = Vec::new();
+    for agent in &agents {
+        println!("  🤖 Agent {} ({})", agent.id, agent.branch);
+        let outcome = integrate_agent(&worktree, into, agent, rebase, verify)?;
+        println!("     {}", outcome.describe(rebase));
+
+        let blocking = outcome.is_blocking();
+        results.push((agent, outcome));
+        if blocking {
+            break;
+        }
+    }
+
+    print_report(&agents, &results, rebase);
+
+    match results.last() {
+        Some((agent, Outcome::Conflict { files })) => {
+            println!();
+            println!("⚠️  Resolve the conflicts in {}:", worktree.display());
+            for file in files {
+                println!("     {}", file);
+            }
+            if rebase {
+                println!("   Then run 'git cherry-pick --continue' in that directory");
+            } else {
+                println!("   Then run 'git commit' in that directory");
+            }
+            println!("   and re-run 'sprite integrate' to continue with the remaining agents.");
+
+            Err(SpriteError::git(format!(
+                "Integration stopped on conflicts with agent {} ({})",
+                agent.id, agent.branch
+            ))
+            .into())
+        }
+        Some((agent, Outcome::VerifyFailed)) => {
+            println!();
+            println!(
+                "💡 '{}' was reset to before agent {}; fix {} and re-run 'sprite integrate'.",
+                into, agent.id, agent.branch
+            );
+
+            Err(SpriteError::agent(
+                format!("Verification failed after integrating agent {}", agent.id),
+                Some(agent.id.as_str()),
+            )
+            .into())
+        }
+        _ => {
+            println!();
+            println!("✅ Integration branch '{}' is up to date", into);
+            Ok(())
+        }
+    }
+}
+
+/// Resolve the requested agents in order, defaulting to every configured agent
+fn select_agents(config: &SpriteConfig, agent_ids: &[String]) -> Result<Vec<AgentConfig>> {
+    if agent_ids.is_empty() {
+        return Ok(config.agents.clone());
+    }
+
+    agent_ids
+        .iter()
+        .map(|id| {
+            config
+                .get_agent(id)
+                .cloned()
+                .ok_or_else(|| SpriteError::agent_not_found(id.as_str()).into())
+        })
+        .collect()
+}
+
+/// Directory of the integration worktree for a branch
+fn integration_worktree_path(common_dir: &Path, into: &str) -> PathBuf {
+    common_dir
+        .join("sprite")
+        .join("integration")
+        .join(into.replace('/', "-"))
+}
+
+/// Make sure the integration branch exists and is checked out in a clean worktree
+fn prepare_integration_worktree(into: &str, base: &str) -> Result<PathBuf> {
+    if !git::branch_exists(into)? {
+        if !git::ref_exists(base)? {
+            return Err(SpriteError::git(format!(
+                "Base ref '{}' does not exist. Use --base to pick another branch, tag or commit.",
+                base
+            ))
+            .into());
+        }
+        git::create_branch_at(into, base)?;
+        println!("🌿 Created integration branch '{}' from {}", into, base);
+    }
+
+    let worktree = match git::find_worktree_for_branch(into)? {
+        Some(path) => path,
+        None => {
+            let path = integration_worktree_path(&git::get_common_dir()?, into);
+            git::prune_worktrees()?;
+            if path.exists() {
+                std::fs::remove_dir_all(&path).with_context(|| {
+                    format!("Failed to clear stale worktree: {}", path.display())
+                })?;
+            }
+            git::create_worktree(&path, into)
+                .with_context(|| format!("Failed to create integration worktree for '{}'", into))?;
+            path
+        }
+    };
+
+    if git::operation_in_progress_at(&worktree)?
+        || !git::get_status_string_at(&worktree)?.is_empty()
+    {
+        return Err(SpriteError::git(format!(
+            "Integration worktree {} has uncommitted changes or an unfinished merge. \
+             Finish or abort it, then re-run 'sprite integrate'.",
+            worktree.display()
+        ))
+        .into());
+    }
+
+    Ok(worktree)
+}
+
+/// Merge or replay one agent branch onto the integration branch
+fn integrate_agent(
+    worktree: &Path,
+    into: &str,
+    agent: &AgentConfig,
+    rebase: bool,
+    verify: Option<&str>,
+) -> Result<Outcome> {
+    if git::rev_parse_at(worktree, &format!("refs/heads/{}", agent.branch)).is_err() {
+        return Ok(Outcome::Skipped {
+            reason: format!("branch '{}' does not exist", agent.branch),
+        });
+    }
+
+    let before = git::get_head_commit_at(worktree)?;
+
+    let (clean, commits) = if rebase {
+        // Skip commits already replayed, even when a conflict fix changed their patch
+        let replayed = git::cherry_picked_sources_at(worktree, into)?;
+        let commits: Vec<String> = git::unapplied_commits_at(worktree, into, &agent.branch)?
+            .into_iter()
+            .filter(|commit| !replayed.contains(commit))
+            .collect();
+        if commits.is_empty() {
+            return Ok(Outcome::UpToDate);
+        }
+        (git::cherry_pick_at(worktree, &commits)?, commits.len())
+    } else {
+        if git::is_ancestor_at(worktree, &agent.branch, into)? {
+            return Ok(Outcome::UpToDate);
+        }
+        let commits = git::count_commits_at(worktree, &format!("{}..{}", into, agent.branch))?;
+        let message = format!("Integrate agent {} ({})", agent.id, agent.branch);
+        (
+            git::merge_branch_at(worktree, &agent.branch, &message)?,
+            commits,
+        )
+    };
+
+    if !clean {
+        return Ok(Outcome::Conflict {
+            files: git::conflicted_files_at(worktree)?,
+        });
+    }
+
+    if let Some(command) = verify {
+        println!("     🧪 Running: {}", command);
+        if !run_verification(worktree, command)? {
+            // Left in place, the agent would count as integrated on the next run
+            git::reset_hard_at(worktree, &before)?;
+            return Ok(Outcome::VerifyFailed);
+        }
+    }
+
+    Ok(Outcome::Integrated { commits })
+}
+
+/// Run the verification command in the integration worktree
+fn run_verification(worktree: &Path, command: &str) -> Result<bool> {
+    let status = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(worktree)
+        .status()
+        .with_context(|| format!("Failed to run verification command: {}", command))?;
+
+    Ok(status.success())
+}
+
+/// Print the per-agent summary, including agents that were never reached
+fn print_report(agents: &[AgentConfig], results: &[(&AgentConfig, Outcome)], rebase: bool) {
+    println!();
+    println!("📋 Integration Summary:");
+    for agent in agents {
+        let line = match results.iter().find(|(a, _)| a.id == agent.id) {
+            Some((_, outcome)) => outcome.describe(rebase),
+            None => "⏸️  not attempted".to_string(),
+        };
+        println!("  Agent {} ({}): {}", agent.id, agent.branch, line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str) -> AgentConfig {
+        AgentConfig {
+            id: id.to_string(),
+            branch: format!("agents/{}", id),
+            worktree_path: format!("agents/{}", id),
+            model: "claude-sonnet-4".to_string(),
+            description: format!("Agent {}", id),
+            tags: Vec::new(),
+            status: "inactive".to_string(),
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_select_agents_keeps_requested_order() {
+        let mut config = SpriteConfig::default();
+        config.agents = vec![agent("1"), agent("2"), agent("3")];
+
+        let all = select_agents(&config, &[]).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let picked = select_agents(&config, &["3".to_string(), "1".to_string()]).unwrap();
+        let ids: Vec<&str> = picked.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "1"]);
+
+        assert!(select_agents(&config, &["9".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_outcomes() {
+        assert!(Outcome::Conflict { files: vec![] }.is_blocking());
+        assert!(Outcome::VerifyFailed.is_blocking());
+        assert!(!Outcome::UpToDate.is_blocking());
+        assert!(!Outcome::Skipped {
+            reason: "missing".to_string()
+        }
+        .is_blocking());
+
+        assert!(Outcome::Integrated { commits: 2 }
+            .describe(false)
+            .contains("merged 2"));
+        assert!(Outcome::Integrated { commits: 2 }
+            .describe(true)
+            .contains("replayed 2"));
+    }
+
+    #[test]
+    fn test_rerun_after_failed_verification_tries_again() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+        };
+        git(&["init", "-q", "-b", "integration"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@example.com"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        git(&["branch", "agents/1"]);
+        git(&["checkout", "-q", "agents/1"]);
+        std::fs::write(repo.join("broken"), "").unwrap();
+        git(&["add", "broken"]);
+        git(&["commit", "-q", "-m", "break the build"]);
+        git(&["checkout", "-q", "integration"]);
+
+        for rebase in [false, true] {
+            for _ in 0..2 {
+                let outcome = integrate_agent(
+                    repo,
+                    "integration",
+                    &agent("1"),
+                    rebase,
+                    Some("test ! -e broken"),
+                )
+                .unwrap();
+                assert_eq!(outcome, Outcome::VerifyFailed);
+                assert!(!repo.join("broken").exists());
+            }
+        }
+    }
+
+    #[test]
+    fn test_integration_worktree_path() {
+        let path = integration_worktree_path(Path::new("/repo/.git"), "release/next");
+        assert_eq!(
+            path,
+            PathBuf::from("/repo/.git/sprite/integration/release-next")
+        );
+    }
+}
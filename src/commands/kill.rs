@@ -1,3 +1,6 @@
+use crate::commands::apply;
+use crate::commands::config::SpriteConfig;
+use crate::commands::selector;
 use crate::error::SpriteError;
 use crate::utils::session_recovery::{
     analyze_session_health, cleanup_temp_files, generate_health_report, RecoveryConfig,
@@ -6,12 +9,22 @@ use crate::utils::tmux;
 use anyhow::{Context, Result};
 use std::io::{self, Write};
 
-/// Execute kill command with session name, force, and all options
-pub fn execute(session_name: Option<String>, force: bool, all: bool) -> Result<()> {
+/// Execute kill command with session name, force, all and agent selector options
+pub fn execute(
+    session_name: Option<String>,
+    force: bool,
+    all: bool,
+    agents: Option<&str>,
+) -> Result<()> {
     // Check if tmux is available
     tmux::is_tmux_available()
         .context("tmux is not available. Please install tmux to use sprite kill.")?;
 
+    // Stop only the selected agents, leaving the session running
+    if let Some(expression) = agents {
+        return kill_agents(expression, force);
+    }
+
     // Handle kill all sessions
     if all {
         return kill_all_sessions(force);
@@ -68,6 +81,77 @@ pub fn execute(session_name: Option<String>, force: bool, all: bool) -> Result<(
     Ok(())
 }
 
+/// Close the panes of the agents matched by a selector
+fn kill_agents(expression: &str, force: bool) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let session_name = config.effective_session_name();
+    if !tmux::session_exists(&session_name)? {
+        println!("📭 No sprite session is running for this project.");
+        return Ok(());
+    }
+
+    let agent_ids = selector::select(&config, expression)?;
+    let panes = tmux::list_agent_panes(&session_name)
+        .with_context(|| format!("Failed to get panes for session '{}'", session_name))?;
+    let root = config.project_root().unwrap_or_default();
+
+    let mut targets = Vec::new();
+    for agent_id in &agent_ids {
+        let agent = config
+            .get_agent(agent_id)
+            .expect("selected agents are configured");
+        match apply::find_agent_pane(&panes, agent, &root) {
+            Some(pane) => targets.push((agent_id.as_str(), pane.pane_id.clone())),
+            None => println!("ℹ️  Agent {} has no pane in the session", agent_id),
+        }
+    }
+
+    if targets.is_empty() {
+        println!("📭 No running agents match '{}'.", expression);
+        return Ok(());
+    }
+
+    let ids: Vec<&str> = targets.iter().map(|(id, _)| *id).collect();
+    if !force {
+        print!(
+            "❓ Stop agent(s) {} in session '{}'? [y/N] ",
+            ids.join(", "),
+            session_name
+        );
+        io::stdout().flush().context("Failed to flush stdout")?;
+
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .context("Failed to read user input")?;
+        let input = input.trim().to_lowercase();
+        if input != "y" && input != "yes" {
+            println!("❌ Agent kill cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut stopped = Vec::new();
+    for (agent_id, pane_id) in &targets {
+        tmux::kill_pane(pane_id).with_context(|| format!("Failed to stop agent '{}'", agent_id))?;
+        println!("  ✅ Stopped agent {}", agent_id);
+        stopped.push(agent_id.to_string());
+    }
+    if !tmux::session_exists(&session_name)? {
+        println!(
+            "ℹ️  That was the last pane; session '{}' has ended.",
+            session_name
+        );
+        return Ok(());
+    }
+
+    // Forget the stopped agents so that 'sprite apply' opens them again
+    apply::record_agent_changes(&[], &stopped);
+    println!("💡 Use 'sprite apply' to reopen windows for stopped agents");
+
+    Ok(())
+}
+
 /// Kill all tmux sessions
 fn kill_all_sessions(force: bool) -> Result<()> {
     let sessions = tmux::list_sessions().context("Failed to list sessions")?;
@@ -137,9 +221,19 @@ fn kill_all_sessions(force: bool) -> Result<()> {
 
 /// Find the default session to kill
 fn find_default_session(sessions: &[tmux::SessionInfo]) -> Result<String> {
-    // First try to find a session with "sprite-session" name
-    if let Some(session) = sessions.iter().find(|s| s.name.contains("sprite-session")) {
-        return Ok(session.name.clone());
+    // Inside a sprite project, only ever target that project's session
+    if let Ok(project_session) = crate::commands::config::current_session_name() {
+        if sessions.iter().any(|s| s.name == project_session) {
+            return Ok(project_session);
+        }
+        return Err(SpriteError::session(
+            format!(
+                "No sprite session '{}' is running for this project.",
+                project_session
+            ),
+            Some(project_session),
+        )
+        .into());
     }
 
     // If no sprite session found, take the most recently created session
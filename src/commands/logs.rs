@@ -0,0 +1,196 @@
+//! Logs command - Read the transcripts captured from agent panes
+
+use crate::commands::config::SpriteConfig;
+use crate::error::SpriteError;
+use crate::utils::tmux;
+use crate::utils::transcript::{self, TranscriptLine, TranscriptTail, TranscriptWriter};
+use anyhow::{Context, Result};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use std::path::Path;
+
+/// Execute the logs command.
+///
+/// With `capture`, reads pane output from stdin into the agent's transcript instead;
+/// this is what `tmux pipe-pane` runs for each agent pane.
+pub fn execute(
+    agent_id: &str,
+    follow: bool,
+    since: Option<&str>,
+    grep: Option<&str>,
+    capture: Option<&Path>,
+) -> Result<()> {
+    if let Some(root) = capture {
+        return capture_transcript(root, agent_id);
+    }
+
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    if config.get_agent(agent_id).is_none() {
+        return Err(SpriteError::agent_not_found(agent_id).into());
+    }
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let dir = transcript::agent_log_dir(&root, agent_id);
+    let since = since.map(parse_since).transpose()?;
+
+    let files = transcript::list_files(&dir)?;
+    if files.is_empty() && !follow {
+        println!("📭 No transcript for agent {} yet.", agent_id);
+        println!(
+            "💡 Transcripts are recorded for agents started with 'sprite start' or 'sprite apply'."
+        );
+        return Ok(());
+    }
+
+    // Remember where the history ends so following picks up right after it
+    let tail = TranscriptTail::at_end(&dir)?;
+
+    let filter = LineFilter { since, grep };
+    for file in &files {
+        for line in transcript::read_file(file)? {
+            filter.print(&line);
+        }
+    }
+
+    if follow {
+        follow_transcript(tail, &filter)?;
+    }
+
+    Ok(())
+}
+
+/// Start recording an agent pane into its transcript.
+///
+/// `pipe-pane` replaces any earlier pipe on the pane, so calling this again is harmless.
+pub fn start_capture(pane_id: &str, agent_id: &str, root: &Path) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the sprite executable")?;
+    let command = format!(
+        "exec {} logs {} --capture {}",
+        tmux::shell_quote(&exe.to_string_lossy()),
+        tmux::shell_quote(agent_id),
+        tmux::shell_quote(&root.to_string_lossy())
+    );
+    tmux::pipe_pane(pane_id, &command)
+}
+
+/// Copy pane output from stdin into the agent's transcript until the pane closes.
+fn capture_transcript(root: &Path, agent_id: &str) -> Result<()> {
+    let config_path = root.join("agents").join("agents.yaml");
+    let rotation = SpriteConfig::load_layered(&config_path)
+        .map(|(config, _)| config.settings.logging.rotation)
+        .unwrap_or_else(|_| SpriteConfig::new().settings.logging.rotation);
+
+    let mut writer = TranscriptWriter::new(transcript::agent_log_dir(root, agent_id), rotation);
+    writer.capture(std::io::stdin().lock())
+}
+
+/// Which transcript lines to show
+struct LineFilter<'a> {
+    since: Option<NaiveDateTime>,
+    grep: Option<&'a str>,
+}
+
+impl LineFilter<'_> {
+    fn matches(&self, line: &TranscriptLine) -> bool {
+        self.since.map_or(true, |since| line.at >= since)
+            && self
+                .grep
+                .map_or(true, |pattern| line.text.contains(pattern))
+    }
+
+    fn print(&self, line: &TranscriptLine) {
+        if self.matches(line) {
+            println!("{} {}", line.at.format("%m-%d %H:%M:%S"), line.text);
+        }
+    }
+}
+
+/// Print new transcript lines as they are written.
+fn follow_transcript(mut tail: TranscriptTail, filter: &LineFilter) -> Result<()> {
+    loop {
+        for line in tail.read_new()? {
+            filter.print(&line);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+}
+
+/// Parse `--since`: a duration ago (`30m`, `2h`, `1d`), a time today (`08:00`),
+/// a date (`2026-10-17`) or both (`2026-10-17 22:00`).
+fn parse_since(value: &str) -> Result<NaiveDateTime> {
+    parse_since_at(value, Local::now().naive_local())
+}
+
+fn parse_since_at(value: &str, now: NaiveDateTime) -> Result<NaiveDateTime> {
+    let value = value.trim();
+
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        if let Ok(amount) = value[..value.len() - 1].parse::<i64>() {
+            let ago = match unit {
+                's' => Some(Duration::seconds(amount)),
+                'm' => Some(Duration::minutes(amount)),
+                'h' => Some(Duration::hours(amount)),
+                'd' => Some(Duration::days(amount)),
+                _ => None,
+            };
+            if let Some(ago) = ago {
+                return Ok(now - ago);
+            }
+        }
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(at);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN));
+    }
+    for format in ["%H:%M:%S", "%H:%M"] {
+        if let Ok(time) = NaiveTime::parse_from_str(value, format) {
+            return Ok(now.date().and_time(time));
+        }
+    }
+
+    Err(SpriteError::validation(
+        format!(
+            "Invalid --since value '{}'. Use a duration (30m, 2h, 1d), a time (08:00) or a date (2026-10-17)",
+            value
+        ),
+        Some("since"),
+        Some(value),
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let at = |d, h, m| {
+            NaiveDate::from_ymd_opt(2026, 10, d)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+        };
+
+        assert_eq!(parse_since_at("30m", now).unwrap(), at(18, 8, 30));
+        assert_eq!(parse_since_at("12h", now).unwrap(), at(17, 21, 0));
+        assert_eq!(parse_since_at("1d", now).unwrap(), at(17, 9, 0));
+        assert_eq!(parse_since_at("07:15", now).unwrap(), at(18, 7, 15));
+        assert_eq!(parse_since_at("2026-10-17", now).unwrap(), at(17, 0, 0));
+        assert_eq!(
+            parse_since_at("2026-10-17 22:00", now).unwrap(),
+            at(17, 22, 0)
+        );
+        assert!(parse_since_at("yesterday", now).is_err());
+        assert!(parse_since_at("5w", now).is_err());
+    }
+}
@@ -1,14 +1,30 @@
 pub mod agents;
+pub mod apply;
 pub mod attach;
+pub mod blame_agents;
+pub mod cache;
+pub mod checkpoint;
 pub mod config;
+pub mod env;
 pub mod help;
 pub mod hey;
+pub mod hooks;
 pub mod init;
+pub mod integrate;
 pub mod kill;
+pub mod logs;
+pub mod overlap;
+pub mod patch;
+pub mod ports;
+pub mod projects;
+pub mod prompts;
 pub mod remove;
+pub mod sandbox;
+pub mod selector;
 pub mod slash_status;
 pub mod start;
 pub mod status;
 pub mod sync;
 pub mod update;
+pub mod watch;
 pub mod zoom;
@@ -0,0 +1,421 @@
+//! Overlap command - Detect agents editing the same files
+//!
+//! Each agent branch is compared with its merge base against the base branch.
+//! Hunk positions are taken from the base side of each diff, so hunk-level
+//! overlap is exact when agents share a merge base and approximate otherwise.
+
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::utils::git;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// File recording the last overlap count seen by `sprite status`
+const STATE_FILE: &str = "overlap.yaml";
+
+/// Changed line range on the base side of a diff, as `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LineRange {
+    start: u32,
+    end: u32,
+}
+
+impl LineRange {
+    fn overlaps(&self, other: &LineRange) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Files (and hunks, where known) an agent changed relative to the base
+#[derive(Debug, Clone, Default)]
+struct AgentChanges {
+    agent_id: String,
+    branch: String,
+    files: BTreeMap<String, Vec<LineRange>>,
+}
+
+/// A file changed by two agents
+#[derive(Debug, Clone, PartialEq)]
+struct SharedFile {
+    path: String,
+    overlapping_hunks: bool,
+}
+
+/// Files shared between a pair of agents
+#[derive(Debug, Clone, PartialEq)]
+struct Overlap {
+    first: String,
+    second: String,
+    files: Vec<SharedFile>,
+}
+
+/// Overlap count remembered between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct OverlapState {
+    shared_files: usize,
+}
+
+/// Execute the overlap command
+pub fn execute(base: Option<&str>, no_predict: bool) -> Result<()> {
+    git::validate_git_repository().context("Must be run from within a git repository")?;
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let base = base.map(str::to_string).unwrap_or_else(|| config.trunk());
+    let base = base.as_str();
+
+    let changes = collect_changes(&config.agents, base)?;
+    if changes.is_empty() {
+        println!("📝 No agent branches to compare.");
+        return Ok(());
+    }
+
+    println!("🔍 Agent changes relative to {}:", base);
+    for agent in &changes {
+        println!(
+            "  Agent {} ({}): {} file(s) changed",
+            agent.agent_id,
+            agent.branch,
+            agent.files.len()
+        );
+    }
+    println!();
+
+    let overlaps = find_overlaps(&changes);
+    save_state(&OverlapState {
+        shared_files: count_shared_files(&overlaps),
+    });
+
+    if overlaps.is_empty() {
+        println!("✅ No files are changed by more than one agent");
+        return Ok(());
+    }
+
+    for overlap in &overlaps {
+        println!(
+            "⚠️  Agent {} ↔ Agent {}: {} shared file(s)",
+            overlap.first,
+            overlap.second,
+            overlap.files.len()
+        );
+        for file in &overlap.files {
+            if file.overlapping_hunks {
+                println!("     {}  (overlapping hunks)", file.path);
+            } else {
+                println!("     {}", file.path);
+            }
+        }
+
+        if !no_predict {
+            let first = branch_of(&changes, &overlap.first);
+            let second = branch_of(&changes, &overlap.second);
+            match git::predict_merge_conflicts(first, second) {
+                Ok(conflicts) if conflicts.is_empty() => {
+                    println!("     ✅ Branches merge cleanly")
+                }
+                Ok(conflicts) => {
+                    println!("     💥 Predicted merge conflicts:");
+                    for path in conflicts {
+                        println!("        {}", path);
+                    }
+                }
+                Err(e) => println!("     ❔ Could not predict conflicts: {}", e),
+            }
+        }
+        println!();
+    }
+
+    println!("💡 Coordinate the agents above, or integrate one branch before the other continues.");
+
+    Ok(())
+}
+
+/// Print a warning from `sprite status` when overlap grew since the last check.
+///
+/// Does nothing outside a Sprite project or git repository.
+pub fn warn_if_overlap_grew() {
+    let config = match SpriteConfig::load() {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+    let changes = match collect_changes(&config.agents, &config.trunk()) {
+        Ok(changes) => changes,
+        Err(_) => return,
+    };
+
+    let current = count_shared_files(&find_overlaps(&changes));
+    let previous = load_state().map(|s| s.shared_files).unwrap_or(0);
+    save_state(&OverlapState {
+        shared_files: current,
+    });
+
+    if current > previous {
+        println!(
+            "⚠️  Agents now share {} changed file(s) (was {}). Run 'sprite overlap' for details.",
+            current, previous
+        );
+        println!();
+    }
+}
+
+/// Gather changed files and hunks for every agent branch that exists
+fn collect_changes(agents: &[AgentConfig], base: &str) -> Result<Vec<AgentChanges>> {
+    let mut changes = Vec::new();
+
+    for agent in agents {
+        if !git::branch_exists(&agent.branch)? {
+            continue;
+        }
+
+        let merge_base = git::merge_base(base, &agent.branch)?;
+        let mut files: BTreeMap<String, Vec<LineRange>> =
+            git::changed_files(&merge_base, &agent.branch)?
+                .into_iter()
+                .map(|path| (path, Vec::new()))
+                .collect();
+        for (path, ranges) in parse_hunks(&git::diff_zero_context(&merge_base, &agent.branch)?) {
+            files.insert(path, ranges);
+        }
+
+        changes.push(AgentChanges {
+            agent_id: agent.id.clone(),
+            branch: agent.branch.clone(),
+            files,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Parse base-side line ranges per file from a `git diff -U0` output
+fn parse_hunks(diff: &str) -> BTreeMap<String, Vec<LineRange>> {
+    let mut hunks: BTreeMap<String, Vec<LineRange>> = BTreeMap::new();
+    let mut old_path: Option<String> = None;
+    let mut current: Option<String> = None;
+    // File headers only appear between `diff --git` and the first hunk, so
+    // removed lines that happen to start with "-- " are not mistaken for them
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            in_header = true;
+            old_path = None;
+            current = None;
+        } else if !in_header {
+            if line.starts_with("@@ ") {
+                if let (Some(path), Some(range)) = (&current, parse_hunk_header(line)) {
+                    hunks.entry(path.clone()).or_default().push(range);
+                }
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = git::unquote_path(path)
+                .strip_prefix("a/")
+                .map(|p| p.to_string());
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files only have a name on the old side
+            current = git::unquote_path(path)
+                .strip_prefix("b/")
+                .map(|p| p.to_string())
+                .or_else(|| old_path.clone());
+        } else if line.starts_with("@@ ") {
+            in_header = false;
+            if let (Some(path), Some(range)) = (&current, parse_hunk_header(line)) {
+                hunks.entry(path.clone()).or_default().push(range);
+            }
+        }
+    }
+
+    hunks
+}
+
+/// Parse the old-side range of a hunk header like `@@ -10,3 +12,4 @@`
+fn parse_hunk_header(line: &str) -> Option<LineRange> {
+    let old = line.split_whitespace().nth(1)?.strip_prefix('-')?;
+    let (start, count) = match old.split_once(',') {
+        Some((start, count)) => (start.parse::<u32>().ok()?, count.parse::<u32>().ok()?),
+        None => (old.parse::<u32>().ok()?, 1),
+    };
+
+    // Pure insertions touch the point right after `start`
+    Some(LineRange {
+        start,
+        end: start + count.max(1),
+    })
+}
+
+/// Find every pair of agents that changed the same files
+fn find_overlaps(changes: &[AgentChanges]) -> Vec<Overlap> {
+    let mut overlaps = Vec::new();
+
+    for (i, first) in changes.iter().enumerate() {
+        for second in &changes[i + 1..] {
+            let files: Vec<SharedFile> = first
+                .files
+                .iter()
+                .filter_map(|(path, ranges)| {
+                    let other = second.files.get(path)?;
+                    Some(SharedFile {
+                        path: path.clone(),
+                        overlapping_hunks: ranges
+                            .iter()
+                            .any(|a| other.iter().any(|b| a.overlaps(b))),
+                    })
+                })
+                .collect();
+
+            if !files.is_empty() {
+                overlaps.push(Overlap {
+                    first: first.agent_id.clone(),
+                    second: second.agent_id.clone(),
+                    files,
+                });
+            }
+        }
+    }
+
+    overlaps
+}
+
+/// Total number of shared files across all agent pairs
+fn count_shared_files(overlaps: &[Overlap]) -> usize {
+    overlaps.iter().map(|o| o.files.len()).sum()
+}
+
+fn branch_of<'a>(changes: &'a [AgentChanges], agent_id: &'a str) -> &'a str {
+    changes
+        .iter()
+        .find(|c| c.agent_id == agent_id)
+        .map(|c| c.branch.as_str())
+        .unwrap_or(agent_id)
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(git::get_common_dir()?.join("sprite").join(STATE_FILE))
+}
+
+fn load_state() -> Option<OverlapState> {
+    let content = std::fs::read_to_string(state_path().ok()?).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+/// Remember the overlap count; failures only cost a repeated warning
+fn save_state(state: &OverlapState) {
+    let path = match state_path() {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_yaml::to_string(state) {
+        let _ = std::fs::write(path, content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(agent_id: &str, files: &[(&str, &[(u32, u32)])]) -> AgentChanges {
+        AgentChanges {
+            agent_id: agent_id.to_string(),
+            branch: format!("agents/{}", agent_id),
+            files: files
+                .iter()
+                .map(|(path, ranges)| {
+                    (
+                        path.to_string(),
+                        ranges
+                            .iter()
+                            .map(|&(start, end)| LineRange { start, end })
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_hunks() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 111..222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,3 +10,4 @@ fn main() {
+--- a removed SQL comment
++++ an added line
+@@ -40,0 +42,2 @@
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,5 @@
+";
+        let hunks = parse_hunks(diff);
+        assert_eq!(
+            hunks["src/lib.rs"],
+            vec![
+                LineRange { start: 10, end: 13 },
+                LineRange { start: 40, end: 41 }
+            ]
+        );
+        assert_eq!(hunks["old.txt"], vec![LineRange { start: 1, end: 2 }]);
+        assert_eq!(hunks["new.txt"], vec![LineRange { start: 0, end: 1 }]);
+    }
+
+    #[test]
+    fn test_parse_hunks_with_quoted_paths() {
+        let diff = "\
+diff --git a/my file.txt b/my file.txt
+--- a/my file.txt\t
++++ b/my file.txt\t
+@@ -2 +2 @@
+diff --git \"a/dir/na\\303\\257ve.rs\" \"b/dir/na\\303\\257ve.rs\"
+--- \"a/dir/na\\303\\257ve.rs\"
++++ \"b/dir/na\\303\\257ve.rs\"
+@@ -7,2 +7,3 @@
+";
+        let hunks = parse_hunks(diff);
+        assert_eq!(hunks["my file.txt"], vec![LineRange { start: 2, end: 3 }]);
+        assert_eq!(hunks["dir/naïve.rs"], vec![LineRange { start: 7, end: 9 }]);
+    }
+
+    #[test]
+    fn test_find_overlaps() {
+        let agents = vec![
+            changes(
+                "1",
+                &[("src/lib.rs", &[(10, 20)]), ("README.md", &[(1, 2)])],
+            ),
+            changes(
+                "2",
+                &[("src/lib.rs", &[(15, 16)]), ("README.md", &[(50, 51)])],
+            ),
+            changes("3", &[("docs/guide.md", &[(1, 5)])]),
+        ];
+
+        let overlaps = find_overlaps(&agents);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].first, "1");
+        assert_eq!(overlaps[0].second, "2");
+        assert_eq!(
+            overlaps[0].files,
+            vec![
+                SharedFile {
+                    path: "README.md".to_string(),
+                    overlapping_hunks: false
+                },
+                SharedFile {
+                    path: "src/lib.rs".to_string(),
+                    overlapping_hunks: true
+                },
+            ]
+        );
+        assert_eq!(count_shared_files(&overlaps), 2);
+    }
+}
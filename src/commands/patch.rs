@@ -0,0 +1,375 @@
+//! Patch command - Carry work from one agent's worktree to another's
+//!
+//! The patch is a binary-safe `git diff` of the source agent's uncommitted work
+//! (untracked files included) or of a commit range, applied to the target's files
+//! one file at a time. A file that no longer applies cleanly is merged three-way
+//! against the version the patch was made from, so a conflict only leaves markers
+//! in that file. The target's index and HEAD are never touched.
+
+use crate::cli::PatchCommands;
+use crate::commands::checkpoint;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::git;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Result of carrying one file over
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    /// The file's patch applied cleanly
+    Applied,
+    /// The file had diverged and merged three-way without conflicts
+    Merged,
+    /// The target already has the patched content
+    AlreadyApplied,
+    /// The merge left conflict markers in this many regions
+    Conflict { regions: usize },
+    /// The file was left alone
+    Skipped { reason: String },
+}
+
+impl Outcome {
+    /// One-line summary, phrased for a dry run or a real run
+    fn describe(&self, dry_run: bool) -> String {
+        match (self, dry_run) {
+            (Outcome::Applied, false) => "✅ applied".to_string(),
+            (Outcome::Applied, true) => "✅ applies cleanly".to_string(),
+            (Outcome::Merged, false) => "🔀 merged three-way".to_string(),
+            (Outcome::Merged, true) => "🔀 needs a three-way merge (no conflicts)".to_string(),
+            (Outcome::AlreadyApplied, _) => "✅ already up to date".to_string(),
+            (Outcome::Conflict { regions }, false) => {
+                format!(
+                    "❌ conflicts in {} region(s), markers left in the file",
+                    regions
+                )
+            }
+            (Outcome::Conflict { regions }, true) => {
+                format!("❌ would conflict in {} region(s)", regions)
+            }
+            (Outcome::Skipped { reason }, _) => format!("⏭️  skipped ({})", reason),
+        }
+    }
+}
+
+/// Options for `sprite patch send`
+pub struct SendOptions<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    /// Limit the patch to these paths, relative to the worktree root
+    pub paths: &'a [String],
+    /// Send the changes of a commit range instead of the uncommitted work
+    pub range: Option<&'a str>,
+    pub dry_run: bool,
+}
+
+/// Execute patch command with subcommands
+pub fn execute(command: PatchCommands) -> Result<()> {
+    match command {
+        PatchCommands::Send {
+            from,
+            to,
+            paths,
+            range,
+            dry_run,
+        } => send_patch(&SendOptions {
+            from: &from,
+            to: &to,
+            paths: &paths,
+            range: range.as_deref(),
+            dry_run,
+        }),
+    }
+}
+
+/// Build the patch from one agent and apply it to another
+fn send_patch(options: &SendOptions) -> Result<()> {
+    if options.from == options.to {
+        return Err(SpriteError::validation(
+            "Source and target agent must differ",
+            Some("to"),
+            Some(options.to),
+        )
+        .into());
+    }
+
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let (source, source_dir) = agent_worktree(&config, &root, options.from)?;
+    let (target, target_dir) = agent_worktree(&config, &root, options.to)?;
+
+    let (base, new, what) = match options.range {
+        Some(range) => {
+            let (from, to) = parse_range(range)?;
+            (
+                git::rev_parse_at(&source_dir, &from)?,
+                git::rev_parse_at(&source_dir, &to)?,
+                format!("commits {}", range),
+            )
+        }
+        None => (
+            git::get_head_commit_at(&source_dir)?,
+            git::snapshot_worktree_at(&source_dir)?,
+            "uncommitted work".to_string(),
+        ),
+    };
+
+    let files = git::diff_name_status_at(&source_dir, &base, &new, options.paths)?;
+    if files.is_empty() {
+        println!("📭 Agent {} has no changes to send ({}).", source.id, what);
+        return Ok(());
+    }
+
+    println!(
+        "📦 {} {} file(s) of agent {}'s {} to agent {}",
+        if options.dry_run {
+            "Dry run: would send"
+        } else {
+            "Sending"
+        },
+        files.len(),
+        source.id,
+        what,
+        target.id
+    );
+
+    // Keep the target's current state so the whole transfer can be undone
+    let backup = if options.dry_run {
+        None
+    } else {
+        checkpoint::checkpoint_agent(
+            &root,
+            &target,
+            Some(&format!("before patch from agent {}", source.id)),
+            usize::MAX,
+            true,
+        )?
+    };
+
+    let mut results = Vec::new();
+    for (status, file) in &files {
+        let outcome = send_file(
+            &source_dir,
+            &target_dir,
+            (&base, &new),
+            (&source.id, &target.id),
+            *status,
+            file,
+            options.dry_run,
+        )?;
+        println!("  {}  {}", file, outcome.describe(options.dry_run));
+        results.push((file.clone(), outcome));
+    }
+
+    let conflicted: Vec<&String> = results
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Conflict { .. }))
+        .map(|(file, _)| file)
+        .collect();
+    let skipped = results
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Skipped { .. }))
+        .count();
+    let changed = results
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Applied | Outcome::Merged))
+        .count();
+
+    println!();
+    if options.dry_run {
+        println!(
+            "🔍 {} file(s) would change, {} would conflict, {} would be skipped. Nothing was written.",
+            changed,
+            conflicted.len(),
+            skipped
+        );
+        return Ok(());
+    }
+
+    if let Some(backup) = &backup {
+        println!(
+            "💡 Agent {}'s previous state is checkpoint {}: 'sprite checkpoint restore {} {}' undoes this.",
+            target.id, backup, target.id, backup
+        );
+    }
+    if conflicted.is_empty() {
+        println!(
+            "✅ Sent agent {}'s changes to agent {}: {} file(s) changed, {} skipped",
+            source.id, target.id, changed, skipped
+        );
+        return Ok(());
+    }
+
+    println!(
+        "⚠️  Resolve the conflict markers in {}:",
+        target_dir.display()
+    );
+    for file in &conflicted {
+        println!("     {}", file);
+    }
+    Err(SpriteError::git(format!(
+        "Patch from agent {} conflicted in {} file(s) of agent {}",
+        source.id,
+        conflicted.len(),
+        target.id
+    ))
+    .into())
+}
+
+/// Carry one file's change into the target worktree.
+///
+/// `revisions` are the patch's base and new trees in the source worktree;
+/// `agents` are the source and target IDs, used to label conflict markers.
+fn send_file(
+    source_dir: &Path,
+    target_dir: &Path,
+    revisions: (&str, &str),
+    agents: (&str, &str),
+    status: char,
+    file: &str,
+    dry_run: bool,
+) -> Result<Outcome> {
+    let (base, new) = revisions;
+    let theirs = git::show_file_at(source_dir, new, file)?;
+    let target_file = target_dir.join(file);
+    let ours = match std::fs::read(&target_file) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            return Err(SpriteError::io(format!("read {}", target_file.display()), e).into());
+        }
+    };
+    if ours == theirs {
+        return Ok(Outcome::AlreadyApplied);
+    }
+
+    let patch = git::binary_diff_at(source_dir, base, new, &[format!(":(literal){}", file)])?;
+    if git::apply_patch_at(target_dir, &patch, true)? {
+        if !dry_run && !git::apply_patch_at(target_dir, &patch, false)? {
+            return Err(SpriteError::git(format!("Failed to apply the patch to {}", file)).into());
+        }
+        return Ok(Outcome::Applied);
+    }
+
+    // The target's copy diverged from the patch's base: fall back to a three-way merge
+    let (ours, theirs) = match (ours, theirs) {
+        (Some(ours), Some(theirs)) => (ours, theirs),
+        (None, _) => {
+            return Ok(Outcome::Skipped {
+                reason: format!("missing in agent {}", agents.1),
+            })
+        }
+        (Some(_), None) => {
+            return Ok(Outcome::Skipped {
+                reason: format!(
+                    "deleted by agent {} but changed in agent {}",
+                    agents.0, agents.1
+                ),
+            })
+        }
+    };
+    // A file added on both sides merges against an empty base
+    let base_content = match status {
+        'A' => Vec::new(),
+        _ => git::show_file_at(source_dir, base, file)?.unwrap_or_default(),
+    };
+
+    let labels = [
+        format!("agent {}", agents.1),
+        "patch base".to_string(),
+        format!("agent {}", agents.0),
+    ];
+    let (merged, regions) = match git::merge_file_contents(
+        &ours,
+        &base_content,
+        &theirs,
+        [&labels[0], &labels[1], &labels[2]],
+    ) {
+        Ok(result) => result,
+        Err(_) => {
+            return Ok(Outcome::Skipped {
+                reason: "binary file changed on both sides".to_string(),
+            })
+        }
+    };
+
+    if !dry_run {
+        std::fs::write(&target_file, merged)
+            .map_err(|e| SpriteError::io(format!("write {}", target_file.display()), e))?;
+    }
+    Ok(if regions == 0 {
+        Outcome::Merged
+    } else {
+        Outcome::Conflict { regions }
+    })
+}
+
+fn agent_worktree(
+    config: &SpriteConfig,
+    root: &Path,
+    agent_id: &str,
+) -> Result<(AgentConfig, PathBuf)> {
+    let agent = config
+        .get_agent(agent_id)
+        .ok_or_else(|| SpriteError::agent_not_found(agent_id))?
+        .clone();
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        return Err(SpriteError::agent(
+            format!(
+                "Agent {} has no worktree at {}",
+                agent.id, agent.worktree_path
+            ),
+            Some(&agent.id),
+        )
+        .into());
+    }
+    Ok((agent, workspace))
+}
+
+/// Split `--range` into the revisions to diff: `A..B`, `A..` (up to HEAD),
+/// or a single commit for just that commit's changes.
+fn parse_range(range: &str) -> Result<(String, String)> {
+    let invalid = || {
+        SpriteError::validation(
+            format!(
+                "Invalid --range '{}'. Use 'A..B', 'A..' or a single commit",
+                range
+            ),
+            Some("range"),
+            Some(range),
+        )
+    };
+
+    if range.contains("...") {
+        return Err(invalid().into());
+    }
+    match range.split_once("..") {
+        Some(("", _)) => Err(invalid().into()),
+        Some((from, to)) => Ok((
+            from.to_string(),
+            if to.is_empty() { "HEAD" } else { to }.to_string(),
+        )),
+        None if range.is_empty() => Err(invalid().into()),
+        None => Ok((format!("{}^", range), range.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        assert_eq!(parse_range("main..HEAD").unwrap(), pair("main", "HEAD"));
+        assert_eq!(parse_range("abc123..").unwrap(), pair("abc123", "HEAD"));
+        assert_eq!(parse_range("abc123").unwrap(), pair("abc123^", "abc123"));
+        assert!(parse_range("..HEAD").is_err());
+        assert!(parse_range("main...HEAD").is_err());
+        assert!(parse_range("").is_err());
+    }
+}
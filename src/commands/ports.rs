@@ -0,0 +1,100 @@
+//! Ports command - Show the port ranges allocated to agents
+//!
+//! Ranges are handed out by [`crate::utils::ports`]; listing them also gives any
+//! agent without one its range, so the table always covers every agent.
+
+use crate::commands::config::{PortsConfig, SpriteConfig};
+use crate::utils::ports::{self, PortAllocation, PortLock};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+
+/// Execute the ports command
+pub fn execute(all: bool) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    ports::allocate_project(&config).context("Failed to allocate agent ports")?;
+    let root = ports::project_root(&config)?;
+    let lock = PortLock::load()?;
+    let listening = ports::listening_ports();
+
+    let names: Vec<(String, u16)> = config
+        .ports
+        .named_ports()
+        .into_iter()
+        .filter(|(_, offset)| *offset < config.ports.range_size)
+        .collect();
+    println!(
+        "🔌 Agent ports ({} per agent from {})",
+        config.ports.range_size, config.ports.base
+    );
+    let mut header = format!("  {:<10} {:<13}", "AGENT", "RANGE");
+    for (name, _) in &names {
+        header.push_str(&format!(" {:<14}", PortsConfig::variable(name)));
+    }
+    println!("{}", header.trim_end());
+
+    for agent in &config.agents {
+        let allocation = match lock.find(&root, &agent.id) {
+            Some(allocation) => allocation,
+            None => continue,
+        };
+        let mut row = format!("  {:<10} {:<13}", agent.id, range(allocation));
+        for (_, offset) in &names {
+            row.push_str(&format!(
+                " {:<14}",
+                port_cell(allocation.base + *offset, &listening)
+            ));
+        }
+        println!("{}", row.trim_end());
+    }
+
+    let busy = config
+        .agents
+        .iter()
+        .filter_map(|agent| lock.find(&root, &agent.id))
+        .any(|allocation| {
+            names
+                .iter()
+                .any(|(_, offset)| listening.contains(&(allocation.base + *offset)))
+        });
+    if busy {
+        println!("   * something is listening on this port now");
+    }
+
+    if all {
+        let others: Vec<&PortAllocation> = lock
+            .allocations
+            .iter()
+            .filter(|allocation| allocation.root != root)
+            .collect();
+        println!();
+        if others.is_empty() {
+            println!("📭 No other project holds ports on this machine.");
+        } else {
+            println!("🗂️  Other projects on this machine:");
+            for allocation in others {
+                println!(
+                    "  {:<13} agent {:<6} {}",
+                    range(allocation),
+                    allocation.agent,
+                    allocation.root.display()
+                );
+            }
+        }
+    }
+
+    println!();
+    println!("💡 Agents get these as SPRITE_PORT_BASE and PORT_<NAME>; see 'sprite env <agent>'.");
+    Ok(())
+}
+
+fn range(allocation: &PortAllocation) -> String {
+    format!("{}-{}", allocation.base, allocation.last())
+}
+
+fn port_cell(port: u16, listening: &HashSet<u16>) -> String {
+    if listening.contains(&port) {
+        format!("{}*", port)
+    } else {
+        port.to_string()
+    }
+}
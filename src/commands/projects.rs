@@ -0,0 +1,111 @@
+//! Projects command - Overview of every Sprite project on this machine
+
+use crate::cli::ProjectsCommands;
+use crate::commands::config::SpriteConfig;
+use crate::utils::git;
+use crate::utils::registry::{ProjectEntry, ProjectRegistry};
+use crate::utils::tmux;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Execute projects command with subcommands
+pub fn execute(command: ProjectsCommands) -> Result<()> {
+    match command {
+        ProjectsCommands::List => list_projects(),
+    }
+}
+
+/// List registered projects with their sessions and agent health
+fn list_projects() -> Result<()> {
+    let registry = ProjectRegistry::load().context("Failed to load project registry")?;
+
+    if registry.projects.is_empty() {
+        println!("📭 No sprite projects registered yet.");
+        println!("💡 Run 'sprite init' or 'sprite start' inside a project to register it.");
+        return Ok(());
+    }
+
+    let current_root = crate::utils::project::find_project_root()
+        .ok()
+        .and_then(|root| std::fs::canonicalize(root).ok());
+
+    println!("📚 Sprite Projects ({}):", registry.projects.len());
+    println!();
+
+    for project in &registry.projects {
+        let marker = if current_root.as_ref() == Some(&project.root) {
+            " (current)"
+        } else {
+            ""
+        };
+        println!("  📁 {}{}", project.name, marker);
+        println!("     Root: {}", project.root.display());
+        print_project_details(project);
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Print session and agent health for a single project
+fn print_project_details(project: &ProjectEntry) {
+    let config_path = project.root.join("agents").join("agents.yaml");
+    if !config_path.exists() {
+        println!("     ⚠️  Configuration missing: {}", config_path.display());
+        return;
+    }
+
+    let config = match SpriteConfig::load_from_path(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("     ❌ Failed to load configuration: {}", e);
+            return;
+        }
+    };
+
+    let session_name = config.effective_session_name();
+    let session_state = match tmux::session_exists(&session_name) {
+        Ok(true) => {
+            let panes = tmux::get_session_panes_with_retry(&session_name, 1)
+                .map(|p| p.len())
+                .unwrap_or(0);
+            format!("🟢 running ({} panes)", panes)
+        }
+        Ok(false) => "⚪ not running".to_string(),
+        Err(_) => "❔ tmux unavailable".to_string(),
+    };
+    println!("     Session: {} {}", session_name, session_state);
+
+    println!("     Agents ({}):", config.agents.len());
+    for agent in &config.agents {
+        println!(
+            "       {} Agent {} [{}] {}",
+            agent_health_icon(&project.root, &agent.worktree_path, &agent.branch),
+            agent.id,
+            agent.status,
+            agent.branch
+        );
+    }
+}
+
+/// Summarize an agent's workspace health as a single icon
+fn agent_health_icon(root: &std::path::Path, worktree_path: &str, branch: &str) -> &'static str {
+    let workspace = {
+        let path = PathBuf::from(worktree_path);
+        if path.is_absolute() {
+            path
+        } else {
+            root.join(path)
+        }
+    };
+
+    if !workspace.exists() {
+        return "❌";
+    }
+
+    match git::get_current_branch_at(&workspace) {
+        Ok(current) if current == branch => "✅",
+        Ok(_) => "⚠️ ",
+        Err(_) => "❌",
+    }
+}
@@ -0,0 +1,388 @@
+//! Prompts command - Reusable prompt templates under agents/prompts
+//!
+//! A template is a Markdown file such as `agents/prompts/review.md` whose text may
+//! contain `{{name}}` placeholders. They are filled from `--var name=value` and from
+//! built-in variables describing the target agent (`agent_id`, `branch`, `commit`,
+//! `diff_stat`, ...), then sent with `sprite hey <agents> --template <name>`.
+
+use crate::cli::PromptsCommands;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::git;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A prompt template loaded from `agents/prompts/<name>.md`
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    /// Template name (file name without `.md`)
+    pub name: String,
+    /// File the template was read from
+    pub path: PathBuf,
+    /// Template text
+    pub body: String,
+}
+
+impl PromptTemplate {
+    /// Load a template by name.
+    pub fn load(config: &SpriteConfig, name: &str) -> Result<Self> {
+        validate_template_name(name)?;
+        let path = prompts_dir(config)?.join(format!("{}.md", name));
+        if !path.exists() {
+            let available: Vec<String> = list_templates(config)?
+                .into_iter()
+                .map(|template| template.name)
+                .collect();
+            let hint = if available.is_empty() {
+                "no templates exist yet".to_string()
+            } else {
+                format!("available: {}", available.join(", "))
+            };
+            return Err(SpriteError::validation(
+                format!(
+                    "Prompt template '{}' not found in {} ({})",
+                    name,
+                    path.parent().unwrap_or(&path).display(),
+                    hint
+                ),
+                Some("template"),
+                Some(name),
+            )
+            .into());
+        }
+
+        let body = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read prompt template {}", path.display()))?;
+        Ok(Self {
+            name: name.to_string(),
+            path,
+            body,
+        })
+    }
+
+    /// First non-empty line, without Markdown heading marks.
+    pub fn summary(&self) -> String {
+        self.body
+            .lines()
+            .map(|line| line.trim().trim_start_matches('#').trim())
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Names of the placeholders used in the template, in order of first use.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for (_, _, name) in placeholder_spans(&self.body) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Fill in every placeholder, failing if any is left without a value.
+    pub fn render(&self, vars: &BTreeMap<String, String>) -> Result<String> {
+        let missing: Vec<String> = self
+            .placeholders()
+            .into_iter()
+            .filter(|name| !vars.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(SpriteError::validation(
+                format!(
+                    "Prompt template '{}' needs a value for {} (pass --var {}=...)",
+                    self.name,
+                    missing.join(", "),
+                    missing[0]
+                ),
+                Some("var"),
+                Some(missing.join(",")),
+            )
+            .into());
+        }
+
+        let mut rendered = String::with_capacity(self.body.len());
+        let mut last = 0;
+        for (start, end, name) in placeholder_spans(&self.body) {
+            rendered.push_str(&self.body[last..start]);
+            rendered.push_str(&vars[name]);
+            last = end;
+        }
+        rendered.push_str(&self.body[last..]);
+        Ok(rendered)
+    }
+
+    /// Render the template for one agent, with `vars` taking precedence over built-ins.
+    pub fn render_for_agent(
+        &self,
+        config: &SpriteConfig,
+        agent: &AgentConfig,
+        vars: &BTreeMap<String, String>,
+    ) -> Result<String> {
+        let mut all = agent_variables(config, agent, &self.placeholders());
+        all.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.render(&all)
+    }
+}
+
+/// Execute prompts command with subcommands
+pub fn execute(command: PromptsCommands) -> Result<()> {
+    match command {
+        PromptsCommands::List => list_prompts(),
+        PromptsCommands::Show { name, agent, vars } => show_prompt(&name, agent.as_deref(), &vars),
+    }
+}
+
+/// Parse `--var` values in KEY=VALUE format.
+pub fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut parsed = BTreeMap::new();
+    for var in vars {
+        match var.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                parsed.insert(key.trim().to_string(), value.to_string());
+            }
+            _ => {
+                return Err(SpriteError::validation(
+                    format!("Invalid template variable '{}'. Expected KEY=VALUE", var),
+                    Some("var"),
+                    Some(var.clone()),
+                )
+                .into())
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Directory that holds the project's prompt templates.
+pub fn prompts_dir(config: &SpriteConfig) -> Result<PathBuf> {
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    Ok(root.join("agents").join("prompts"))
+}
+
+/// Load every template in the prompts directory, sorted by name.
+pub fn list_templates(config: &SpriteConfig) -> Result<Vec<PromptTemplate>> {
+    let dir = prompts_dir(config)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+
+    names
+        .iter()
+        .map(|name| PromptTemplate::load(config, name))
+        .collect()
+}
+
+/// Built-in variables for an agent. Git-derived values are only computed when `needed`.
+///
+/// Provides `agent_id`, `branch`, `description`, `model`, `worktree`, `session`,
+/// `project`, `base`, `commit`, `short_commit` and `diff_stat`.
+pub fn agent_variables(
+    config: &SpriteConfig,
+    agent: &AgentConfig,
+    needed: &[String],
+) -> BTreeMap<String, String> {
+    let root = config.project_root().unwrap_or_default();
+    let mut vars = BTreeMap::new();
+    vars.insert("agent_id".to_string(), agent.id.clone());
+    vars.insert("branch".to_string(), agent.branch.clone());
+    vars.insert("description".to_string(), agent.description.clone());
+    vars.insert("model".to_string(), agent.model.clone());
+    vars.insert("worktree".to_string(), agent.worktree_path.clone());
+    vars.insert("session".to_string(), config.effective_session_name());
+    // The trunk, which `diff_stat` is measured against
+    let base = config.trunk();
+    vars.insert("base".to_string(), base.clone());
+    if let Some(name) = root.file_name() {
+        vars.insert("project".to_string(), name.to_string_lossy().to_string());
+    }
+
+    let needs = |name: &str| needed.iter().any(|n| n == name);
+    if needs("commit") || needs("short_commit") {
+        if let Some(commit) = agent_commit(&root, agent) {
+            vars.insert("short_commit".to_string(), commit.chars().take(7).collect());
+            vars.insert("commit".to_string(), commit);
+        }
+    }
+    if needs("diff_stat") {
+        if let Ok(stat) = git::diff_stat(&base, &agent.branch) {
+            let stat = if stat.is_empty() {
+                format!("(no changes against {})", base)
+            } else {
+                stat
+            };
+            vars.insert("diff_stat".to_string(), stat);
+        }
+    }
+
+    vars
+}
+
+/// The commit an agent is on: its worktree HEAD, or the tip of its branch.
+fn agent_commit(root: &Path, agent: &AgentConfig) -> Option<String> {
+    let workspace = root.join(&agent.worktree_path);
+    if workspace.exists() {
+        if let Ok(commit) = git::get_head_commit_at(&workspace) {
+            return Some(commit);
+        }
+    }
+    git::get_branch_commit(&agent.branch)
+        .or_else(|_| git::get_current_commit())
+        .ok()
+}
+
+/// Byte ranges and names of the `{{name}}` placeholders in `text`.
+fn placeholder_spans(text: &str) -> Vec<(usize, usize, &str)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = text[offset..].find("{{") {
+        let start = offset + open;
+        let close = match text[start + 2..].find("}}") {
+            Some(close) => close,
+            None => break,
+        };
+        let end = start + 2 + close + 2;
+        let name = text[start + 2..end - 2].trim();
+        if !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        {
+            spans.push((start, end, name));
+            offset = end;
+        } else {
+            offset = start + 2;
+        }
+    }
+    spans
+}
+
+/// List the available templates with their placeholders
+fn list_prompts() -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let templates = list_templates(&config)?;
+
+    if templates.is_empty() {
+        println!("📭 No prompt templates yet.");
+        println!(
+            "💡 Add Markdown files with {{{{var}}}} placeholders to {}",
+            prompts_dir(&config)?.display()
+        );
+        return Ok(());
+    }
+
+    println!("📝 Prompt Templates ({}):", templates.len());
+    for template in &templates {
+        println!("  • {} - {}", template.name, template.summary());
+        let placeholders = template.placeholders();
+        if !placeholders.is_empty() {
+            println!("    Variables: {}", placeholders.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a template, rendered for an agent when one is given
+fn show_prompt(name: &str, agent_id: Option<&str>, vars: &[String]) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let template = PromptTemplate::load(&config, name)?;
+
+    let agent_id = match agent_id {
+        Some(agent_id) => agent_id,
+        None => {
+            println!("📝 {} ({})", template.name, template.path.display());
+            let placeholders = template.placeholders();
+            if !placeholders.is_empty() {
+                println!("   Variables: {}", placeholders.join(", "));
+            }
+            println!();
+            println!("{}", template.body.trim_end());
+            return Ok(());
+        }
+    };
+
+    let agent = config
+        .get_agent(agent_id)
+        .ok_or_else(|| SpriteError::agent_not_found(agent_id))?;
+    let rendered = template.render_for_agent(&config, agent, &parse_vars(vars)?)?;
+    println!("📝 {} for agent {}", template.name, agent.id);
+    println!();
+    println!("{}", rendered.trim_end());
+    Ok(())
+}
+
+/// Template names are plain file names, so they cannot reach outside the prompts directory
+fn validate_template_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) || name.contains("..")
+    {
+        return Err(SpriteError::validation(
+            format!(
+                "Invalid prompt template name '{}'. Use the file name without '.md'",
+                name
+            ),
+            Some("template"),
+            Some(name),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(body: &str) -> PromptTemplate {
+        PromptTemplate {
+            name: "review".to_string(),
+            path: PathBuf::from("agents/prompts/review.md"),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let template = template(
+            "# Review {{ branch }}\n\nFocus on {{area}}; {{area}} only. Keep {{ not a var }} and {{",
+        );
+        assert_eq!(template.placeholders(), vec!["branch", "area"]);
+        assert_eq!(template.summary(), "Review {{ branch }}");
+
+        let vars = parse_vars(&["area=auth".to_string(), "branch=agents/3".to_string()]).unwrap();
+        assert_eq!(
+            template.render(&vars).unwrap(),
+            "# Review agents/3\n\nFocus on auth; auth only. Keep {{ not a var }} and {{"
+        );
+
+        let missing = template
+            .render(&parse_vars(&["branch=x".to_string()]).unwrap())
+            .unwrap_err();
+        assert!(missing.to_string().contains("area"));
+        assert!(parse_vars(&["=oops".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_template_names_stay_in_prompts_dir() {
+        assert!(validate_template_name("review").is_ok());
+        assert!(validate_template_name("fix-tests_v2").is_ok());
+        assert!(validate_template_name("../../x").is_err());
+        assert!(validate_template_name("sub/review").is_err());
+        assert!(validate_template_name("sub\\review").is_err());
+        assert!(validate_template_name("..").is_err());
+        assert!(validate_template_name("").is_err());
+    }
+}
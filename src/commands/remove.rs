@@ -1,10 +1,6 @@
-use crate::config::{load_default_config, save_default_config};
+use crate::commands::config::{AgentConfig, SpriteConfig};
 use crate::error::SpriteError;
-use crate::models::Agent;
-use crate::utils::git::{
-    self, delete_branch, has_merge_conflicts, has_uncommitted_changes, merge_branch,
-    remove_worktree,
-};
+use crate::utils::git::{self, delete_branch, remove_worktree};
 use crate::utils::tmux;
 use anyhow::{Context, Result};
 use std::io::{self, Write};
@@ -21,53 +17,51 @@ pub fn execute(
     git::validate_git_repository().context("Must be run from within a git repository")?;
 
     // Load configuration
-    let mut config =
-        load_default_config().context("Failed to load configuration. Run 'sprite init' first.")?;
+    let config =
+        SpriteConfig::load().context("Failed to load configuration. Run 'sprite init' first.")?;
 
     // Find the agent to remove
-    let agent_index = config
-        .agents
-        .iter()
-        .position(|a| a.id == agent_id)
-        .ok_or_else(|| {
-            SpriteError::agent(
-                format!(
-                    "Agent '{}' not found. Use 'sprite agents list' to see available agents.",
-                    agent_id
-                ),
-                Some(agent_id),
-            )
-        })?;
-
-    let agent = &config.agents[agent_index];
+    let agent = config.get_agent(agent_id).cloned().ok_or_else(|| {
+        SpriteError::agent(
+            format!(
+                "Agent '{}' not found. Use 'sprite agents list' to see available agents.",
+                agent_id
+            ),
+            Some(agent_id),
+        )
+    })?;
+    let workspace_path = config
+        .project_root()
+        .unwrap_or_default()
+        .join(&agent.worktree_path);
+    let trunk = config.trunk();
 
     // Safety checks
     if !force {
-        perform_safety_checks(agent)?;
+        perform_safety_checks(&agent, &workspace_path, &trunk)?;
     }
 
     // Check if agent is currently running in any tmux session
-    check_agent_not_running(agent)?;
+    check_agent_not_running(&agent.id, &workspace_path)?;
 
     // Merge branch if requested
     if merge_branch {
         println!(
-            "🔄 Merging agent branch '{}' before removal...",
-            agent.branch
+            "🔄 Merging agent branch '{}' into {} before removal...",
+            agent.branch, trunk
         );
-        merge_agent_branch(agent)?;
+        merge_agent_branch(&agent, &trunk)?;
         println!("✅ Branch merged successfully");
     }
 
     // Remove worktree if it exists
-    let workspace_path = agent.workspace_path();
     if workspace_path.exists() {
         if !keep_workspace {
             println!(
                 "🗑️  Removing agent workspace at {}...",
                 workspace_path.display()
             );
-            remove_agent_worktree(workspace_path)?;
+            remove_agent_worktree(&workspace_path)?;
             println!("✅ Workspace removed");
         } else {
             println!("📂 Keeping workspace files as requested");
@@ -83,29 +77,32 @@ pub fn execute(
             if !force && !prompt_for_branch_deletion(&agent.branch)? {
                 println!("📂 Keeping git branch '{}' as requested", agent.branch);
             } else {
-                delete_agent_branch(&agent.branch, force)?;
+                delete_agent_branch(&agent.branch, &trunk, force)?;
                 println!("✅ Branch '{}' removed", agent.branch);
             }
         } else {
             // Branch was already merged, safe to delete
-            delete_agent_branch(&agent.branch, false)?;
+            delete_agent_branch(&agent.branch, &trunk, false)?;
             println!("✅ Merged branch '{}' removed", agent.branch);
         }
     } else {
         println!("ℹ️  No git branch '{}' found", agent.branch);
     }
 
-    // Remove agent from configuration
-    config.agents.remove(agent_index);
-    save_default_config(&config).context("Failed to save updated configuration")?;
+    // Remove agent from the project file, leaving local and user overrides alone
+    let mut project = SpriteConfig::load_project().context("Failed to load configuration")?;
+    project.agents.retain(|a| a.id != agent_id);
+    project
+        .save()
+        .context("Failed to save updated configuration")?;
 
     println!("✅ Agent '{}' removed successfully", agent_id);
 
     // Show remaining agents
-    if !config.agents.is_empty() {
+    if !project.agents.is_empty() {
         println!("\n📋 Remaining agents:");
-        for remaining_agent in &config.agents {
-            let status = if remaining_agent.is_active() {
+        for remaining_agent in &project.agents {
+            let status = if remaining_agent.status.eq_ignore_ascii_case("active") {
                 "🟢 Active"
             } else {
                 "⚪ Inactive"
@@ -122,13 +119,45 @@ pub fn execute(
     Ok(())
 }
 
+/// Work that removing an agent now would lose
+#[derive(Debug, Clone, PartialEq)]
+pub enum SafetyIssue {
+    /// The workspace has uncommitted or untracked changes
+    UncommittedChanges,
+    /// The branch has commits that are not on the trunk
+    UnmergedBranch,
+}
+
+/// Check an agent's workspace and branch for work that removal would lose.
+///
+/// Merge status is checked against `trunk` with `git merge-base --is-ancestor`
+/// without switching any checkout, so this is safe to call while agents are running.
+pub fn check_removal_safety(
+    workspace_path: &Path,
+    branch: &str,
+    trunk: &str,
+) -> Result<Vec<SafetyIssue>> {
+    let mut issues = Vec::new();
+
+    if workspace_path.exists() && !git::get_status_string_at(workspace_path)?.is_empty() {
+        issues.push(SafetyIssue::UncommittedChanges);
+    }
+
+    if git::branch_exists(branch)? && !git::is_ancestor(branch, trunk)? {
+        issues.push(SafetyIssue::UnmergedBranch);
+    }
+
+    Ok(issues)
+}
+
 /// Perform comprehensive safety checks before agent removal.
-fn perform_safety_checks(agent: &Agent) -> Result<()> {
+fn perform_safety_checks(agent: &AgentConfig, workspace_path: &Path, trunk: &str) -> Result<()> {
     println!("🔍 Performing safety checks for agent '{}'...", agent.id);
 
+    let issues = check_removal_safety(workspace_path, &agent.branch, trunk)?;
+
     // Check for uncommitted changes in workspace
-    let workspace_path = agent.workspace_path();
-    if workspace_path.exists() && has_uncommitted_changes(workspace_path)? {
+    if issues.contains(&SafetyIssue::UncommittedChanges) {
         return Err(SpriteError::agent(
             format!(
                 "Agent '{}' has uncommitted changes in workspace '{}'. \
@@ -142,33 +171,19 @@ fn perform_safety_checks(agent: &Agent) -> Result<()> {
     }
 
     // Check if branch has unmerged changes
-    if git::branch_exists(&agent.branch)? {
-        let current_branch = git::get_current_branch()?;
-
-        // Switch to main branch to check merge status
-        if current_branch != agent.branch {
-            git::switch_branch("main")?;
-        }
-
-        if !git::is_branch_merged(&agent.branch)? {
-            println!(
-                "⚠️  Warning: Branch '{}' may have unmerged changes.",
-                agent.branch
-            );
-            println!("   Consider using --merge-branch to merge before removal.");
-
-            if !confirm_proceed_with_unmerged()? {
-                return Err(SpriteError::agent(
-                    "Agent removal cancelled by user due to unmerged changes".to_string(),
-                    Some(&agent.id),
-                )
-                .into());
-            }
-        }
+    if issues.contains(&SafetyIssue::UnmergedBranch) {
+        println!(
+            "⚠️  Warning: Branch '{}' has commits that are not on {}.",
+            agent.branch, trunk
+        );
+        println!("   Consider using --merge-branch to merge before removal.");
 
-        // Return to original branch
-        if current_branch != agent.branch && current_branch != "main" {
-            git::switch_branch(&current_branch)?;
+        if !confirm_proceed_with_unmerged()? {
+            return Err(SpriteError::agent(
+                "Agent removal cancelled by user due to unmerged changes".to_string(),
+                Some(&agent.id),
+            )
+            .into());
         }
     }
 
@@ -185,7 +200,7 @@ fn perform_safety_checks(agent: &Agent) -> Result<()> {
 }
 
 /// Check if agent is currently running in any tmux session.
-fn check_agent_not_running(agent: &Agent) -> Result<()> {
+fn check_agent_not_running(agent_id: &str, workspace_path: &Path) -> Result<()> {
     let sessions = tmux::list_sessions().context("Failed to list tmux sessions")?;
 
     for session in sessions {
@@ -195,18 +210,16 @@ fn check_agent_not_running(agent: &Agent) -> Result<()> {
             // Check if this pane belongs to our agent by examining the current working directory
             let pane_cwd = tmux::get_pane_current_path(&session.name, &pane.id).unwrap_or_default();
 
-            if let Some(agent_workspace) = agent.worktree_path.as_ref() {
-                if pane_cwd == agent_workspace.to_string_lossy() {
-                    return Err(SpriteError::agent(
-                        format!(
-                            "Agent '{}' appears to be running in tmux session '{}' pane {}. \
-                            Stop the agent first with 'sprite kill' or detach from the session.",
-                            agent.id, session.name, pane.id
-                        ),
-                        Some(&agent.id),
-                    )
-                    .into());
-                }
+            if pane_cwd == workspace_path.to_string_lossy() {
+                return Err(SpriteError::agent(
+                    format!(
+                        "Agent '{}' appears to be running in tmux session '{}' pane {}. \
+                        Stop the agent first with 'sprite kill' or detach from the session.",
+                        agent_id, session.name, pane.id
+                    ),
+                    Some(agent_id),
+                )
+                .into());
             }
         }
     }
@@ -238,8 +251,12 @@ fn remove_agent_worktree(workspace_path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Merge agent branch into main branch.
-fn merge_agent_branch(agent: &Agent) -> Result<()> {
+/// Merge the agent branch into the trunk without switching any checkout.
+///
+/// A branch already on the trunk needs nothing. Otherwise the merge happens in
+/// the worktree that has the trunk checked out, which must be clean; when no
+/// worktree has it, the trunk can only be fast-forwarded to the agent branch.
+fn merge_agent_branch(agent: &AgentConfig, trunk: &str) -> Result<()> {
     if !git::branch_exists(&agent.branch)? {
         println!(
             "ℹ️  Branch '{}' does not exist, skipping merge",
@@ -247,19 +264,53 @@ fn merge_agent_branch(agent: &Agent) -> Result<()> {
         );
         return Ok(());
     }
+    if !git::branch_exists(trunk)? {
+        return Err(SpriteError::git(format!(
+            "Trunk branch '{}' does not exist; set it with 'sprite config set project.trunk <branch>'",
+            trunk
+        ))
+        .into());
+    }
+    if git::is_ancestor(&agent.branch, trunk)? {
+        println!("ℹ️  Branch '{}' is already on {}", agent.branch, trunk);
+        return Ok(());
+    }
 
-    // Get current branch to restore later
-    let original_branch = git::get_current_branch()?;
-
-    // Switch to main branch
-    git::switch_branch("main").context("Failed to switch to main branch for merging")?;
+    let checkout = match git::find_worktree_for_branch(trunk)? {
+        Some(checkout) => checkout,
+        None if git::is_ancestor(trunk, &agent.branch)? => {
+            // Nothing has the trunk checked out, so moving it touches no files
+            return git::update_ref_at(
+                Path::new("."),
+                &format!("refs/heads/{}", trunk),
+                &agent.branch,
+            );
+        }
+        None => {
+            return Err(SpriteError::git(format!(
+                "'{}' cannot be fast-forwarded to '{}' and is not checked out anywhere to merge in; \
+                check it out or collect the branch with 'sprite integrate'",
+                trunk, agent.branch
+            ))
+            .into());
+        }
+    };
 
-    // Perform the merge
-    merge_branch(&agent.branch).context("Failed to merge agent branch")?;
+    if !git::get_status_string_at(&checkout)?.is_empty() {
+        return Err(SpriteError::git(format!(
+            "{} has uncommitted changes at {}; commit or stash them before merging into it",
+            trunk,
+            checkout.display()
+        ))
+        .into());
+    }
 
-    // Check for conflicts
-    if has_merge_conflicts()? {
-        println!("⚠️  Merge conflicts detected. Please resolve them manually:");
+    let message = format!("Merge branch '{}' into {}", agent.branch, trunk);
+    if !git::merge_branch_at(&checkout, &agent.branch, &message)? {
+        println!(
+            "⚠️  Merge conflicts detected in {}. Please resolve them manually:",
+            checkout.display()
+        );
         println!("   1. Run 'git status' to see conflicted files");
         println!("   2. Edit files to resolve conflicts");
         println!("   3. Run 'git add' on resolved files");
@@ -276,50 +327,25 @@ fn merge_agent_branch(agent: &Agent) -> Result<()> {
         .into());
     }
 
-    // Return to original branch if it wasn't the agent branch
-    if original_branch != agent.branch && original_branch != "main" {
-        git::switch_branch(&original_branch)?;
-    }
-
     Ok(())
 }
 
-/// Delete agent branch with appropriate safety checks.
-fn delete_agent_branch(branch: &str, force: bool) -> Result<()> {
+/// Delete agent branch, unless it has commits that are not on the trunk and `force` is off.
+fn delete_agent_branch(branch: &str, trunk: &str, force: bool) -> Result<()> {
     if !git::branch_exists(branch)? {
         return Ok(());
     }
 
-    if force {
-        delete_branch(branch, true)?;
-    } else {
-        // Safe deletion - check if merged first
-        let current_branch = git::get_current_branch()?;
-
-        // Temporarily switch to main to check merge status
-        if current_branch != "main" {
-            git::switch_branch("main")?;
-        }
-
-        if git::is_branch_merged(branch)? {
-            delete_branch(branch, false)?;
-        } else {
-            println!(
-                "⚠️  Branch '{}' has unmerged changes. Use --force to delete anyway.",
-                branch
-            );
-            return Err(
-                SpriteError::git(format!("Branch '{}' has unmerged changes", branch)).into(),
-            );
-        }
-
-        // Return to original branch
-        if current_branch != "main" {
-            git::switch_branch(&current_branch)?;
-        }
+    if !force && !git::is_ancestor(branch, trunk)? {
+        println!(
+            "⚠️  Branch '{}' has commits that are not on {}. Use --force to delete anyway.",
+            branch, trunk
+        );
+        return Err(SpriteError::git(format!("Branch '{}' has unmerged changes", branch)).into());
     }
 
-    Ok(())
+    // Merged into the trunk is what counts; `git branch -d` would compare with HEAD
+    delete_branch(branch, true)
 }
 
 /// Prompt user to confirm proceeding with unmerged changes.
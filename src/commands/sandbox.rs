@@ -0,0 +1,604 @@
+//! Sandbox command - Confine agent pane shells with Linux namespaces
+//!
+//! An agent with `sandbox.enabled` gets its pane shell started under bubblewrap
+//! or `unshare` instead of directly. Inside, the agent's worktree, the build cache
+//! and just enough of the shared git directory to commit (its objects, refs, logs
+//! and the agent's own `worktrees/<name>`) are writable; the rest of the git
+//! directory, including every config file git reads, the main repository and any
+//! `read_only` paths can only be read; `$HOME` is a private, per-agent directory;
+//! `/tmp` is private, hiding the tmux socket; and with `network: false` only
+//! loopback exists.
+//!
+//! Bubblewrap also makes the rest of the filesystem read-only; the `unshare`
+//! fallback leaves other paths as writable as they are for the user running sprite.
+//! It maps the user to themself rather than root and drops every capability
+//! before starting the shell, so the mounts cannot be undone from inside.
+
+use crate::cli::SandboxCommands;
+use crate::commands::cache;
+use crate::commands::config::{AgentConfig, SandboxBackend, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::{git, secrets, tmux};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// `setpriv` command dropping every capability the `unshare` backend's shell would keep
+const DROP_CAPS: [&str; 5] = [
+    "setpriv",
+    "--inh-caps=-all",
+    "--ambient-caps=-all",
+    "--bounding-set=-all",
+    "--no-new-privs",
+];
+
+/// Everything a sandboxed shell is given access to
+#[derive(Debug, Clone, PartialEq)]
+struct Layout {
+    /// The agent's worktree, writable
+    workspace: PathBuf,
+    /// The main repository, read-only
+    root: PathBuf,
+    /// The git directory all worktrees share, read-only
+    git_dir: PathBuf,
+    /// Parts of the git directory the agent needs to commit, writable
+    git_writable: Vec<PathBuf>,
+    /// Files inside writable paths that stay read-only (the worktree's config and links)
+    protected: Vec<PathBuf>,
+    /// Shared build cache directory, writable
+    cache: Option<PathBuf>,
+    /// The user's home directory, hidden behind `private_home`
+    home: PathBuf,
+    private_home: PathBuf,
+    /// Extra read-only paths
+    read_only: Vec<PathBuf>,
+    network: bool,
+    shell: String,
+}
+
+/// Execute sandbox command with subcommands
+pub fn execute(command: SandboxCommands) -> Result<()> {
+    match command {
+        SandboxCommands::Check => check(),
+    }
+}
+
+/// Start an agent's pane shell inside its sandbox, if it has one enabled.
+///
+/// When the sandbox cannot be built the pane is left showing why instead of
+/// running an unconfined shell.
+pub fn launch(pane_id: &str, config: &SpriteConfig, agent: &AgentConfig) -> Result<bool> {
+    if !agent.config.sandbox.enabled {
+        return Ok(false);
+    }
+
+    let (command, workspace) = match pane_command(config, agent) {
+        Ok(command) => command,
+        Err(e) => {
+            let message = format!(
+                "❌ Agent {} was not started: its sandbox is unavailable: {}",
+                agent.id, e
+            );
+            let blocked = format!(
+                "printf '%s\\n' {}; exec tail -f /dev/null",
+                tmux::shell_quote(&message)
+            );
+            tmux::respawn_pane(
+                pane_id,
+                "/",
+                &format!("sh -c {}", tmux::shell_quote(&blocked)),
+            )?;
+            return Err(e);
+        }
+    };
+    tmux::respawn_pane(pane_id, &workspace.to_string_lossy(), &command)?;
+    Ok(true)
+}
+
+/// Shell command starting the agent's shell in its sandbox, and the directory it starts in
+fn pane_command(config: &SpriteConfig, agent: &AgentConfig) -> Result<(String, PathBuf)> {
+    let backend = resolve_backend(agent.config.sandbox.backend)?;
+    let layout = layout(config, agent)?;
+    std::fs::create_dir_all(&layout.private_home)
+        .map_err(|e| SpriteError::io(format!("create {}", layout.private_home.display()), e))?;
+
+    let args = match backend {
+        SandboxBackend::Unshare => unshare_args(&layout),
+        _ => bwrap_args(&layout),
+    };
+    let command = args
+        .iter()
+        .map(|arg| tmux::shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok((command, layout.workspace))
+}
+
+fn layout(config: &SpriteConfig, agent: &AgentConfig) -> Result<Layout> {
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let root = std::fs::canonicalize(&root).unwrap_or(root);
+    let workspace = root.join(&agent.worktree_path);
+    if !workspace.exists() {
+        return Err(SpriteError::agent(
+            format!(
+                "Agent {} has no worktree at {}",
+                agent.id, agent.worktree_path
+            ),
+            Some(&agent.id),
+        )
+        .into());
+    }
+    let git_dir = git::common_dir_at(&workspace)?;
+    let worktree_git_dir = git::git_dir_at(&workspace)?;
+    if worktree_git_dir == git_dir {
+        return Err(SpriteError::agent(
+            format!(
+                "Agent {}'s workspace {} is not a linked worktree",
+                agent.id, agent.worktree_path
+            ),
+            Some(&agent.id),
+        )
+        .into());
+    }
+    let home = std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| SpriteError::config("Cannot determine home directory (HOME is not set)"))?;
+    // The private /tmp would hide them, and a shared one exposes the tmux socket
+    if root.starts_with("/tmp") || home.starts_with("/tmp") {
+        return Err(SpriteError::config(
+            "Cannot sandbox agents when the project or home directory is under /tmp",
+        )
+        .into());
+    }
+
+    // Git reads config.worktree once extensions.worktreeConfig is on; it must exist
+    // to be mounted read-only, or the agent could create it in its writable git dir
+    let worktree_config = worktree_git_dir.join("config.worktree");
+    if !worktree_config.exists() {
+        std::fs::write(&worktree_config, "")
+            .map_err(|e| SpriteError::io(format!("create {}", worktree_config.display()), e))?;
+    }
+
+    let expand = |path: &str| match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => root.join(path),
+    };
+    let mut read_only: Vec<PathBuf> = agent
+        .config
+        .sandbox
+        .read_only
+        .iter()
+        .map(|path| expand(path))
+        .collect();
+    // Secret files are read by the agent's shell, inside the sandbox
+    let lock = crate::utils::ports::PortLock::load().unwrap_or_default();
+    for value in crate::commands::env::agent_env(config, agent, &lock).values() {
+        if let Some(Ok(secrets::Secret::File(path))) =
+            secrets::reference(value).map(secrets::Secret::parse)
+        {
+            read_only.push(expand(&path));
+        }
+    }
+    // The managed git hooks call back into sprite, which may be installed under $HOME
+    if let Some(dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        read_only.push(dir);
+    }
+
+    Ok(Layout {
+        git_writable: ["objects", "refs", "logs"]
+            .iter()
+            .map(|dir| git_dir.join(dir))
+            .filter(|dir| dir.exists())
+            .chain(std::iter::once(worktree_git_dir.clone()))
+            .collect(),
+        protected: vec![
+            worktree_config,
+            worktree_git_dir.join("commondir"),
+            worktree_git_dir.join("gitdir"),
+            workspace.join(".git"),
+        ],
+        private_home: git_dir.join("sprite").join("homes").join(&agent.id),
+        cache: cache::cache_dir(config).filter(|dir| dir.exists()),
+        shell: agent
+            .config
+            .shell
+            .clone()
+            .unwrap_or_else(|| config.settings.default_shell.clone()),
+        network: agent.config.sandbox.network,
+        workspace,
+        root,
+        git_dir,
+        home,
+        read_only,
+    })
+}
+
+/// The backend to use, checking the programs it needs are installed
+fn resolve_backend(requested: SandboxBackend) -> Result<SandboxBackend> {
+    let missing = |backend| {
+        requirements(backend)
+            .iter()
+            .find(|program| !cache::is_installed(program))
+            .copied()
+    };
+    match requested {
+        SandboxBackend::Auto if missing(SandboxBackend::Bwrap).is_none() => {
+            Ok(SandboxBackend::Bwrap)
+        }
+        SandboxBackend::Auto if missing(SandboxBackend::Unshare).is_none() => {
+            Ok(SandboxBackend::Unshare)
+        }
+        SandboxBackend::Auto => Err(SpriteError::config(
+            "Neither bwrap nor unshare and setpriv are installed; install bubblewrap to sandbox agents",
+        )
+        .into()),
+        backend => match missing(backend) {
+            None => Ok(backend),
+            Some(program) => {
+                Err(SpriteError::config(format!("{} is not installed", program)).into())
+            }
+        },
+    }
+}
+
+fn program(backend: SandboxBackend) -> &'static str {
+    requirements(backend)[0]
+}
+
+/// Programs a backend runs, its own first
+fn requirements(backend: SandboxBackend) -> &'static [&'static str] {
+    match backend {
+        SandboxBackend::Unshare => &["unshare", "setpriv"],
+        _ => &["bwrap"],
+    }
+}
+
+/// Bubblewrap command line; later mounts cover earlier ones
+fn bwrap_args(layout: &Layout) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "bwrap",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev-bind",
+        "/dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--unshare-all",
+        "--die-with-parent",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    if layout.network {
+        args.push("--share-net".to_string());
+    }
+    args.extend(["--tmpfs", "/tmp"].iter().map(|arg| arg.to_string()));
+
+    let mut mount = |option: &str, path: &Path, target: &Path| {
+        args.push(option.to_string());
+        args.push(path.to_string_lossy().to_string());
+        args.push(target.to_string_lossy().to_string());
+    };
+    mount("--bind", &layout.private_home, &layout.home);
+    // The project may live inside the home directory just hidden
+    mount("--ro-bind", &layout.root, &layout.root);
+    for path in &layout.read_only {
+        mount("--ro-bind-try", path, path);
+    }
+    // The git directory may live outside the repository
+    mount("--ro-bind", &layout.git_dir, &layout.git_dir);
+    for path in &layout.git_writable {
+        mount("--bind", path, path);
+    }
+    if let Some(cache) = &layout.cache {
+        mount("--bind", cache, cache);
+    }
+    mount("--bind", &layout.workspace, &layout.workspace);
+    for path in &layout.protected {
+        mount("--ro-bind-try", path, path);
+    }
+
+    args.push("--chdir".to_string());
+    args.push(layout.workspace.to_string_lossy().to_string());
+    args.push("--".to_string());
+    args.push(layout.shell.clone());
+    args
+}
+
+/// `unshare` command line running a script that sets up the mounts, then the shell.
+///
+/// The real home directory stays reachable through a staging mount while paths
+/// inside it (possibly the project itself) are bound into the private home.
+/// Writable paths are bound before the repository is made read-only, so they
+/// keep their own, writable mounts. The shell runs without capabilities, so it
+/// cannot remount or unmount any of them.
+fn unshare_args(layout: &Layout) -> Vec<String> {
+    let quote = |path: &Path| tmux::shell_quote(&path.to_string_lossy());
+    // Where a path's real content is once the private home hides the real one
+    let source = |path: &Path| match path.strip_prefix(&layout.home) {
+        Ok(rest) => format!("\"$stage\"/{}", quote(rest)),
+        Err(_) => quote(path),
+    };
+    let bind = |path: &Path| {
+        format!(
+            "mkdir -p {p} && mount --bind {} {p}",
+            source(path),
+            p = quote(path)
+        )
+    };
+    let read_only = |path: &Path| {
+        format!(
+            "if [ -d {s} ]; then mkdir -p {p}; elif [ -e {s} ]; then mkdir -p {parent} && touch {p}; fi; \
+             if [ -e {s} ]; then mount --bind {s} {p} && mount -o remount,bind,ro {p}; fi",
+            s = source(path),
+            p = quote(path),
+            parent = quote(path.parent().unwrap_or(path))
+        )
+    };
+
+    let mut script = vec![
+        "set -e".to_string(),
+        "stage=$(mktemp -d)".to_string(),
+        format!("mount --bind {} \"$stage\"", quote(&layout.home)),
+        format!(
+            "mount --bind {} {}",
+            quote(&layout.private_home),
+            quote(&layout.home)
+        ),
+        bind(&layout.root),
+        bind(&layout.git_dir),
+    ];
+    for path in &layout.git_writable {
+        script.push(bind(path));
+    }
+    if let Some(cache) = &layout.cache {
+        script.push(bind(cache));
+    }
+    script.push(bind(&layout.workspace));
+    for path in [&layout.root, &layout.git_dir] {
+        script.push(format!("mount -o remount,bind,ro {}", quote(path)));
+    }
+    for path in layout.protected.iter().chain(&layout.read_only) {
+        script.push(read_only(path));
+    }
+    script.push("umount -l \"$stage\" && rmdir \"$stage\"".to_string());
+    script.push("mount -t tmpfs tmpfs /tmp".to_string());
+    script.push(format!("cd {}", quote(&layout.workspace)));
+    script.push(format!(
+        "exec {} -- {}",
+        DROP_CAPS.join(" "),
+        tmux::shell_quote(&layout.shell)
+    ));
+
+    let mut args: Vec<String> = [
+        "unshare",
+        "--user",
+        "--map-current-user",
+        "--keep-caps",
+        "--mount",
+        "--pid",
+        "--fork",
+        "--mount-proc",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    if !layout.network {
+        args.push("--net".to_string());
+    }
+    args.extend(["--", "sh", "-c"].iter().map(|arg| arg.to_string()));
+    args.push(script.join("\n"));
+    args
+}
+
+/// Report which sandbox tools work here and how each agent is sandboxed
+fn check() -> Result<()> {
+    println!("🔒 Sandbox tooling:");
+    let bwrap = probe(&[
+        "bwrap",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev-bind",
+        "/dev",
+        "/dev",
+        "--unshare-all",
+        "true",
+    ]);
+    let mut unshare_probe = vec![
+        "unshare",
+        "--user",
+        "--map-current-user",
+        "--keep-caps",
+        "--mount",
+        "--pid",
+        "--fork",
+        "--net",
+    ];
+    unshare_probe.extend(DROP_CAPS);
+    unshare_probe.extend(["--", "true"]);
+    let unshare = if cache::is_installed("setpriv") {
+        probe(&unshare_probe)
+    } else {
+        Err("needs setpriv, which is not installed".to_string())
+    };
+    for (name, result) in [("bwrap", &bwrap), ("unshare", &unshare)] {
+        match result {
+            Ok(()) => println!("  ✅ {:<8} works", name),
+            Err(reason) => println!("  ❌ {:<8} {}", name, reason),
+        }
+    }
+
+    let config = match SpriteConfig::load() {
+        Ok(config) => config,
+        Err(_) => return Ok(()),
+    };
+    println!();
+    println!("🤖 Agents:");
+    let mut broken = Vec::new();
+    for agent in &config.agents {
+        let sandbox = &agent.config.sandbox;
+        if !sandbox.enabled {
+            println!("  Agent {}: not sandboxed", agent.id);
+            continue;
+        }
+        let backend = match sandbox.backend {
+            SandboxBackend::Auto if bwrap.is_ok() => Some(SandboxBackend::Bwrap),
+            SandboxBackend::Auto if unshare.is_ok() => Some(SandboxBackend::Unshare),
+            SandboxBackend::Bwrap if bwrap.is_ok() => Some(SandboxBackend::Bwrap),
+            SandboxBackend::Unshare if unshare.is_ok() => Some(SandboxBackend::Unshare),
+            _ => None,
+        };
+        match backend {
+            Some(backend) => println!(
+                "  Agent {}: {}, network {}{}",
+                agent.id,
+                program(backend),
+                if sandbox.network { "on" } else { "off" },
+                if sandbox.read_only.is_empty() {
+                    String::new()
+                } else {
+                    format!(", also reads {}", sandbox.read_only.join(", "))
+                }
+            ),
+            None => {
+                println!(
+                    "  Agent {}: ❌ {} does not work here",
+                    agent.id,
+                    match sandbox.backend {
+                        SandboxBackend::Auto => "neither bwrap nor unshare",
+                        backend => program(backend),
+                    }
+                );
+                broken.push(agent.id.clone());
+            }
+        }
+    }
+
+    if broken.is_empty() {
+        return Ok(());
+    }
+    Err(SpriteError::config(format!(
+        "Agent(s) {} cannot be sandboxed; their panes will not start",
+        broken.join(", ")
+    ))
+    .into())
+}
+
+/// Run a command that only succeeds if the sandbox tool works, returning why it does not
+fn probe(args: &[&str]) -> std::result::Result<(), String> {
+    if !cache::is_installed(args[0]) {
+        return Err("not installed".to_string());
+    }
+    let output = Command::new(args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("failed to run: {}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!(
+        "cannot create namespaces: {}",
+        stderr.lines().last().unwrap_or("unknown error").trim()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Layout {
+        Layout {
+            workspace: PathBuf::from("/repo/agents/1"),
+            root: PathBuf::from("/repo"),
+            git_dir: PathBuf::from("/repo/.git"),
+            git_writable: vec![
+                PathBuf::from("/repo/.git/objects"),
+                PathBuf::from("/repo/.git/worktrees/1"),
+            ],
+            protected: vec![
+                PathBuf::from("/repo/.git/worktrees/1/config.worktree"),
+                PathBuf::from("/repo/agents/1/.git"),
+            ],
+            cache: Some(PathBuf::from("/repo/agents/cache")),
+            home: PathBuf::from("/home/dev"),
+            private_home: PathBuf::from("/repo/.git/sprite/homes/1"),
+            read_only: vec![PathBuf::from("/home/dev/.cargo")],
+            network: false,
+            shell: "bash".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bwrap_args_order() {
+        let args = bwrap_args(&layout()).join(" ");
+        assert!(args.starts_with("bwrap --ro-bind / / "));
+        assert!(!args.contains("--share-net"));
+        assert!(args.contains("--tmpfs /tmp"));
+        // The private home hides the real one before read-only paths inside it are added back
+        let home = args
+            .find("--bind /repo/.git/sprite/homes/1 /home/dev")
+            .unwrap();
+        let cargo = args
+            .find("--ro-bind-try /home/dev/.cargo /home/dev/.cargo")
+            .unwrap();
+        // Only parts of the read-only git directory are writable, minus the worktree's config
+        let git = args.find("--ro-bind /repo/.git /repo/.git").unwrap();
+        let objects = args
+            .find("--bind /repo/.git/objects /repo/.git/objects")
+            .unwrap();
+        let worktree = args
+            .find("--bind /repo/.git/worktrees/1 /repo/.git/worktrees/1")
+            .unwrap();
+        let config = args
+            .find("--ro-bind-try /repo/.git/worktrees/1/config.worktree")
+            .unwrap();
+        let workspace = args.find("--bind /repo/agents/1 /repo/agents/1").unwrap();
+        let dot_git = args.find("--ro-bind-try /repo/agents/1/.git").unwrap();
+        assert!(home < cargo && git < objects && worktree < config && workspace < dot_git);
+        assert!(args.ends_with("--chdir /repo/agents/1 -- bash"));
+    }
+
+    #[test]
+    fn test_unshare_script_binds_before_read_only() {
+        let args = unshare_args(&layout());
+        assert!(args.contains(&"--net".to_string()));
+        let script = args.last().unwrap();
+        let home = script
+            .find("mount --bind /repo/.git/sprite/homes/1 /home/dev")
+            .unwrap();
+        let workspace = script
+            .find("mount --bind /repo/agents/1 /repo/agents/1")
+            .unwrap();
+        let worktree = script
+            .find("mount --bind /repo/.git/worktrees/1 /repo/.git/worktrees/1")
+            .unwrap();
+        let read_only = script.find("mount -o remount,bind,ro /repo\n").unwrap();
+        let git = script
+            .find("mount -o remount,bind,ro /repo/.git\n")
+            .unwrap();
+        let config = script
+            .find("mount -o remount,bind,ro /repo/.git/worktrees/1/config.worktree")
+            .unwrap();
+        assert!(home < workspace && worktree < git && workspace < read_only && git < config);
+        // Paths inside the hidden home are bound from the staged real one
+        assert!(script.contains("mount --bind \"$stage\"/.cargo /home/dev/.cargo"));
+        // The shell keeps no capabilities to undo the mounts with, and is not root
+        assert!(args.contains(&"--map-current-user".to_string()));
+        assert!(!args.contains(&"--map-root-user".to_string()));
+        assert!(script.ends_with(
+            "cd /repo/agents/1\nexec setpriv --inh-caps=-all --ambient-caps=-all \
+             --bounding-set=-all --no-new-privs -- bash"
+        ));
+    }
+}
@@ -0,0 +1,412 @@
+//! Agent selector expressions shared by commands that target agents.
+//!
+//! A selector is a comma-separated list of terms, applied left to right:
+//! - `all` - every configured agent
+//! - `3`, `review-*` - agent IDs, with `*` and `?` globs
+//! - `1-4` - a range of numeric agent IDs
+//! - `@frontend` - agents tagged `frontend` or listed in the `frontend` group
+//! - `idle`, `busy`, `error`, `stopped` - agents in that runtime state
+//! - `!term` - removes the agents matched by `term` (`all,!3`)
+//!
+//! Parts of a term joined with `+` must all match, so `@backend+idle` selects
+//! every idle backend agent. An agent ID always wins over a keyword of the
+//! same name.
+
+use crate::commands::apply;
+use crate::commands::config::{AgentConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::tmux;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What an agent is doing right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentState {
+    /// Its pane is sitting at a shell prompt
+    Idle,
+    /// Its pane is running a command
+    Busy,
+    /// It has no pane in the running session, its worktree is missing or its status is `error`
+    Error,
+    /// The project session is not running
+    Stopped,
+}
+
+impl AgentState {
+    fn from_keyword(word: &str) -> Option<Self> {
+        match word {
+            "idle" => Some(Self::Idle),
+            "busy" => Some(Self::Busy),
+            "error" => Some(Self::Error),
+            "stopped" => Some(Self::Stopped),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AgentState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Idle => "idle",
+            Self::Busy => "busy",
+            Self::Error => "error",
+            Self::Stopped => "stopped",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A parsed selector expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    exclude: bool,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    /// `@name`: a tag or group
+    Label(String),
+    /// An ID, glob, range or keyword, told apart once the agents are known
+    Word(String),
+}
+
+impl Selector {
+    /// Parse a selector expression such as `@backend+idle,!3`.
+    pub fn parse(expression: &str) -> Result<Self> {
+        let mut terms = Vec::new();
+        for raw in expression.split(',').map(str::trim) {
+            if raw.is_empty() {
+                continue;
+            }
+            let (exclude, body) = match raw.strip_prefix('!') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, raw),
+            };
+
+            let mut parts = Vec::new();
+            for part in body.split('+').map(str::trim) {
+                let parsed = match part.strip_prefix('@') {
+                    Some(name) if !name.is_empty() => Part::Label(name.to_string()),
+                    None if !part.is_empty() => Part::Word(part.to_string()),
+                    _ => return Err(invalid(expression, raw)),
+                };
+                parts.push(parsed);
+            }
+            terms.push(Term { exclude, parts });
+        }
+
+        if terms.is_empty() {
+            return Err(SpriteError::validation(
+                "Agent selector is empty",
+                Some("agents"),
+                Some(expression),
+            )
+            .into());
+        }
+        Ok(Self { terms })
+    }
+
+    /// Whether resolving this selector needs the agents' runtime states
+    pub fn uses_state(&self) -> bool {
+        self.terms.iter().flat_map(|t| &t.parts).any(
+            |part| matches!(part, Part::Word(word) if AgentState::from_keyword(word).is_some()),
+        )
+    }
+
+    /// IDs of the selected agents, in configuration order.
+    ///
+    /// `states` only needs entries when [`Selector::uses_state`] is true.
+    pub fn resolve(
+        &self,
+        config: &SpriteConfig,
+        states: &HashMap<String, AgentState>,
+    ) -> Result<Vec<String>> {
+        let mut selected = vec![false; config.agents.len()];
+        if self.terms.first().map(|t| t.exclude).unwrap_or(false) {
+            selected.iter_mut().for_each(|s| *s = true);
+        }
+
+        for term in &self.terms {
+            let mut matched = vec![true; config.agents.len()];
+            for part in &term.parts {
+                let part_matches = match_part(part, config, states)?;
+                for (m, p) in matched.iter_mut().zip(part_matches) {
+                    *m &= p;
+                }
+            }
+            for (s, m) in selected.iter_mut().zip(matched) {
+                if m {
+                    *s = !term.exclude;
+                }
+            }
+        }
+
+        Ok(config
+            .agents
+            .iter()
+            .zip(selected)
+            .filter(|(_, s)| *s)
+            .map(|(agent, _)| agent.id.clone())
+            .collect())
+    }
+}
+
+/// Resolve a selector against the current configuration and session.
+pub fn select(config: &SpriteConfig, expression: &str) -> Result<Vec<String>> {
+    let selector = Selector::parse(expression)?;
+    let states = if selector.uses_state() {
+        agent_states(config)?
+    } else {
+        HashMap::new()
+    };
+    selector.resolve(config, &states)
+}
+
+/// Work out the runtime state of every configured agent.
+pub fn agent_states(config: &SpriteConfig) -> Result<HashMap<String, AgentState>> {
+    let session = config.effective_session_name();
+    let panes = if tmux::session_exists(&session)? {
+        Some(tmux::list_agent_panes(&session)?)
+    } else {
+        None
+    };
+    let root = config.project_root().unwrap_or_default();
+
+    Ok(config
+        .agents
+        .iter()
+        .map(|agent| {
+            let state = if agent.status.eq_ignore_ascii_case("error") {
+                AgentState::Error
+            } else {
+                match &panes {
+                    None => AgentState::Stopped,
+                    Some(_) if !root.join(&agent.worktree_path).exists() => AgentState::Error,
+                    Some(panes) => match apply::find_agent_pane(panes, agent, &root) {
+                        Some(pane) if pane.is_idle() => AgentState::Idle,
+                        Some(_) => AgentState::Busy,
+                        None => AgentState::Error,
+                    },
+                }
+            };
+            (agent.id.clone(), state)
+        })
+        .collect())
+}
+
+fn match_part(
+    part: &Part,
+    config: &SpriteConfig,
+    states: &HashMap<String, AgentState>,
+) -> Result<Vec<bool>> {
+    let agents = &config.agents;
+    match part {
+        Part::Label(name) => {
+            let group = config.groups.get(name);
+            let tagged = agents.iter().any(|a| a.tags.contains(name));
+            if group.is_none() && !tagged {
+                return Err(SpriteError::validation(
+                    format!("Unknown tag or group '@{}'", name),
+                    Some("agents"),
+                    Some(format!("@{}", name)),
+                )
+                .into());
+            }
+
+            let mut matched: Vec<bool> = agents.iter().map(|a| a.tags.contains(name)).collect();
+            for member in group.into_iter().flatten() {
+                for (m, agent) in matched.iter_mut().zip(agents) {
+                    *m |= member_matches(member, agent);
+                }
+            }
+            Ok(matched)
+        }
+        Part::Word(word) => {
+            if agents.iter().any(|a| &a.id == word) {
+                return Ok(agents.iter().map(|a| &a.id == word).collect());
+            }
+            if word == "all" {
+                return Ok(vec![true; agents.len()]);
+            }
+            if let Some(state) = AgentState::from_keyword(word) {
+                return Ok(agents
+                    .iter()
+                    .map(|a| states.get(&a.id) == Some(&state))
+                    .collect());
+            }
+            if is_glob(word) || parse_range(word).is_some() {
+                return Ok(agents.iter().map(|a| member_matches(word, a)).collect());
+            }
+
+            Err(SpriteError::agent_not_found(format!(
+                "Agent '{}' not found. Use 'sprite agents list' to see configured agents.",
+                word
+            ))
+            .into())
+        }
+    }
+}
+
+/// Whether a group member (an ID, range or glob) names `agent`
+fn member_matches(member: &str, agent: &AgentConfig) -> bool {
+    if let Some((low, high)) = parse_range(member) {
+        return agent
+            .id
+            .parse::<u64>()
+            .map(|n| (low..=high).contains(&n))
+            .unwrap_or(false);
+    }
+    if is_glob(member) {
+        return glob_matches(member, &agent.id);
+    }
+    member == agent.id
+}
+
+fn is_glob(word: &str) -> bool {
+    word.contains('*') || word.contains('?')
+}
+
+/// Parse `low-high` with numeric bounds
+fn parse_range(word: &str) -> Option<(u64, u64)> {
+    let (low, high) = word.split_once('-')?;
+    let low = low.trim().parse().ok()?;
+    let high = high.trim().parse().ok()?;
+    (low <= high).then_some((low, high))
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` any one
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn invalid(expression: &str, term: &str) -> anyhow::Error {
+    SpriteError::validation(
+        format!("Invalid agent selector term '{}'", term),
+        Some("agents"),
+        Some(expression),
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::config::AgentConfigDetails;
+
+    fn config() -> SpriteConfig {
+        let mut config = SpriteConfig::new();
+        for (id, tags) in [
+            ("1", vec!["frontend"]),
+            ("2", vec!["backend"]),
+            ("3", vec!["backend"]),
+            ("4", vec![]),
+            ("review-a", vec![]),
+            ("review-b", vec![]),
+        ] {
+            config.agents.push(AgentConfig {
+                id: id.to_string(),
+                branch: format!("agents/{}", id),
+                worktree_path: format!("agents/{}", id),
+                model: "claude-sonnet".to_string(),
+                description: String::new(),
+                tags: tags.into_iter().map(String::from).collect(),
+                status: "active".to_string(),
+                config: AgentConfigDetails::default(),
+            });
+        }
+        config
+            .groups
+            .insert("reviewers".to_string(), vec!["review-*".to_string()]);
+        config
+    }
+
+    fn resolve(expression: &str, states: &HashMap<String, AgentState>) -> Result<Vec<String>> {
+        Selector::parse(expression)?.resolve(&config(), states)
+    }
+
+    #[test]
+    fn test_selector_grammar() {
+        let none = HashMap::new();
+        assert_eq!(resolve("2,1", &none).unwrap(), vec!["1", "2"]);
+        assert_eq!(resolve("1-3", &none).unwrap(), vec!["1", "2", "3"]);
+        assert_eq!(resolve("all,!3", &none).unwrap().len(), 5);
+        assert_eq!(
+            resolve("!review-*", &none).unwrap(),
+            vec!["1", "2", "3", "4"]
+        );
+        assert_eq!(resolve("@backend", &none).unwrap(), vec!["2", "3"]);
+        assert_eq!(
+            resolve("@reviewers,1", &none).unwrap(),
+            vec!["1", "review-a", "review-b"]
+        );
+        assert_eq!(
+            resolve("review-?", &none).unwrap(),
+            vec!["review-a", "review-b"]
+        );
+
+        assert!(resolve("99", &none).is_err());
+        assert!(resolve("@nobody", &none).is_err());
+        assert!(Selector::parse(" , ").is_err());
+        assert!(Selector::parse("1+").is_err());
+    }
+
+    #[test]
+    fn test_state_filters() {
+        let states: HashMap<String, AgentState> = [
+            ("1", AgentState::Idle),
+            ("2", AgentState::Idle),
+            ("3", AgentState::Busy),
+            ("4", AgentState::Error),
+        ]
+        .into_iter()
+        .map(|(id, state)| (id.to_string(), state))
+        .collect();
+
+        assert!(Selector::parse("@backend+idle").unwrap().uses_state());
+        assert!(!Selector::parse("1-4,@backend").unwrap().uses_state());
+        assert_eq!(resolve("@backend+idle", &states).unwrap(), vec!["2"]);
+        assert_eq!(resolve("all,!busy,!error", &states).unwrap().len(), 4);
+        assert_eq!(resolve("error", &states).unwrap(), vec!["4"]);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("review-*", "review-frontend"));
+        assert!(glob_matches("*-b", "review-b"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(!glob_matches("review-?", "review-ab"));
+        assert!(!glob_matches("1*", "21"));
+    }
+}
@@ -2,6 +2,7 @@ use crate::commands::config::SpriteConfig;
 use crate::error::SpriteError;
 use crate::utils::git;
 use crate::utils::project;
+use crate::utils::registry;
 use crate::utils::tmux;
 use anyhow::{Context, Result};
 
@@ -25,11 +26,11 @@ fn execute_from_project_root(
     detach: bool,
     force: bool,
 ) -> Result<()> {
-    let session_name = session_name.unwrap_or_else(|| "sprite-session".to_string());
-
     // Load configuration
     let config = SpriteConfig::load().context("Failed to load configuration")?;
 
+    let session_name = session_name.unwrap_or_else(|| config.effective_session_name());
+
     if config.agents.is_empty() {
         return Err(SpriteError::config(
             "No agents configured. Use 'sprite init' to create agents first.",
@@ -90,6 +91,16 @@ fn execute_from_project_root(
     // Create tmux session
     create_tmux_session(&session_name, &config, &profile, detach)?;
 
+    // Remember what the session was built from so 'sprite apply' can diff against it
+    crate::commands::apply::record_applied(&session_name, &config);
+
+    // Remember this project so 'sprite projects list' can find it. The registry
+    // records the session the other commands look for, not a one-off
+    // --session-name override.
+    if let Some(root) = config.project_root() {
+        registry::register_project(&root, &config.effective_session_name());
+    }
+
     // Give the tmux session a moment to fully initialize before updating status
     std::thread::sleep(std::time::Duration::from_millis(300));
     // Update agent status to Active after successful session creation
@@ -184,6 +195,9 @@ fn setup_agent_panes(session_name: &str, config: &SpriteConfig) -> Result<()> {
     // Get the current panes in the session to determine where to send commands
     let panes = tmux::get_session_panes(session_name)
         .context("Failed to get session panes for agent setup")?;
+    let root = config.project_root().unwrap_or_default();
+    crate::commands::env::refresh_envrcs(config);
+    let port_lock = crate::utils::ports::PortLock::load().unwrap_or_default();
 
     for (index, agent_config) in config.agents.iter().enumerate() {
         let workspace_path = &agent_config.worktree_path;
@@ -194,8 +208,41 @@ fn setup_agent_panes(session_name: &str, config: &SpriteConfig) -> Result<()> {
             "".to_string()
         };
 
+        // Tag the pane so later commands can find it even after it changes directory
+        if let Some(pane) = panes.get(index) {
+            // Restart the pane's shell in the agent's sandbox before anything is sent to it
+            if let Err(e) = crate::commands::sandbox::launch(&pane.pane_id, config, agent_config) {
+                eprintln!(
+                    "❌ Agent {}: sandbox unavailable, pane not started: {}",
+                    agent_config.id, e
+                );
+                continue;
+            }
+
+            let title = crate::commands::apply::pane_title(agent_config);
+            if let Err(e) = tmux::tag_agent_pane(&pane.pane_id, &agent_config.id, &title) {
+                eprintln!(
+                    "⚠️  Warning: Failed to tag agent {} pane: {}",
+                    agent_config.id, e
+                );
+            }
+
+            // Record everything the pane prints to agents/logs/<agent>/
+            if let Err(e) =
+                crate::commands::logs::start_capture(&pane.pane_id, &agent_config.id, &root)
+            {
+                eprintln!(
+                    "⚠️  Warning: Failed to record agent {} transcript: {}",
+                    agent_config.id, e
+                );
+            }
+        }
+
         // Change to agent workspace
-        let cmd = format!("cd {}", workspace_path);
+        let cmd = format!(
+            "cd {}",
+            tmux::shell_quote(&root.join(workspace_path).to_string_lossy())
+        );
         if let Err(e) = tmux::send_keys_with_delay(session_name, &pane_target, &cmd, 50) {
             eprintln!(
                 "⚠️  Warning: Failed to setup agent {} workspace: {}",
@@ -203,6 +250,22 @@ fn setup_agent_panes(session_name: &str, config: &SpriteConfig) -> Result<()> {
             );
         }
 
+        // Export the same environment the agent's .envrc gives a shell opened by hand
+        for (key, value) in crate::commands::env::agent_env(config, agent_config, &port_lock) {
+            let export_cmd = format!(
+                "export {}={}",
+                key,
+                crate::utils::secrets::shell_value(&value)
+            );
+            if let Err(e) = tmux::send_keys_with_delay(session_name, &pane_target, &export_cmd, 50)
+            {
+                eprintln!(
+                    "⚠️  Warning: Failed to export {} for agent {}: {}",
+                    key, agent_config.id, e
+                );
+            }
+        }
+
         // Display agent information
         let info_cmd = format!(
             "echo '🤖 Agent {} - {}'",
@@ -278,7 +341,7 @@ fn update_agent_status_to_active(_config: &crate::commands::config::SpriteConfig
     println!("🔄 Activating agents...");
 
     // Load current configuration
-    let mut current_config = crate::commands::config::SpriteConfig::load()
+    let mut current_config = crate::commands::config::SpriteConfig::load_project()
         .context("Failed to load configuration for status update")?;
 
     let mut updated_count = 0;
@@ -1,3 +1,5 @@
+use crate::commands::config::SpriteConfig;
+use crate::commands::selector::{self, AgentState, Selector};
 use crate::utils::session_recovery::{
     analyze_session_health, cleanup_old_sessions, cleanup_temp_files, generate_health_report,
     RecoveryConfig,
@@ -6,7 +8,12 @@ use crate::utils::tmux;
 use anyhow::{Context, Result};
 
 /// Execute status command with session health checking and cleanup options
-pub fn execute(session_name: Option<String>, cleanup: bool, detailed: bool) -> Result<()> {
+pub fn execute(
+    session_name: Option<String>,
+    cleanup: bool,
+    detailed: bool,
+    agents: Option<&str>,
+) -> Result<()> {
     // Check if tmux is available
     tmux::is_tmux_available()
         .context("tmux is not available. Please install tmux to use sprite status.")?;
@@ -16,6 +23,18 @@ pub fn execute(session_name: Option<String>, cleanup: bool, detailed: bool) -> R
         return cleanup_resources();
     }
 
+    // Warn early when agents started editing more of the same files
+    crate::commands::overlap::warn_if_overlap_grew();
+
+    // Show only the selected agents
+    if let Some(expression) = agents {
+        return show_agent_states(expression);
+    }
+
+    // Default to the session that belongs to the current project
+    let session_name =
+        session_name.or_else(|| crate::commands::config::current_session_name().ok());
+
     // Get session health information
     let config = RecoveryConfig::default();
     let health_reports = match analyze_session_health(&config) {
@@ -71,6 +90,44 @@ pub fn execute(session_name: Option<String>, cleanup: bool, detailed: bool) -> R
     Ok(())
 }
 
+/// Show the runtime state of the agents matched by a selector
+fn show_agent_states(expression: &str) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let states = selector::agent_states(&config)?;
+    let agent_ids = Selector::parse(expression)?.resolve(&config, &states)?;
+
+    println!("🤖 Agents matching '{}'", expression);
+    if agent_ids.is_empty() {
+        println!("   None");
+        return Ok(());
+    }
+
+    for agent_id in &agent_ids {
+        let agent = config
+            .get_agent(agent_id)
+            .expect("selected agents are configured");
+        let state = states.get(agent_id).copied().unwrap_or(AgentState::Stopped);
+        let icon = match state {
+            AgentState::Idle => "🟢",
+            AgentState::Busy => "🟡",
+            AgentState::Error => "🔴",
+            AgentState::Stopped => "⚪",
+        };
+        let tags: Vec<String> = agent.tags.iter().map(|t| format!("@{}", t)).collect();
+        let line = format!(
+            "  {} Agent {} ({}) - {} {}",
+            icon,
+            agent_id,
+            state,
+            agent.branch,
+            tags.join(" ")
+        );
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
 /// Clean up old sessions and temporary files
 fn cleanup_resources() -> Result<()> {
     println!("🧹 Starting cleanup of old resources...");
@@ -1,10 +1,12 @@
 //! Sync command - Context-aware git synchronization
 
+use crate::commands::config::SpriteConfig;
+use crate::commands::selector;
 use crate::error::SpriteError;
 use crate::models::ConflictResolution;
 use crate::utils::git;
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::process::Command as TokioCommand;
 
 /// Execute the sync command with the given parameters.
@@ -26,8 +28,17 @@ pub fn execute(agent: Option<&str>, force: bool, strategy: &str, dry_run: bool)
         }
     };
 
+    // The branch agents merge from; detected when there is no configuration to say
+    let config = SpriteConfig::load().unwrap_or_default();
+    let trunk = config.trunk();
+
+    // An agent selector syncs those agents' worktrees from anywhere in the project
+    if let Some(expression) = agent {
+        return sync_selected_agents(expression, &trunk, conflict_strategy, dry_run, force);
+    }
+
     // Determine sync context
-    let sync_context = determine_sync_context(agent.map(|s| s.to_string()))?;
+    let sync_context = determine_sync_context(trunk)?;
 
     println!("🔄 Syncing in {:?} context...", sync_context.context_type);
 
@@ -40,6 +51,15 @@ pub fn execute(agent: Option<&str>, force: bool, strategy: &str, dry_run: bool)
         SyncContextType::MainWorktree => {
             sync_main_worktree(&sync_context, conflict_strategy, dry_run, force)?
         }
+        SyncContextType::AgentWorktree { agent_id }
+            if config.is_sync_excluded(&sync_context.current_branch) =>
+        {
+            println!(
+                "⏭️  Not syncing agent {}: branch {} is excluded from sync",
+                agent_id, sync_context.current_branch
+            );
+            return Ok(());
+        }
         SyncContextType::AgentWorktree { agent_id } => {
             sync_agent_worktree(&sync_context, agent_id, conflict_strategy, dry_run, force)?
         }
@@ -66,6 +86,8 @@ struct SyncContext {
     context_type: SyncContextType,
     /// Current working directory
     current_dir: PathBuf,
+    /// Trunk branch agent worktrees merge from
+    trunk: String,
     /// Current git branch
     current_branch: String,
     /// Git repository root
@@ -84,8 +106,82 @@ enum SyncContextType {
     Unknown,
 }
 
+/// Sync the worktree of every agent matched by a selector.
+fn sync_selected_agents(
+    expression: &str,
+    trunk: &str,
+    conflict_strategy: ConflictResolution,
+    dry_run: bool,
+    force: bool,
+) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let git_root = git::get_git_root().context("Failed to get git repository root")?;
+
+    let agent_ids = selector::select(&config, expression)?;
+    if agent_ids.is_empty() {
+        println!("ℹ️  No agents match '{}'.", expression);
+        return Ok(());
+    }
+    if dry_run {
+        println!("🔍 Dry run mode - no changes will be made");
+    }
+
+    let mut failed = Vec::new();
+    for agent_id in &agent_ids {
+        let agent = config
+            .get_agent(agent_id)
+            .expect("selected agents are configured");
+        let current_dir = git_root.join(&agent.worktree_path);
+        if !current_dir.exists() {
+            println!(
+                "⚠️  Skipping agent {}: worktree {} is missing",
+                agent_id, agent.worktree_path
+            );
+            failed.push(agent_id.clone());
+            continue;
+        }
+
+        let context = SyncContext {
+            context_type: SyncContextType::AgentWorktree {
+                agent_id: agent_id.clone(),
+            },
+            current_branch: git::get_current_branch_at(&current_dir)
+                .context("Failed to get current git branch")?,
+            current_dir,
+            trunk: trunk.to_string(),
+            git_root: git_root.clone(),
+        };
+        if config.is_sync_excluded(&context.current_branch) {
+            println!(
+                "⏭️  Skipping agent {}: branch {} is excluded from sync",
+                agent_id, context.current_branch
+            );
+            continue;
+        }
+        if let Err(e) = sync_agent_worktree(
+            &context,
+            agent_id,
+            conflict_strategy.clone(),
+            dry_run,
+            force,
+        ) {
+            println!("❌ Agent {}: {}", agent_id, e);
+            failed.push(agent_id.clone());
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(
+            SpriteError::sync(format!("Could not sync agent(s): {}", failed.join(", "))).into(),
+        );
+    }
+
+    println!("✓ Synced {} agent(s)", agent_ids.len());
+    Ok(())
+}
+
 /// Determine the current sync context.
-fn determine_sync_context(agent_id: Option<String>) -> Result<SyncContext> {
+fn determine_sync_context(trunk: String) -> Result<SyncContext> {
     let current_dir = std::env::current_dir().context("Failed to get current directory")?;
 
     let git_root = git::get_git_root().context("Failed to get git repository root")?;
@@ -106,6 +202,7 @@ fn determine_sync_context(agent_id: Option<String>) -> Result<SyncContext> {
         return Ok(SyncContext {
             context_type: SyncContextType::MainWorktree,
             current_dir,
+            trunk,
             current_branch,
             git_root,
         });
@@ -118,6 +215,7 @@ fn determine_sync_context(agent_id: Option<String>) -> Result<SyncContext> {
             return Ok(SyncContext {
                 context_type: SyncContextType::Unknown,
                 current_dir,
+                trunk,
                 current_branch,
                 git_root,
             });
@@ -129,26 +227,12 @@ fn determine_sync_context(agent_id: Option<String>) -> Result<SyncContext> {
 
     // Try to extract agent ID from path
     if let Some(extracted_agent_id) = extract_agent_id_from_path(&path_str) {
-        // Validate against provided agent ID if specified
-        if let Some(provided_id) = &agent_id {
-            if provided_id != &extracted_agent_id {
-                return Err(SpriteError::validation(
-                    format!(
-                        "Agent ID mismatch. Path suggests '{}' but you specified '{}'",
-                        extracted_agent_id, provided_id
-                    ),
-                    Some("agent".to_string()),
-                    Some(format!("path: {}, agent: {}", path_str, provided_id)),
-                )
-                .into());
-            }
-        }
-
         return Ok(SyncContext {
             context_type: SyncContextType::AgentWorktree {
                 agent_id: extracted_agent_id,
             },
             current_dir,
+            trunk,
             current_branch,
             git_root,
         });
@@ -157,6 +241,7 @@ fn determine_sync_context(agent_id: Option<String>) -> Result<SyncContext> {
     Ok(SyncContext {
         context_type: SyncContextType::Unknown,
         current_dir,
+        trunk,
         current_branch,
         git_root,
     })
@@ -202,10 +287,10 @@ fn sync_main_worktree(
     dry_run: bool,
     force: bool,
 ) -> Result<()> {
-    println!("📥 Syncing main worktree (pull from remote origin/main)");
+    println!("📥 Syncing main worktree (pull from remote)");
 
     if dry_run {
-        println!("  Would run: git pull origin main");
+        println!("  Would run: git pull");
         return Ok(());
     }
 
@@ -227,14 +312,17 @@ fn sync_main_worktree(
     }
 
     // Pull changes from remote
-    println!("  Pulling changes from origin/main...");
+    println!(
+        "  Pulling changes from the upstream of '{}'...",
+        context.current_branch
+    );
     git::pull().with_context(|| "Failed to pull changes from remote")?;
 
     println!("✓ Main worktree synced successfully");
     Ok(())
 }
 
-/// Sync an agent worktree (merge from main).
+/// Sync an agent worktree (merge from the trunk).
 fn sync_agent_worktree(
     context: &SyncContext,
     agent_id: &str,
@@ -243,12 +331,12 @@ fn sync_agent_worktree(
     force: bool,
 ) -> Result<()> {
     println!(
-        "📥 Syncing agent worktree '{}' (merge from main branch)",
-        agent_id
+        "📥 Syncing agent worktree '{}' (merge from {})",
+        agent_id, context.trunk
     );
 
     if dry_run {
-        println!("  Would run: git merge main");
+        println!("  Would run: git merge {}", context.trunk);
         return Ok(());
     }
 
@@ -269,43 +357,50 @@ fn sync_agent_worktree(
         println!("⚠️  Proceeding with uncommitted changes due to --force flag");
     }
 
-    // Ensure main branch exists and is up to date
-    println!("  Ensuring main branch is available...");
-
-    // Switch to main branch temporarily if needed
-    let current_branch = context.current_branch.clone();
-    if current_branch != "main" && current_branch != "master" {
-        println!("  Switching to main branch temporarily...");
-        git::switch_branch("main")
-            .or_else(|_| {
-                // Try master if main doesn't exist
-                git::switch_branch("master")
-            })
-            .with_context(|| "Failed to switch to main branch")?;
-
-        // Switch back to original branch after merge
-        defer_switch_back(&current_branch)?;
+    // Ensure the trunk exists; merging it needs no checkout, so whichever
+    // worktree we were started from stays on its branch
+    if git::rev_parse_at(
+        &context.current_dir,
+        &format!("refs/heads/{}", context.trunk),
+    )
+    .is_err()
+    {
+        return Err(SpriteError::sync(format!(
+            "Trunk branch '{}' does not exist; set it with 'sprite config set project.trunk <branch>'",
+            context.trunk
+        ))
+        .into());
     }
 
-    // Merge changes from main branch
-    println!("  Merging changes from main branch...");
+    // Merge changes from the trunk
+    println!("  Merging changes from {}...", context.trunk);
 
     let output = std::process::Command::new("git")
-        .args(["merge", "main"])
+        .args(["merge", &context.trunk])
         .current_dir(&context.current_dir)
         .output()
-        .with_context(|| "Failed to merge main branch into agent worktree")?;
+        .with_context(|| format!("Failed to merge {} into agent worktree", context.trunk))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        // git reports conflicts on stdout and other failures on stderr
+        let merge_output = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
 
         // Check for merge conflicts
-        if stderr.contains("CONFLICT") || stderr.contains("Merge conflict") {
-            handle_merge_conflicts(&stderr, conflict_strategy, dry_run)?;
+        if merge_output.contains("CONFLICT") || merge_output.contains("Merge conflict") {
+            handle_merge_conflicts(
+                &context.current_dir,
+                &merge_output,
+                conflict_strategy,
+                dry_run,
+            )?;
         } else {
             return Err(SpriteError::git_with_source(
-                "Failed to merge main branch",
-                stderr.to_string(),
+                format!("Failed to merge {}", context.trunk),
+                merge_output,
             )
             .into());
         }
@@ -315,9 +410,11 @@ fn sync_agent_worktree(
     Ok(())
 }
 
-/// Handle merge conflicts based on strategy.
+/// Resolve a conflicted merge in `worktree` according to the strategy and
+/// conclude it; with the manual strategy the merge is left for the user.
 fn handle_merge_conflicts(
-    error_output: &str,
+    worktree: &Path,
+    merge_output: &str,
     strategy: ConflictResolution,
     dry_run: bool,
 ) -> Result<()> {
@@ -329,80 +426,67 @@ fn handle_merge_conflicts(
         return Ok(());
     }
 
-    match strategy {
+    let side = match strategy {
         ConflictResolution::Manual => {
             println!("❌ Merge conflicts detected:");
-            println!("{}", error_output);
-            return Err(SpriteError::sync(
-                "Merge conflicts detected. Please resolve them manually and try again.",
-            )
+            println!("{}", merge_output);
+            return Err(SpriteError::sync(format!(
+                "Merge conflicts detected. Please resolve them manually in {} and try again.",
+                worktree.display()
+            ))
             .into());
         }
         ConflictResolution::AutoTheirs => {
             println!("🔄 Automatically accepting their changes...");
-            let output = std::process::Command::new("git")
-                .args(["checkout", "--theirs", "--", "."])
-                .output()
-                .with_context(|| "Failed to auto-accept their changes")?;
-
-            if !output.status.success() {
-                return Err(SpriteError::git_with_source(
-                    "Failed to auto-accept their changes",
-                    String::from_utf8_lossy(&output.stderr),
-                )
-                .into());
-            }
-
-            println!("✓ Automatically accepted their changes");
+            "--theirs"
         }
         ConflictResolution::AutoOurs => {
             println!("🔄 Automatically accepting our changes...");
-            let output = std::process::Command::new("git")
-                .args(["checkout", "--ours", "--", "."])
-                .output()
-                .with_context(|| "Failed to auto-accept our changes")?;
-
-            if !output.status.success() {
-                return Err(SpriteError::git_with_source(
-                    "Failed to auto-accept our changes",
-                    String::from_utf8_lossy(&output.stderr),
-                )
-                .into());
-            }
-
-            println!("✓ Automatically accepted our changes");
+            "--ours"
         }
-    }
+    };
 
-    // Stage resolved files
-    let output = std::process::Command::new("git")
-        .args(["add", "."])
-        .output()
-        .with_context(|| "Failed to stage resolved files")?;
+    let run = |args: &[&str], what: &str| -> Result<String> {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(worktree)
+            .output()
+            .with_context(|| format!("{} in {}", what, worktree.display()))?;
+        if !output.status.success() {
+            return Err(SpriteError::git_with_source(
+                format!("{} in {}", what, worktree.display()),
+                String::from_utf8_lossy(&output.stderr),
+            )
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
 
-    if !output.status.success() {
-        return Err(SpriteError::git_with_source(
-            "Failed to stage resolved files",
-            String::from_utf8_lossy(&output.stderr),
-        )
-        .into());
+    // Only the conflicted paths: the rest of the merge is already staged, and
+    // paths missing on one side cannot be checked out from it
+    let conflicted = run(
+        &["diff", "--name-only", "--diff-filter=U", "-z"],
+        "Failed to list conflicted files",
+    )?;
+    let conflicted: Vec<&str> = conflicted.split('\0').filter(|p| !p.is_empty()).collect();
+    if !conflicted.is_empty() {
+        let mut checkout = vec!["checkout", side, "--"];
+        checkout.extend(&conflicted);
+        run(&checkout, "Failed to take one side of the conflicts")?;
+
+        // Stage resolved files
+        let mut add = vec!["add", "--"];
+        add.extend(&conflicted);
+        run(&add, "Failed to stage resolved files")?;
     }
 
-    Ok(())
-}
+    run(&["commit", "--no-edit"], "Failed to conclude the merge")?;
+    println!(
+        "✓ Resolved {} conflicted file(s) with {}",
+        conflicted.len(),
+        side
+    );
 
-/// Helper function to switch back to original branch (using defer pattern).
-fn defer_switch_back(original_branch: &str) -> Result<()> {
-    // In Rust, we don't have defer, so this is just a placeholder
-    // The actual implementation would need to use a more sophisticated approach
-    // or handle the switch back logic differently
-    if original_branch != "main" && original_branch != "master" {
-        // This would need to be handled with proper error handling
-        println!(
-            "  Note: Would switch back to '{}' after operations",
-            original_branch
-        );
-    }
     Ok(())
 }
 
@@ -825,7 +909,68 @@ mod tests {
     fn test_determine_sync_context() {
         // This test would need to be run in different directories
         // For now, just ensure the function compiles
-        let _context = determine_sync_context(None);
+        let _context = determine_sync_context("main".to_string());
+    }
+
+    #[test]
+    fn test_auto_strategy_resolves_in_agent_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let worktree = dir.path().join("agent-1");
+        std::fs::create_dir(&repo).unwrap();
+        let git = |at: &Path, args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .current_dir(at)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+        };
+        git(&repo, &["init", "-q", "-b", "main"]);
+        git(&repo, &["config", "user.name", "Test"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("a.txt"), "base\n").unwrap();
+        std::fs::write(repo.join("notes.txt"), "mine\n").unwrap();
+        git(&repo, &["add", "a.txt", "notes.txt"]);
+        git(&repo, &["commit", "-q", "-m", "base"]);
+        let worktree_arg = worktree.to_string_lossy().to_string();
+        git(
+            &repo,
+            &["worktree", "add", "-q", "-b", "agents/1", &worktree_arg],
+        );
+
+        std::fs::write(worktree.join("a.txt"), "agent\n").unwrap();
+        git(&worktree, &["commit", "-q", "-am", "agent change"]);
+        std::fs::write(repo.join("a.txt"), "trunk\n").unwrap();
+        git(&repo, &["commit", "-q", "-am", "trunk change"]);
+        // Unstaged work in the user's checkout must survive the sync
+        std::fs::write(repo.join("notes.txt"), "unsaved\n").unwrap();
+
+        let context = SyncContext {
+            context_type: SyncContextType::AgentWorktree {
+                agent_id: "1".to_string(),
+            },
+            current_dir: worktree.clone(),
+            trunk: "main".to_string(),
+            current_branch: "agents/1".to_string(),
+            git_root: worktree.clone(),
+        };
+        // The sync runs from the crate's directory, outside either checkout
+        sync_agent_worktree(&context, "1", ConflictResolution::AutoTheirs, false, false).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("a.txt")).unwrap(),
+            "trunk\n"
+        );
+        assert!(git::get_status_string_at(&worktree).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("notes.txt")).unwrap(),
+            "unsaved\n"
+        );
     }
 
     #[test]
@@ -0,0 +1,308 @@
+//! Watch command - React when agents print output matching the configured triggers
+//!
+//! Each trigger in `agents.yaml` pairs a regular expression with actions. `sprite watch`
+//! checks every line an agent writes to its transcript, plus the line its cursor is
+//! sitting on (a prompt such as "Proceed? (y/n)" never ends with a newline), and fires
+//! the actions of every matching trigger at most once per `cooldown_secs`.
+
+use crate::commands::config::{SpriteConfig, TriggerConfig};
+use crate::commands::selector::{self, Selector};
+use crate::error::SpriteError;
+use crate::utils::tmux;
+use crate::utils::transcript::{self, TranscriptTail};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// A trigger with its pattern compiled
+struct Rule {
+    trigger: TriggerConfig,
+    pattern: Regex,
+    selector: Selector,
+}
+
+/// What has already been seen of one agent's output
+struct AgentCursor {
+    tail: TranscriptTail,
+    prompt: Option<String>,
+}
+
+/// Execute the watch command.
+///
+/// Polls every `interval_secs`; with `once`, checks the current prompt lines a single time.
+pub fn execute(interval_secs: u64, once: bool) -> Result<()> {
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    if config.triggers.is_empty() {
+        println!("📭 No triggers configured.");
+        println!("💡 Add a 'triggers:' section to agents/agents.yaml, e.g.:");
+        println!("   triggers:");
+        println!("   - name: waiting-for-input");
+        println!("     pattern: '\\(y/n\\)\\s*$'");
+        println!("     notify: true");
+        return Ok(());
+    }
+
+    let rules = compile_rules(&config.triggers)?;
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    let session = config.effective_session_name();
+    if !tmux::session_exists(&session)? {
+        return Err(SpriteError::session_not_found(format!(
+            "No sprite session '{}' is running for this project. Use 'sprite start' first.",
+            session
+        ))
+        .into());
+    }
+
+    let mut cursors = HashMap::new();
+    for agent in &config.agents {
+        let tail = TranscriptTail::at_end(&transcript::agent_log_dir(&root, &agent.id))?;
+        cursors.insert(agent.id.clone(), AgentCursor { tail, prompt: None });
+    }
+
+    println!(
+        "👀 Watching {} agent(s) for {} trigger(s)... (Ctrl+C to stop)",
+        config.agents.len(),
+        rules.len()
+    );
+
+    let mut watcher = Watcher {
+        config,
+        root,
+        session,
+        rules,
+        cursors,
+        last_fired: HashMap::new(),
+    };
+    loop {
+        if !tmux::session_exists(&watcher.session)? {
+            println!("🛑 Session '{}' ended; stopping.", watcher.session);
+            return Ok(());
+        }
+        watcher.poll()?;
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(interval_secs.max(1)));
+    }
+}
+
+fn compile_rules(triggers: &[TriggerConfig]) -> Result<Vec<Rule>> {
+    triggers
+        .iter()
+        .map(|trigger| {
+            let pattern = trigger.compile()?;
+            Ok(Rule {
+                trigger: trigger.clone(),
+                pattern,
+                selector: Selector::parse(&trigger.agents)?,
+            })
+        })
+        .collect()
+}
+
+struct Watcher {
+    config: SpriteConfig,
+    root: PathBuf,
+    session: String,
+    rules: Vec<Rule>,
+    cursors: HashMap<String, AgentCursor>,
+    last_fired: HashMap<(String, String), Instant>,
+}
+
+impl Watcher {
+    /// Check the new output of every agent once.
+    fn poll(&mut self) -> Result<()> {
+        let states = if self.rules.iter().any(|rule| rule.selector.uses_state()) {
+            selector::agent_states(&self.config)?
+        } else {
+            HashMap::new()
+        };
+        let panes = tmux::list_agent_panes(&self.session)?;
+
+        for agent in self.config.agents.clone() {
+            let pane = match crate::commands::apply::find_agent_pane(&panes, &agent, &self.root) {
+                Some(pane) => pane.pane_id.clone(),
+                None => continue,
+            };
+            let lines = self.new_lines(&agent.id, &pane)?;
+            if lines.is_empty() {
+                continue;
+            }
+
+            for index in 0..self.rules.len() {
+                let rule = &self.rules[index];
+                if !rule
+                    .selector
+                    .resolve(&self.config, &states)?
+                    .contains(&agent.id)
+                {
+                    continue;
+                }
+                let matched = match lines.iter().find(|line| rule.pattern.is_match(line)) {
+                    Some(line) => line.clone(),
+                    None => continue,
+                };
+
+                let key = (agent.id.clone(), rule.trigger.name.clone());
+                let cooldown = Duration::from_secs(rule.trigger.cooldown_secs);
+                if self
+                    .last_fired
+                    .get(&key)
+                    .is_some_and(|at| at.elapsed() < cooldown)
+                {
+                    continue;
+                }
+                self.last_fired.insert(key, Instant::now());
+
+                let trigger = rule.trigger.clone();
+                self.fire(&trigger, &agent.id, &pane, &matched);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transcript lines written since the last poll, plus the prompt line if it changed.
+    fn new_lines(&mut self, agent_id: &str, pane: &str) -> Result<Vec<String>> {
+        let cursor = match self.cursors.get_mut(agent_id) {
+            Some(cursor) => cursor,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut lines: Vec<String> = cursor
+            .tail
+            .read_new()?
+            .into_iter()
+            .map(|line| line.text)
+            .collect();
+
+        let screen = tmux::capture_pane(&self.session, pane)?;
+        let prompt = screen
+            .lines()
+            .rev()
+            .map(|line| line.trim_end())
+            .find(|line| !line.is_empty())
+            .map(|line| line.to_string());
+        if prompt != cursor.prompt {
+            if let Some(prompt) = &prompt {
+                if lines.last() != Some(prompt) {
+                    lines.push(prompt.clone());
+                }
+            }
+            cursor.prompt = prompt;
+        }
+
+        Ok(lines)
+    }
+
+    /// Run the actions of a trigger; failures are reported without stopping the watch.
+    fn fire(&mut self, trigger: &TriggerConfig, agent_id: &str, pane: &str, line: &str) {
+        println!(
+            "🔔 [{}] Agent {}: '{}' matched: {}",
+            chrono::Local::now().format("%H:%M:%S"),
+            agent_id,
+            trigger.name,
+            line.trim()
+        );
+
+        if trigger.notify {
+            let message = format!(
+                "Sprite: agent {} - {}: {}",
+                agent_id,
+                trigger.name,
+                line.trim()
+            );
+            if let Err(e) = tmux::display_message(&self.session, &message) {
+                eprintln!("   ⚠️  Failed to notify: {}", e);
+            }
+        }
+
+        if trigger.mark_error {
+            match mark_agent_error(agent_id) {
+                Ok(()) => {
+                    println!("   🔴 Marked agent {} as Error", agent_id);
+                    if let Some(agent) = self.config.agents.iter_mut().find(|a| a.id == agent_id) {
+                        agent.status = "Error".to_string();
+                    }
+                }
+                Err(e) => eprintln!("   ⚠️  Failed to mark agent {} as Error: {}", agent_id, e),
+            }
+        }
+
+        if let Some(reply) = &trigger.reply {
+            match tmux::paste_text(pane, reply, true) {
+                Ok(()) => println!("   ↩️  Replied '{}'", reply),
+                Err(e) => eprintln!("   ⚠️  Failed to reply: {}", e),
+            }
+        }
+
+        if let Some(hook) = &trigger.hook {
+            match run_hook(hook, &self.root, agent_id, &trigger.name, line) {
+                Ok(()) => println!("   🪝 Ran hook: {}", hook),
+                Err(e) => eprintln!("   ⚠️  Hook failed: {}", e),
+            }
+        }
+    }
+}
+
+/// Record the agent as errored in the project configuration.
+fn mark_agent_error(agent_id: &str) -> Result<()> {
+    let mut config = SpriteConfig::load_project()?;
+    let agent = config
+        .agents
+        .iter_mut()
+        .find(|agent| agent.id == agent_id)
+        .ok_or_else(|| SpriteError::agent_not_found(agent_id))?;
+    if agent.status == "Error" {
+        return Ok(());
+    }
+    agent.status = "Error".to_string();
+    config.save()
+}
+
+/// Run a trigger hook with the match described in `SPRITE_*` variables.
+fn run_hook(hook: &str, root: &Path, agent_id: &str, trigger: &str, line: &str) -> Result<()> {
+    let status = std::process::Command::new("sh")
+        .args(["-c", hook])
+        .current_dir(root)
+        .env("SPRITE_AGENT", agent_id)
+        .env("SPRITE_TRIGGER", trigger)
+        .env("SPRITE_MATCH", line)
+        .status()
+        .with_context(|| format!("Failed to run hook '{}'", hook))?;
+
+    if !status.success() {
+        return Err(SpriteError::process(hook, status.code(), "", "").into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_rules() {
+        let trigger = |pattern: &str, agents: &str| TriggerConfig {
+            name: "prompt".to_string(),
+            pattern: pattern.to_string(),
+            agents: agents.to_string(),
+            notify: true,
+            mark_error: false,
+            reply: None,
+            hook: None,
+            cooldown_secs: 60,
+        };
+
+        let rules = compile_rules(&[trigger(r"\(y/n\)\s*$", "all")]).unwrap();
+        assert!(rules[0].pattern.is_match("Do you want to proceed? (y/n) "));
+        assert!(!rules[0].pattern.is_match("Do you want to proceed? (y/n) y"));
+
+        assert!(compile_rules(&[trigger("(unclosed", "all")]).is_err());
+        assert!(compile_rules(&[trigger("panic", "1+")]).is_err());
+    }
+}
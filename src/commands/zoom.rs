@@ -1,17 +1,14 @@
 //! Zoom command - Focus on specific agent panes in tmux sessions
 
+use crate::commands::apply;
 use crate::commands::config::SpriteConfig;
+use crate::commands::selector;
 use crate::error::SpriteError;
 use crate::utils::tmux;
 use anyhow::{Context, Result};
 
 /// Execute the zoom command with the given parameters.
 pub fn execute(agent: Option<String>, unzoom: bool, list: bool) -> Result<()> {
-    // Load current configuration
-    if SpriteConfig::load().is_ok() {
-        // Configuration loaded successfully
-    }
-
     // Handle list option
     if list {
         list_panes()?;
@@ -25,57 +22,82 @@ pub fn execute(agent: Option<String>, unzoom: bool, list: bool) -> Result<()> {
     }
 
     // If no agent specified, show available panes
-    let agent_id = if let Some(agent) = agent {
+    let agent_expr = if let Some(agent) = agent {
         agent
     } else {
         list_panes_with_prompt()?;
         return Ok(());
     };
 
-    // Find the active session
-    let sessions = tmux::list_sessions().context("Failed to list tmux sessions")?;
-
-    let active_session = sessions
-        .iter()
-        .find(|s| s.name.starts_with("sprite-") && s.attached)
-        .or_else(|| sessions.iter().find(|s| s.name.starts_with("sprite-")))
-        .ok_or_else(|| SpriteError::session_not_found("No active sprite session found"))?;
-
-    // Get session panes
-    let panes = tmux::get_session_panes(&active_session.name)
-        .with_context(|| format!("Failed to get panes for session '{}'", active_session.name))?;
-
-    // Find the target pane
-    let target_pane = find_target_pane(&panes, &agent_id)?;
+    // Find the session that belongs to this project
+    let session_name = find_project_session()?.ok_or_else(|| {
+        SpriteError::session_not_found("No sprite session is running for this project")
+    })?;
+
+    // Resolve the selector to the one agent to zoom on
+    let config = SpriteConfig::load().context("Failed to load configuration")?;
+    let agent_id = match selector::select(&config, &agent_expr)?.as_slice() {
+        [agent_id] => agent_id.clone(),
+        [] => {
+            return Err(SpriteError::validation(
+                format!("No agent matches '{}'", agent_expr),
+                Some("agent"),
+                Some(agent_expr.as_str()),
+            )
+            .into())
+        }
+        several => {
+            return Err(SpriteError::validation(
+                format!(
+                    "'{}' matches several agents ({}); zoom needs exactly one",
+                    agent_expr,
+                    several.join(", ")
+                ),
+                Some("agent"),
+                Some(agent_expr.as_str()),
+            )
+            .into())
+        }
+    };
+    let agent = config
+        .get_agent(&agent_id)
+        .expect("selected agents are configured");
+
+    // Find the agent's pane
+    let panes = tmux::list_agent_panes(&session_name)
+        .with_context(|| format!("Failed to get panes for session '{}'", session_name))?;
+    let root = config.project_root().unwrap_or_default();
+    let pane = apply::find_agent_pane(&panes, agent, &root).ok_or_else(|| {
+        SpriteError::validation(
+            format!("Agent '{}' not found or no active pane", agent_id),
+            Some("agent"),
+            Some(agent_id.as_str()),
+        )
+    })?;
 
     // Zoom to the target pane
-    zoom_to_pane(&active_session.name, target_pane)?;
+    zoom_to_pane(&pane.pane_id, &agent_id)?;
 
     Ok(())
 }
 
 /// List all available panes in the active session
 fn list_panes() -> Result<()> {
-    // Find the active session
-    let sessions = tmux::list_sessions().context("Failed to list tmux sessions")?;
-
-    let active_session = sessions
-        .iter()
-        .find(|s| s.name.starts_with("sprite-") && s.attached)
-        .or_else(|| sessions.iter().find(|s| s.name.starts_with("sprite-")));
-
-    if active_session.is_none() {
-        println!("📭 No active sprite session found.");
-        println!("💡 Use 'sprite start' to create a new session.");
-        return Ok(());
-    }
+    // Find the session that belongs to this project
+    let session_name = match find_project_session()? {
+        Some(name) => name,
+        None => {
+            println!("📭 No sprite session is running for this project.");
+            println!("💡 Use 'sprite start' to create a new session.");
+            return Ok(());
+        }
+    };
 
-    let active_session = active_session.unwrap();
-    println!("🎬 Active Session: {}", active_session.name);
+    println!("🎬 Active Session: {}", session_name);
 
     // Get session panes
-    let panes = tmux::get_session_panes(&active_session.name)
-        .with_context(|| format!("Failed to get panes for session '{}'", active_session.name))?;
+    let panes = tmux::get_session_panes(&session_name)
+        .with_context(|| format!("Failed to get panes for session '{}'", session_name))?;
 
     if panes.is_empty() {
         println!("   No panes found in session.");
@@ -100,13 +122,23 @@ fn list_panes() -> Result<()> {
 
     println!();
     println!("💡 Usage:");
-    println!("  sprite zoom <agent_number>  # Focus on specific agent pane");
+    println!("  sprite zoom <agent>          # Focus on an agent pane (ID or selector)");
     println!("  sprite zoom --unzoom         # Return to normal layout");
     println!("  sprite zoom --list           # Show this pane list");
 
     Ok(())
 }
 
+/// Find the running tmux session that belongs to the current project
+fn find_project_session() -> Result<Option<String>> {
+    let session_name = crate::commands::config::current_session_name()?;
+    if tmux::session_exists(&session_name)? {
+        Ok(Some(session_name))
+    } else {
+        Ok(None)
+    }
+}
+
 /// List panes with a prompt for selection
 fn list_panes_with_prompt() -> Result<()> {
     list_panes()?;
@@ -116,62 +148,31 @@ fn list_panes_with_prompt() -> Result<()> {
     Ok(())
 }
 
-/// Find the target pane for the given agent
-fn find_target_pane(panes: &[tmux::PaneInfo], agent_id: &str) -> Result<usize> {
-    // Try to parse as numeric agent ID first
-    if let Ok(agent_num) = agent_id.parse::<usize>() {
-        let expected_pane = agent_num - 1; // 0-indexed
-        if expected_pane < panes.len() {
-            return Ok(expected_pane);
-        }
-    }
-
-    // Try to find by agent ID in path
-    for (i, pane) in panes.iter().enumerate() {
-        if let Some(path) = &pane.current_path {
-            if path.contains(&format!("agents/{}", agent_id)) || path.ends_with(agent_id) {
-                return Ok(i);
-            }
-        }
-    }
-
-    // Try to find by agent ID in command
-    for (i, pane) in panes.iter().enumerate() {
-        if let Some(command) = &pane.current_command {
-            if command.contains(agent_id) {
-                return Ok(i);
-            }
-        }
-    }
-
-    Err(SpriteError::validation(
-        format!("Agent '{}' not found or no active pane", agent_id),
-        Some("agent".to_string()),
-        Some(agent_id.to_string()),
-    )
-    .into())
-}
-
 /// Zoom to the specified pane
-fn zoom_to_pane(_session_name: &str, pane_index: usize) -> Result<()> {
-    println!("🔍 Zooming to pane {}...", pane_index + 1);
-
-    // Use tmux's select-pane command to focus on the pane
-    let output = std::process::Command::new("tmux")
-        .args(["select-pane", "-t", &pane_index.to_string()])
-        .output()
-        .with_context(|| "Failed to select pane")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(
-            SpriteError::tmux_with_source("Failed to select pane", stderr.to_string()).into(),
-        );
+fn zoom_to_pane(pane_id: &str, agent_id: &str) -> Result<()> {
+    println!("🔍 Zooming to agent {}...", agent_id);
+
+    // Agents added later live in their own windows, so bring the pane's window forward first
+    for args in [
+        ["select-window", "-t", pane_id],
+        ["select-pane", "-t", pane_id],
+    ] {
+        let output = std::process::Command::new("tmux")
+            .args(args)
+            .output()
+            .with_context(|| "Failed to select pane")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(
+                SpriteError::tmux_with_source("Failed to select pane", stderr.to_string()).into(),
+            );
+        }
     }
 
     // Optionally maximize the pane (tmux has zoom functionality)
     let zoom_output = std::process::Command::new("tmux")
-        .args(["resize-pane", "-Z"])
+        .args(["resize-pane", "-Z", "-t", pane_id])
         .output()
         .with_context(|| "Failed to zoom pane")?;
 
@@ -180,7 +181,7 @@ fn zoom_to_pane(_session_name: &str, pane_index: usize) -> Result<()> {
         eprintln!("⚠️  Warning: Could not maximize pane: {}", stderr);
     }
 
-    println!("✓ Zoomed to pane {}", pane_index + 1);
+    println!("✓ Zoomed to agent {}", agent_id);
     println!("💡 Press Ctrl+B then Z to unzoom and return to normal layout");
 
     Ok(())
@@ -332,19 +332,8 @@ impl DeliveryConfirmation {
 
     /// Send command to agent via tmux
     async fn send_command_to_agent(&self, agent_pane: &str, command: &str) -> Result<()> {
-        // Send the command text first
-        std::process::Command::new("tmux")
-            .args(["send-keys", "-t", agent_pane, command])
-            .output()
-            .context("Failed to send command text to agent")?;
-
-        // Small delay then send Enter to execute
-        tokio::time::sleep(Duration::from_millis(100)).await;
-
-        std::process::Command::new("tmux")
-            .args(["send-keys", "-t", agent_pane, "C-m"])
-            .output()
-            .context("Failed to send Enter key to agent")?;
+        crate::utils::tmux::paste_text(agent_pane, command, true)
+            .context("Failed to send command to agent")?;
 
         Ok(())
     }
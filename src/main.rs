@@ -44,6 +44,15 @@ fn main() -> Result<()> {
             commands::start::execute(session_name, layout, detach, force)?;
             Ok(())
         }
+        cli::Commands::Apply {
+            watch,
+            interval,
+            yes,
+            dry_run,
+        } => {
+            commands::apply::execute(watch, interval, yes, dry_run)?;
+            Ok(())
+        }
         cli::Commands::Attach { session_name, list } => {
             commands::attach::execute(session_name, list)?;
             Ok(())
@@ -52,8 +61,9 @@ fn main() -> Result<()> {
             session_name,
             force,
             all,
+            agents,
         } => {
-            commands::kill::execute(session_name, force, all)?;
+            commands::kill::execute(session_name, force, all, agents.as_deref())?;
             Ok(())
         }
 
@@ -61,6 +71,9 @@ fn main() -> Result<()> {
             agent,
             command,
             args,
+            file,
+            template,
+            vars,
             timeout,
             work_dir,
             env_vars,
@@ -68,8 +81,11 @@ fn main() -> Result<()> {
         } => {
             commands::hey::execute(
                 &agent,
-                &command,
+                command.as_deref(),
                 &args,
+                file.as_deref(),
+                template.as_deref(),
+                &vars,
                 timeout,
                 work_dir.as_deref(),
                 &env_vars,
@@ -95,6 +111,22 @@ fn main() -> Result<()> {
             commands::remove::execute(&agent, force, keep_workspace, merge_branch)?;
             Ok(())
         }
+        cli::Commands::Integrate {
+            agents,
+            into,
+            base,
+            rebase,
+            verify,
+        } => {
+            commands::integrate::execute(
+                agents,
+                &into,
+                base.as_deref(),
+                rebase,
+                verify.as_deref(),
+            )?;
+            Ok(())
+        }
 
         cli::Commands::Zoom {
             agent,
@@ -104,12 +136,98 @@ fn main() -> Result<()> {
             commands::zoom::execute(agent, unzoom, list)?;
             Ok(())
         }
+        cli::Commands::Overlap { base, no_predict } => {
+            commands::overlap::execute(base.as_deref(), no_predict)?;
+            Ok(())
+        }
+        cli::Commands::Projects { command } => {
+            commands::projects::execute(command)?;
+            Ok(())
+        }
+        cli::Commands::Logs {
+            agent,
+            follow,
+            since,
+            grep,
+            capture,
+        } => {
+            commands::logs::execute(
+                &agent,
+                follow,
+                since.as_deref(),
+                grep.as_deref(),
+                capture.as_deref(),
+            )?;
+            Ok(())
+        }
+        cli::Commands::Checkpoint {
+            command,
+            agents,
+            message,
+            watch,
+            interval,
+            keep,
+        } => {
+            commands::checkpoint::execute(
+                command,
+                commands::checkpoint::CheckpointOptions {
+                    agents: agents.as_deref(),
+                    message: message.as_deref(),
+                    watch,
+                    interval_secs: interval,
+                    keep,
+                },
+            )?;
+            Ok(())
+        }
+        cli::Commands::Env { agent, format } => {
+            commands::env::execute(&agent, format)?;
+            Ok(())
+        }
+        cli::Commands::Cache { command } => {
+            commands::cache::execute(command)?;
+            Ok(())
+        }
+        cli::Commands::Sandbox { command } => {
+            commands::sandbox::execute(command)?;
+            Ok(())
+        }
+        cli::Commands::Ports { all } => {
+            commands::ports::execute(all)?;
+            Ok(())
+        }
+        cli::Commands::BlameAgents {
+            paths,
+            rev,
+            summary,
+            commits,
+        } => {
+            commands::blame_agents::execute(&paths, &rev, summary, commits)?;
+            Ok(())
+        }
+        cli::Commands::Hooks { command } => {
+            commands::hooks::execute(command)?;
+            Ok(())
+        }
+        cli::Commands::Patch { command } => {
+            commands::patch::execute(command)?;
+            Ok(())
+        }
+        cli::Commands::Watch { interval, once } => {
+            commands::watch::execute(interval, once)?;
+            Ok(())
+        }
+        cli::Commands::Prompts { command } => {
+            commands::prompts::execute(command)?;
+            Ok(())
+        }
         cli::Commands::Status {
             session_name,
             cleanup,
             detailed,
+            agents,
         } => {
-            commands::status::execute(session_name, cleanup, detailed)?;
+            commands::status::execute(session_name, cleanup, detailed, agents.as_deref())?;
             Ok(())
         }
         cli::Commands::Guide {
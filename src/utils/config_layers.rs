@@ -0,0 +1,431 @@
+//! Layered configuration resolution.
+//!
+//! Configuration is resolved from several layers, each overriding the ones
+//! before it: user-global defaults, the committed project file, uncommitted
+//! local overrides and `SPRITE_*` environment variables. Layers are merged as
+//! YAML value trees so a layer only needs to contain the keys it changes.
+
+use crate::utils::yaml_path;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Prefix for environment variable overrides
+pub const ENV_PREFIX: &str = "SPRITE_";
+
+/// `SPRITE_*` variables with their own meaning that are never configuration overrides
+const RESERVED_ENV_VARS: &[&str] = &[
+    "SPRITE_PROJECT_ROOT",
+    "SPRITE_SESSION",
+    "SPRITE_DISABLE_EXE_DISCOVERY",
+    "SPRITE_AGENT_ID",
+    "SPRITE_BRANCH",
+    "SPRITE_WORK_DIR",
+    "SPRITE_PORT_BASE",
+];
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Built-in default, not set by any layer
+    Default,
+    /// `~/.config/sprite/config.yaml`
+    User(PathBuf),
+    /// The committed `agents/agents.yaml`
+    Project(PathBuf),
+    /// The uncommitted `agents/agents.local.yaml`
+    Local(PathBuf),
+    /// An environment variable
+    Env(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::User(path) => write!(f, "user ({})", path.display()),
+            Origin::Project(path) => write!(f, "project ({})", path.display()),
+            Origin::Local(path) => write!(f, "local ({})", path.display()),
+            Origin::Env(var) => write!(f, "env ({})", var),
+        }
+    }
+}
+
+/// Effective value tree together with the origin of every leaf
+#[derive(Debug, Clone, Default)]
+pub struct Resolved {
+    pub tree: Value,
+    pub origins: BTreeMap<String, Origin>,
+    /// `SPRITE_*` variables that looked like overrides but matched no key
+    pub unmatched_env: Vec<String>,
+}
+
+impl Resolved {
+    /// Merge a file layer on top of what has been resolved so far
+    pub fn apply(&mut self, layer: Value, origin: Origin) {
+        for (path, _) in flatten(&layer) {
+            self.origins.insert(path, origin.clone());
+        }
+        merge(&mut self.tree, layer);
+    }
+
+    /// Fill every agent's unset fields from a partial agent mapping
+    ///
+    /// Values any layer already set for an agent take precedence.
+    pub fn apply_agent_defaults(&mut self, defaults: &Value, origin: Origin) {
+        let agents = match self.tree.get_mut("agents") {
+            Some(Value::Sequence(agents)) => agents,
+            _ => return,
+        };
+
+        for agent in agents.iter_mut() {
+            let id = match agent.get("id") {
+                Some(id) => yaml_path::format_value(id),
+                None => continue,
+            };
+            let mut merged = defaults.clone();
+            merge(&mut merged, agent.clone());
+            *agent = merged;
+
+            for (path, _) in flatten(defaults) {
+                self.origins
+                    .entry(format!("agents.{}.{}", id, path))
+                    .or_insert_with(|| origin.clone());
+            }
+        }
+    }
+
+    /// Apply `SPRITE_*` environment overrides to the resolved tree
+    pub fn apply_env<I>(&mut self, vars: I)
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (var, raw) in vars {
+            let name = match var.strip_prefix(ENV_PREFIX) {
+                Some(name) if !name.is_empty() => name,
+                _ => continue,
+            };
+            if RESERVED_ENV_VARS.contains(&var.as_str()) {
+                continue;
+            }
+
+            let path = match resolve_env_path(&self.tree, name) {
+                Some(path) => path,
+                None => {
+                    self.unmatched_env.push(var);
+                    continue;
+                }
+            };
+
+            // Keep string fields strings, so SPRITE_SESSION_NAME=123 stays "123"
+            let value = match yaml_path::get(&self.tree, &path) {
+                Ok(Value::String(_)) => Value::String(raw),
+                _ => yaml_path::parse_value(&raw),
+            };
+            if yaml_path::set(&mut self.tree, &path, value).is_ok() {
+                self.origins
+                    .insert(origin_key(&self.tree, &path), Origin::Env(var));
+            }
+        }
+    }
+
+    /// Origin of the value at a dotted path, or of the closest parent that has one
+    pub fn origin_of(&self, path: &str) -> Origin {
+        let mut key = path.to_string();
+        loop {
+            if let Some(origin) = self.origins.get(&key) {
+                return origin.clone();
+            }
+            match key.rfind('.') {
+                Some(index) => key.truncate(index),
+                None => return Origin::Default,
+            }
+        }
+    }
+}
+
+/// Deep-merge `overlay` into `base`
+///
+/// Maps merge key by key, lists of entries with an `id` merge entry by entry
+/// (new ids are appended), and anything else is replaced. A null in the
+/// overlay never clears a value from a lower layer.
+pub fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (_, Value::Null) => {}
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(overlay))
+            if is_keyed_list(base) && is_keyed_list(&overlay) =>
+        {
+            for item in overlay {
+                match base
+                    .iter_mut()
+                    .find(|existing| existing.get("id") == item.get("id"))
+                {
+                    Some(existing) => merge(existing, item),
+                    None => base.push(item),
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Flatten a value tree into dotted leaf paths, addressing keyed list entries by id
+///
+/// Null leaves are skipped since they never override anything.
+pub fn flatten(value: &Value) -> Vec<(String, Value)> {
+    let mut leaves = Vec::new();
+    flatten_into(value, String::new(), &mut leaves);
+    leaves
+}
+
+fn flatten_into(value: &Value, prefix: String, leaves: &mut Vec<(String, Value)>) {
+    let join = |segment: String| {
+        if prefix.is_empty() {
+            segment
+        } else {
+            format!("{}.{}", prefix, segment)
+        }
+    };
+
+    match value {
+        Value::Null => {}
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_into(child, join(yaml_path::format_value(key)), leaves);
+            }
+        }
+        Value::Sequence(seq) if !seq.is_empty() && is_keyed_list(seq) => {
+            for item in seq {
+                let id = item
+                    .get("id")
+                    .map(yaml_path::format_value)
+                    .unwrap_or_default();
+                if let Value::Mapping(map) = item {
+                    for (key, child) in map {
+                        let segment = format!("{}.{}", id, yaml_path::format_value(key));
+                        flatten_into(child, join(segment), leaves);
+                    }
+                }
+            }
+        }
+        other => leaves.push((prefix, other.clone())),
+    }
+}
+
+/// Whether every element of a list is a mapping with an `id`
+fn is_keyed_list(seq: &[Value]) -> bool {
+    seq.iter().all(|item| item.get("id").is_some())
+}
+
+/// Map an environment variable name (without prefix) onto an existing config path
+///
+/// `__` separates path segments and each segment matches keys case-insensitively,
+/// so `SPRITE_SETTINGS__DEFAULT_SHELL` addresses `settings.default_shell`. A new
+/// key may only be introduced inside an existing map, such as `env_vars`.
+fn resolve_env_path(tree: &Value, name: &str) -> Option<Vec<String>> {
+    let segments: Vec<&str> = name.split("__").collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let mut current = Some(tree);
+    for (depth, segment) in segments.iter().enumerate() {
+        let node = current?;
+        let is_last = depth + 1 == segments.len();
+        match node {
+            Value::Mapping(map) => {
+                let key = map
+                    .keys()
+                    .filter_map(Value::as_str)
+                    .find(|key| key.eq_ignore_ascii_case(segment));
+                match key {
+                    Some(key) => {
+                        path.push(key.to_string());
+                        current = map.get(key);
+                    }
+                    None if is_last && depth > 0 => {
+                        path.push(segment.to_string());
+                        current = None;
+                    }
+                    None => return None,
+                }
+            }
+            Value::Sequence(_) => {
+                let key = segment.to_string();
+                let mut probe = path.clone();
+                probe.push(key.clone());
+                current = Some(yaml_path::get(tree, &probe).ok()?);
+                path.push(key);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+/// Origin key for a resolved path, using ids rather than indices for keyed lists
+fn origin_key(tree: &Value, path: &[String]) -> String {
+    let mut segments = Vec::new();
+    let mut current = Some(tree);
+    for segment in path {
+        let node = current;
+        current = node.and_then(|n| yaml_path::get(n, std::slice::from_ref(segment)).ok());
+        match (node, current) {
+            (Some(Value::Sequence(_)), Some(item)) => match item.get("id") {
+                Some(id) => segments.push(yaml_path::format_value(id)),
+                None => segments.push(segment.clone()),
+            },
+            _ => segments.push(segment.clone()),
+        }
+    }
+    segments.join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    fn project() -> Value {
+        yaml(
+            r#"
+session_name: demo
+agents:
+  - id: "1"
+    model: claude-sonnet-4
+    config:
+      shell: null
+      env_vars: {}
+  - id: "2"
+    model: claude-sonnet-4
+    config:
+      shell: zsh
+      env_vars: {}
+settings:
+  default_shell: bash
+  logging:
+    level: info
+"#,
+        )
+    }
+
+    #[test]
+    fn test_merge_layers_and_origins() {
+        let user = PathBuf::from("/home/me/.config/sprite/config.yaml");
+        let proj = PathBuf::from("agents/agents.yaml");
+        let local = PathBuf::from("agents/agents.local.yaml");
+
+        let mut resolved = Resolved::default();
+        resolved.apply(
+            yaml("settings: {default_shell: fish}"),
+            Origin::User(user.clone()),
+        );
+        resolved.apply(project(), Origin::Project(proj.clone()));
+        resolved.apply(
+            yaml(
+                r#"
+agents:
+  - id: "2"
+    config:
+      env_vars: {RUST_LOG: debug}
+  - id: "3"
+    model: local-model
+settings:
+  logging: {level: trace}
+"#,
+            ),
+            Origin::Local(local.clone()),
+        );
+        resolved.apply_agent_defaults(
+            &yaml("{model: ignored, config: {shell: fish}}"),
+            Origin::User(user.clone()),
+        );
+
+        let get = |p: &str| {
+            yaml_path::format_value(
+                yaml_path::get(&resolved.tree, &yaml_path::split_path(p).unwrap()).unwrap(),
+            )
+        };
+        // The project layer overrides the user layer; local overrides the project
+        assert_eq!(get("settings.default_shell"), "bash");
+        assert_eq!(get("settings.logging.level"), "trace");
+        assert_eq!(get("agents.2.config.env_vars.RUST_LOG"), "debug");
+        assert_eq!(get("agents.2.config.shell"), "zsh");
+        // New ids are appended; agent defaults only fill gaps
+        assert_eq!(get("agents.3.model"), "local-model");
+        assert_eq!(get("agents.1.model"), "claude-sonnet-4");
+        assert_eq!(get("agents.1.config.shell"), "fish");
+
+        assert_eq!(
+            resolved.origin_of("settings.logging.level"),
+            Origin::Local(local.clone())
+        );
+        assert_eq!(
+            resolved.origin_of("agents.1.config.shell"),
+            Origin::User(user)
+        );
+        assert_eq!(
+            resolved.origin_of("agents.2.config.shell"),
+            Origin::Project(proj)
+        );
+        assert_eq!(resolved.origin_of("sync.auto_sync"), Origin::Default);
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut resolved = Resolved::default();
+        resolved.apply(project(), Origin::Project(PathBuf::from("agents.yaml")));
+
+        resolved.apply_env(vec![
+            ("SPRITE_SESSION_NAME".to_string(), "123".to_string()),
+            (
+                "SPRITE_SETTINGS__DEFAULT_SHELL".to_string(),
+                "zsh".to_string(),
+            ),
+            (
+                "SPRITE_AGENTS__2__CONFIG__ENV_VARS__TOKEN".to_string(),
+                "x".to_string(),
+            ),
+            ("SPRITE_SESSION".to_string(), "ignored".to_string()),
+            ("SPRITE_NO_SUCH_KEY".to_string(), "1".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ]);
+
+        assert_eq!(
+            resolved.unmatched_env,
+            vec!["SPRITE_NO_SUCH_KEY".to_string()]
+        );
+        assert_eq!(
+            resolved.tree["session_name"],
+            Value::String("123".to_string())
+        );
+        assert_eq!(
+            resolved.tree["settings"]["default_shell"],
+            Value::String("zsh".to_string())
+        );
+        assert_eq!(
+            resolved.tree["agents"][1]["config"]["env_vars"]["TOKEN"],
+            Value::String("x".to_string())
+        );
+        assert_eq!(
+            resolved.origin_of("agents.2.config.env_vars.TOKEN"),
+            Origin::Env("SPRITE_AGENTS__2__CONFIG__ENV_VARS__TOKEN".to_string())
+        );
+    }
+}
@@ -5,8 +5,10 @@
 
 use crate::error::SpriteError;
 use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// Get the current git repository root directory.
 #[allow(dead_code)]
@@ -64,7 +66,41 @@ pub fn branch_exists(branch: &str) -> Result<bool> {
     Ok(output.status.success())
 }
 
+/// The repository's default branch: the one `origin/HEAD` points to, else
+/// `main` or `master`, whichever exists.
+pub fn default_branch() -> Option<String> {
+    default_branch_at(Path::new("."))
+}
+
+/// The default branch of the repository at `path`; see [`default_branch`].
+pub fn default_branch_at(path: &Path) -> Option<String> {
+    let remote_head = Command::new("git")
+        .current_dir(path)
+        .args([
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .strip_prefix("origin/")
+                .map(str::to_string)
+        });
+    remote_head.or_else(|| {
+        ["main", "master"]
+            .into_iter()
+            .find(|branch| rev_parse_at(path, &format!("refs/heads/{}", branch)).is_ok())
+            .map(str::to_string)
+    })
+}
+
 /// Create a new git branch.
+#[allow(dead_code)]
 pub fn create_branch(branch: &str) -> Result<()> {
     create_branch_from(branch, "HEAD")
 }
@@ -91,6 +127,116 @@ pub fn create_branch_from(branch: &str, from: &str) -> Result<()> {
     Ok(())
 }
 
+/// Create a new git branch pointing at a start point without checking it out.
+pub fn create_branch_at(branch: &str, start_point: &str) -> Result<()> {
+    if branch_exists(branch)? {
+        return Err(SpriteError::git(format!("Branch '{}' already exists", branch)).into());
+    }
+
+    let output = Command::new("git")
+        .args(["branch", branch, start_point])
+        .output()
+        .with_context(|| format!("Failed to create branch '{}' at '{}'", branch, start_point))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to create branch '{}' at '{}'", branch, start_point),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Check whether a revision (branch, tag, commit, remote ref) resolves to a commit.
+pub fn ref_exists(reference: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", reference),
+        ])
+        .output()
+        .with_context(|| format!("Failed to check if '{}' exists", reference))?;
+
+    Ok(output.status.success())
+}
+
+/// Get the remote-tracking branch a local branch follows, e.g. `origin/main`.
+pub fn upstream_of_at(path: &Path, branch: &str) -> Option<String> {
+    git_at(
+        path,
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            &format!("{}@{{upstream}}", branch),
+        ],
+        "Failed to read upstream",
+    )
+    .ok()
+    .filter(|upstream| !upstream.is_empty())
+}
+
+/// List the names of the configured remotes.
+pub fn remote_names_at(path: &Path) -> Result<Vec<String>> {
+    Ok(git_at(path, &["remote"], "Failed to list remotes")?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Fetch one branch from a remote, updating its remote-tracking ref.
+pub fn fetch_branch_at(path: &Path, remote: &str, branch: &str) -> Result<()> {
+    git_at(
+        path,
+        &["fetch", "--quiet", remote, branch],
+        &format!("Failed to fetch '{}' from '{}'", branch, remote),
+    )
+    .map(|_| ())
+}
+
+/// Replace a branch with a lightweight tag at its tip; the branch must not be checked out.
+pub fn archive_branch_at(path: &Path, branch: &str, tag: &str) -> Result<()> {
+    git_at(
+        path,
+        &["tag", tag, &format!("refs/heads/{}", branch)],
+        &format!("Failed to create tag '{}' at '{}'", tag, branch),
+    )?;
+    git_at(
+        path,
+        &["branch", "-D", branch],
+        &format!("Failed to delete branch '{}'", branch),
+    )
+    .map(|_| ())
+}
+
+/// Create a new branch from a start point and check it out in a worktree.
+pub fn checkout_new_branch_at(path: &Path, branch: &str, start_point: &str) -> Result<()> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["checkout", "-b", branch, start_point])
+        .output()
+        .with_context(|| format!("Failed to create branch '{}' at {}", branch, path.display()))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!(
+                "Failed to create branch '{}' from '{}' at {}",
+                branch,
+                start_point,
+                path.display()
+            ),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
 /// Switch to an existing git branch.
 #[allow(dead_code)]
 pub fn switch_branch(branch: &str) -> Result<()> {
@@ -137,6 +283,12 @@ pub fn checkout_branch(path: &Path, branch: &str) -> Result<()> {
 
 /// Create a git worktree for an agent.
 pub fn create_worktree(path: &Path, branch: &str) -> Result<()> {
+    create_worktree_from(path, branch, "HEAD")
+}
+
+/// Create a git worktree for an agent, starting a missing branch at `start`
+/// (or at HEAD when `start` does not resolve).
+pub fn create_worktree_from(path: &Path, branch: &str, start: &str) -> Result<()> {
     // Ensure the parent directory exists
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).with_context(|| {
@@ -147,9 +299,10 @@ pub fn create_worktree(path: &Path, branch: &str) -> Result<()> {
         })?;
     }
 
-    // Check if branch exists first
+    // Create the branch without switching the current checkout to it
     if !branch_exists(branch)? {
-        create_branch(branch)?;
+        let start = if ref_exists(start)? { start } else { "HEAD" };
+        create_branch_at(branch, start)?;
     }
 
     // Try to create worktree with existing branch first
@@ -234,7 +387,7 @@ pub fn remove_worktree(path: &Path) -> Result<()> {
 /// List all git worktrees in the repository.
 pub fn list_worktrees() -> Result<Vec<WorktreeInfo>> {
     let output = Command::new("git")
-        .args(["worktree", "list"])
+        .args(["worktree", "list", "--porcelain"])
         .output()
         .with_context(|| "Failed to list worktrees")?;
 
@@ -255,8 +408,7 @@ pub fn list_worktrees() -> Result<Vec<WorktreeInfo>> {
 pub struct WorktreeInfo {
     /// Path to the worktree
     pub path: PathBuf,
-    /// Branch name
-    #[allow(dead_code)]
+    /// Branch name, empty when detached or bare
     pub branch: String,
     /// Whether the worktree is bare
     #[allow(dead_code)]
@@ -266,8 +418,12 @@ pub struct WorktreeInfo {
     pub id: Option<String>,
 }
 
-/// Parse the output of `git worktree list`.
+/// Parse the output of `git worktree list`, with or without `--porcelain`.
 fn parse_worktree_list(output: &str) -> Result<Vec<WorktreeInfo>> {
+    if output.starts_with("worktree ") {
+        return Ok(parse_worktree_porcelain(output));
+    }
+
     let mut worktrees = Vec::new();
 
     for line in output.lines() {
@@ -324,6 +480,45 @@ fn parse_worktree_list(output: &str) -> Result<Vec<WorktreeInfo>> {
     Ok(worktrees)
 }
 
+/// Parse `git worktree list --porcelain`, where paths may contain spaces.
+fn parse_worktree_porcelain(output: &str) -> Vec<WorktreeInfo> {
+    let mut worktrees: Vec<WorktreeInfo> = Vec::new();
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.push(WorktreeInfo {
+                path: PathBuf::from(path),
+                branch: String::new(),
+                bare: false,
+                id: None,
+            });
+            continue;
+        }
+        let worktree = match worktrees.last_mut() {
+            Some(worktree) => worktree,
+            None => continue,
+        };
+        if let Some(reference) = line.strip_prefix("branch ") {
+            worktree.branch = reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string();
+        } else if let Some(head) = line.strip_prefix("HEAD ") {
+            worktree.id = Some(head.to_string());
+        } else if line == "bare" {
+            worktree.bare = true;
+        }
+    }
+
+    // Only detached worktrees are identified by their commit
+    for worktree in &mut worktrees {
+        if !worktree.branch.is_empty() {
+            worktree.id = None;
+        }
+    }
+    worktrees
+}
+
 /// Check if the current directory is a git repository.
 pub fn is_git_repository() -> Result<bool> {
     is_git_repository_at(&std::env::current_dir()?)
@@ -708,37 +903,430 @@ pub fn get_branch_commit(branch: &str) -> Result<String> {
     Ok(commit)
 }
 
-/// Merge a branch into the current branch.
-pub fn merge_branch(branch: &str) -> Result<()> {
-    if !branch_exists(branch)? {
-        return Err(SpriteError::git(format!("Branch '{}' does not exist", branch)).into());
+/// Check whether `ancestor` is reachable from `descendant`.
+pub fn is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
+    is_ancestor_at(Path::new("."), ancestor, descendant)
+}
+
+/// Check whether `ancestor` is reachable from `descendant` in the repository at `path`.
+pub fn is_ancestor_at(path: &Path, ancestor: &str, descendant: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .output()
+        .with_context(|| {
+            format!(
+                "Failed to check if '{}' is an ancestor of '{}'",
+                ancestor, descendant
+            )
+        })?;
+
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(SpriteError::git_with_source(
+            format!(
+                "Failed to check if '{}' is an ancestor of '{}'",
+                ancestor, descendant
+            ),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into()),
     }
+}
 
+/// Get the commit HEAD points to in a specific worktree.
+pub fn get_head_commit_at(path: &Path) -> Result<String> {
     let output = Command::new("git")
-        .args(["merge", "--no-ff", branch])
+        .current_dir(path)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .with_context(|| format!("Failed to get HEAD commit at {}", path.display()))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to get HEAD commit at {}", path.display()),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Count the commits in a revision range such as `a..b`, as seen from `path`.
+pub fn count_commits_at(path: &Path, range: &str) -> Result<usize> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["rev-list", "--count", range])
+        .output()
+        .with_context(|| format!("Failed to count commits in '{}'", range))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to count commits in '{}'", range),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(0))
+}
+
+/// List commits of `head` whose changes are not yet in `upstream`, oldest first.
+///
+/// Uses `git cherry`, so commits already applied under a different hash are skipped.
+pub fn unapplied_commits_at(path: &Path, upstream: &str, head: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["cherry", upstream, head])
+        .output()
+        .with_context(|| format!("Failed to compare '{}' with '{}'", head, upstream))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to compare '{}' with '{}'", head, upstream),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("+ "))
+        .map(|commit| commit.trim().to_string())
+        .collect())
+}
+
+/// Move the branch checked out at `path` back to a commit, discarding later changes.
+pub fn reset_hard_at(path: &Path, commit: &str) -> Result<()> {
+    git_at(
+        path,
+        &["reset", "--quiet", "--hard", commit],
+        &format!("Failed to reset {} to '{}'", path.display(), commit),
+    )
+    .map(|_| ())
+}
+
+/// Merge a branch into the branch checked out at `path` with a merge commit.
+///
+/// Returns `Ok(false)` when the merge stopped on conflicts.
+pub fn merge_branch_at(path: &Path, branch: &str, message: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["merge", "--no-ff", "-m", message, branch])
         .output()
         .with_context(|| format!("Failed to merge branch '{}'", branch))?;
 
+    if output.status.success() {
+        return Ok(true);
+    }
+    if !conflicted_files_at(path)?.is_empty() {
+        return Ok(false);
+    }
+
+    Err(SpriteError::git_with_source(
+        format!("Failed to merge branch '{}' at {}", branch, path.display()),
+        String::from_utf8_lossy(&output.stderr),
+    )
+    .into())
+}
+
+/// Cherry-pick commits, in order, onto the branch checked out at `path`.
+///
+/// Commits are recorded with `-x` so [`cherry_picked_sources_at`] can find them
+/// later. Returns `Ok(false)` when the cherry-pick stopped on conflicts.
+pub fn cherry_pick_at(path: &Path, commits: &[String]) -> Result<bool> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["cherry-pick", "-x"])
+        .args(commits)
+        .output()
+        .with_context(|| format!("Failed to cherry-pick commits at {}", path.display()))?;
+
+    if output.status.success() {
+        return Ok(true);
+    }
+    if !conflicted_files_at(path)?.is_empty() {
+        return Ok(false);
+    }
+
+    Err(SpriteError::git_with_source(
+        format!("Failed to cherry-pick commits at {}", path.display()),
+        String::from_utf8_lossy(&output.stderr),
+    )
+    .into())
+}
+
+/// Collect the source commits recorded by `cherry-pick -x` in a branch's history.
+pub fn cherry_picked_sources_at(path: &Path, branch: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args([
+            "log",
+            "--format=%B",
+            "--grep=cherry picked from commit",
+            branch,
+        ])
+        .output()
+        .with_context(|| format!("Failed to read history of '{}'", branch))?;
+
     if !output.status.success() {
         return Err(SpriteError::git_with_source(
-            format!("Failed to merge branch '{}'", branch),
+            format!("Failed to read history of '{}'", branch),
             String::from_utf8_lossy(&output.stderr),
         )
         .into());
     }
 
-    Ok(())
+    Ok(parse_cherry_pick_trailers(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Extract commit hashes from `(cherry picked from commit <sha>)` lines.
+fn parse_cherry_pick_trailers(log: &str) -> Vec<String> {
+    log.lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("(cherry picked from commit ")
+                .and_then(|rest| rest.strip_suffix(')'))
+        })
+        .map(|commit| commit.to_string())
+        .collect()
 }
 
-/// Check if there are merge conflicts after a merge operation.
-pub fn has_merge_conflicts() -> Result<bool> {
+/// List files with unresolved conflicts in a specific worktree.
+pub fn conflicted_files_at(path: &Path) -> Result<Vec<String>> {
     let output = Command::new("git")
+        .current_dir(path)
         .args(["diff", "--name-only", "--diff-filter=U"])
         .output()
-        .with_context(|| "Failed to check for merge conflicts")?;
+        .with_context(|| format!("Failed to check for conflicts at {}", path.display()))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Check whether a merge, cherry-pick or rebase is in progress in a worktree.
+pub fn operation_in_progress_at(path: &Path) -> Result<bool> {
+    for head in ["MERGE_HEAD", "CHERRY_PICK_HEAD", "REBASE_HEAD"] {
+        let output = Command::new("git")
+            .current_dir(path)
+            .args(["rev-parse", "--quiet", "--verify", head])
+            .output()
+            .with_context(|| format!("Failed to inspect git state at {}", path.display()))?;
+        if output.status.success() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Decode a path as git prints it in diff headers: C-quoted when it holds
+/// special characters, followed by a tab when it holds spaces.
+pub fn unquote_path(path: &str) -> String {
+    let path = path.strip_suffix('\t').unwrap_or(path);
+    let quoted = match path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        Some(quoted) => quoted,
+        None => return path.to_string(),
+    };
+
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('a') => bytes.push(0x07),
+            Some('b') => bytes.push(0x08),
+            Some('f') => bytes.push(0x0c),
+            Some('v') => bytes.push(0x0b),
+            // Octal escapes are raw bytes, e.g. UTF-8 sequences
+            Some(d @ '0'..='7') => {
+                let octal: String = std::iter::once(d).chain(chars.by_ref().take(2)).collect();
+                bytes.push(u8::from_str_radix(&octal, 8).unwrap_or(b'?'));
+            }
+            Some(other) => bytes.push(other as u8),
+            None => {}
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Find the best common ancestor of two revisions.
+pub fn merge_base(a: &str, b: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["merge-base", a, b])
+        .output()
+        .with_context(|| format!("Failed to find merge base of '{}' and '{}'", a, b))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to find merge base of '{}' and '{}'", a, b),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// List files changed between two revisions.
+pub fn changed_files(from: &str, to: &str) -> Result<Vec<String>> {
+    // NUL-separated, so paths are not quoted
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "-z", "--no-renames", from, to, "--"])
+        .output()
+        .with_context(|| format!("Failed to diff '{}' and '{}'", from, to))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to diff '{}' and '{}'", from, to),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Get a zero-context unified diff between two revisions.
+///
+/// Non-ASCII paths are left as they are; paths with quotes, backslashes or
+/// control characters are still quoted (see [`unquote_path`]).
+pub fn diff_zero_context(from: &str, to: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "-U0",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            from,
+            to,
+            "--",
+        ])
+        .output()
+        .with_context(|| format!("Failed to diff '{}' and '{}'", from, to))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to diff '{}' and '{}'", from, to),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Get the `--stat` summary of what `to` changed since it diverged from `from`.
+pub fn diff_stat(from: &str, to: &str) -> Result<String> {
+    let range = format!("{}...{}", from, to);
+    let output = Command::new("git")
+        .args(["diff", "--stat", "--no-color", &range, "--"])
+        .output()
+        .with_context(|| format!("Failed to diff '{}'", range))?;
 
-    let conflicts_str = String::from_utf8_lossy(&output.stdout);
-    Ok(!conflicts_str.trim().is_empty())
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to diff '{}'", range),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Predict merge conflicts between two revisions without touching any worktree.
+///
+/// Uses `git merge-tree --write-tree` (git 2.38+) and returns the conflicted paths.
+pub fn predict_merge_conflicts(a: &str, b: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args([
+            "merge-tree",
+            "--write-tree",
+            "--name-only",
+            "--no-messages",
+            a,
+            b,
+        ])
+        .output()
+        .with_context(|| format!("Failed to run merge-tree for '{}' and '{}'", a, b))?;
+
+    match output.status.code() {
+        Some(0) => Ok(Vec::new()),
+        // The first line is the resulting tree, followed by the conflicted paths
+        Some(1) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(1)
+            .map(|line| line.to_string())
+            .filter(|line| !line.is_empty())
+            .collect()),
+        _ => Err(SpriteError::git_with_source(
+            format!("Failed to predict merge of '{}' and '{}'", a, b),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into()),
+    }
+}
+
+/// Get the git directory shared by all worktrees, as an absolute path.
+pub fn get_common_dir() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-common-dir"])
+        .output()
+        .with_context(|| "Failed to locate the git directory")?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            "Failed to locate the git directory",
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    if dir.is_absolute() {
+        Ok(dir)
+    } else {
+        Ok(std::env::current_dir()?.join(dir))
+    }
+}
+
+/// Find the worktree that currently has a branch checked out.
+pub fn find_worktree_for_branch(branch: &str) -> Result<Option<PathBuf>> {
+    Ok(list_worktrees()?
+        .into_iter()
+        .find(|worktree| worktree.branch == branch)
+        .map(|worktree| worktree.path))
 }
 
 /// Prune stale worktree references.
@@ -787,6 +1375,548 @@ pub fn remove_worktree_reference(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Run git in `path` and return its trimmed output, failing with `what` on error.
+fn git_at(path: &Path, args: &[&str], what: &str) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .with_context(|| format!("{} at {}", what, path.display()))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("{} at {}", what, path.display()),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Write the full working tree of a worktree, untracked files included, as a tree object.
+///
+/// Uses a scratch copy of the index, so the real index, HEAD and files are left alone.
+pub fn snapshot_worktree_at(path: &Path) -> Result<String> {
+    let scratch = tempfile::tempdir().context("Failed to create a scratch index")?;
+    let index = scratch.path().join("index");
+    // Starting from the real index lets git reuse its cached file stats
+    let real_index = git_at(
+        path,
+        &["rev-parse", "--path-format=absolute", "--git-path", "index"],
+        "Failed to locate the index",
+    )?;
+    if Path::new(&real_index).exists() {
+        std::fs::copy(&real_index, &index).context("Failed to copy the index")?;
+    }
+
+    let run = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .current_dir(path)
+            .env("GIT_INDEX_FILE", &index)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to snapshot worktree at {}", path.display()))?;
+        if !output.status.success() {
+            return Err(SpriteError::git_with_source(
+                format!("Failed to snapshot worktree at {}", path.display()),
+                String::from_utf8_lossy(&output.stderr),
+            )
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    run(&["add", "--all", "--", "."])?;
+    run(&["write-tree"])
+}
+
+/// Create a commit for `tree` with an optional parent, without moving any branch.
+pub fn commit_tree_at(
+    path: &Path,
+    tree: &str,
+    parent: Option<&str>,
+    message: &str,
+) -> Result<String> {
+    let mut args = vec!["commit-tree", tree, "-m", message];
+    if let Some(parent) = parent {
+        args.extend(["-p", parent]);
+    }
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(&args)
+        // Hidden bookkeeping commits should not depend on the user's identity being set
+        .env("GIT_AUTHOR_NAME", "Sprite")
+        .env("GIT_AUTHOR_EMAIL", "sprite@localhost")
+        .env("GIT_COMMITTER_NAME", "Sprite")
+        .env("GIT_COMMITTER_EMAIL", "sprite@localhost")
+        .output()
+        .with_context(|| format!("Failed to create commit at {}", path.display()))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to create commit at {}", path.display()),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Point a reference at a commit, creating it if needed.
+pub fn update_ref_at(path: &Path, reference: &str, commit: &str) -> Result<()> {
+    git_at(
+        path,
+        &["update-ref", reference, commit],
+        &format!("Failed to update {}", reference),
+    )
+    .map(|_| ())
+}
+
+/// Delete a reference.
+pub fn delete_ref_at(path: &Path, reference: &str) -> Result<()> {
+    git_at(
+        path,
+        &["update-ref", "-d", reference],
+        &format!("Failed to delete {}", reference),
+    )
+    .map(|_| ())
+}
+
+/// A reference with the commit it points to
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefInfo {
+    pub name: String,
+    pub commit: String,
+    /// Commit time as a Unix timestamp
+    pub timestamp: i64,
+    pub subject: String,
+}
+
+/// List the references under a prefix such as `refs/sprite/`, sorted by name.
+pub fn list_refs_at(path: &Path, prefix: &str) -> Result<Vec<RefInfo>> {
+    let output = git_at(
+        path,
+        &[
+            "for-each-ref",
+            "--sort=refname",
+            "--format=%(refname)%00%(objectname)%00%(committerdate:unix)%00%(subject)",
+            prefix,
+        ],
+        &format!("Failed to list {}", prefix),
+    )?;
+    Ok(parse_ref_list(&output))
+}
+
+fn parse_ref_list(output: &str) -> Vec<RefInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\0');
+            Some(RefInfo {
+                name: parts.next()?.to_string(),
+                commit: parts.next()?.to_string(),
+                timestamp: parts.next()?.parse().unwrap_or_default(),
+                subject: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Resolve a revision (e.g. `<commit>^{tree}`) to an object ID.
+pub fn rev_parse_at(path: &Path, revision: &str) -> Result<String> {
+    git_at(
+        path,
+        &["rev-parse", "--verify", "--quiet", revision],
+        &format!("Failed to resolve '{}'", revision),
+    )
+}
+
+/// Diff two trees or commits, as a patch or as a `--stat` summary.
+pub fn diff_objects_at(path: &Path, from: &str, to: &str, stat: bool) -> Result<String> {
+    let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+    if stat {
+        args.push("--stat");
+    }
+    args.extend([from, to]);
+    git_at(
+        path,
+        &args,
+        &format!("Failed to diff '{}' and '{}'", from, to),
+    )
+}
+
+/// Paths that differ between two trees or commits, filtered by `git diff --diff-filter`.
+pub fn diff_paths_at(path: &Path, from: &str, to: &str, filter: &str) -> Result<Vec<String>> {
+    let filter = format!("--diff-filter={}", filter);
+    let output = git_at(
+        path,
+        &[
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "-z",
+            &filter,
+            from,
+            to,
+        ],
+        &format!("Failed to diff '{}' and '{}'", from, to),
+    )?;
+    // NUL-separated so paths come back verbatim rather than C-quoted
+    Ok(output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Overwrite a worktree's files with their content at `source`; the index and HEAD are untouched.
+pub fn restore_worktree_at(path: &Path, source: &str) -> Result<()> {
+    let source = format!("--source={}", source);
+    git_at(
+        path,
+        &["restore", &source, "--worktree", "--", "."],
+        "Failed to restore worktree",
+    )
+    .map(|_| ())
+}
+
+/// Build a binary-safe patch of the changes between two trees or commits, limited to `paths`.
+pub fn binary_diff_at(path: &Path, from: &str, to: &str, paths: &[String]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args([
+            "diff",
+            "--binary",
+            "--full-index",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            from,
+            to,
+            "--",
+        ])
+        .args(paths)
+        .output()
+        .with_context(|| format!("Failed to diff '{}' and '{}'", from, to))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!(
+                "Failed to diff '{}' and '{}' at {}",
+                from,
+                to,
+                path.display()
+            ),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(output.stdout)
+}
+
+/// List the files changed between two trees or commits with their status letter (`A`, `M`, `D`...).
+pub fn diff_name_status_at(
+    path: &Path,
+    from: &str,
+    to: &str,
+    paths: &[String],
+) -> Result<Vec<(char, String)>> {
+    let mut args = vec![
+        "diff",
+        "--name-status",
+        "--no-renames",
+        "-z",
+        from,
+        to,
+        "--",
+    ];
+    args.extend(paths.iter().map(|p| p.as_str()));
+    let output = git_at(
+        path,
+        &args,
+        &format!("Failed to diff '{}' and '{}'", from, to),
+    )?;
+
+    Ok(parse_name_status_z(&output))
+}
+
+/// Parse `git diff --name-status -z` output: a status and a path per entry,
+/// each NUL-terminated, with paths left unquoted.
+fn parse_name_status_z(output: &str) -> Vec<(char, String)> {
+    let mut fields = output.split('\0');
+    let mut entries = Vec::new();
+    while let (Some(status), Some(file)) = (fields.next(), fields.next()) {
+        if let Some(status) = status.chars().next() {
+            entries.push((status, file.to_string()));
+        }
+    }
+    entries
+}
+
+/// Apply a patch to the files of a worktree, leaving the index alone.
+///
+/// With `check`, only tests whether it applies. Returns `Ok(false)` when it does not.
+pub fn apply_patch_at(path: &Path, patch: &[u8], check: bool) -> Result<bool> {
+    let mut command = Command::new("git");
+    command.current_dir(path).arg("apply");
+    if check {
+        command.arg("--check");
+    }
+    let mut child = command
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to apply patch at {}", path.display()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(patch)
+            .with_context(|| format!("Failed to apply patch at {}", path.display()))?;
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("Failed to apply patch at {}", path.display()))?;
+    Ok(status.success())
+}
+
+/// Read a file's content at a revision, or `None` if it does not exist there.
+pub fn show_file_at(path: &Path, revision: &str, file: &str) -> Result<Option<Vec<u8>>> {
+    let object = format!("{}:{}", revision, file);
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["cat-file", "blob", &object])
+        .output()
+        .with_context(|| format!("Failed to read '{}'", object))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(output.stdout))
+}
+
+/// Three-way merge file contents with `git merge-file`, without touching any repository.
+///
+/// Returns the merged content, with conflict markers labelled by `labels`
+/// (ours, base, theirs), and the number of conflicting regions.
+pub fn merge_file_contents(
+    ours: &[u8],
+    base: &[u8],
+    theirs: &[u8],
+    labels: [&str; 3],
+) -> Result<(Vec<u8>, usize)> {
+    let scratch = tempfile::tempdir().context("Failed to create a scratch directory")?;
+    let mut files = Vec::new();
+    for (name, content) in [("ours", ours), ("base", base), ("theirs", theirs)] {
+        let file = scratch.path().join(name);
+        std::fs::write(&file, content).context("Failed to write merge input")?;
+        files.push(file);
+    }
+
+    let output = Command::new("git")
+        .args(["merge-file", "-p"])
+        .args(["-L", labels[0], "-L", labels[1], "-L", labels[2]])
+        .args(&files)
+        .output()
+        .context("Failed to run git merge-file")?;
+
+    // The exit code is the number of conflicts; errors (such as binary input) exit 255
+    match output.status.code() {
+        Some(conflicts) if (0..=127).contains(&conflicts) => {
+            Ok((output.stdout, conflicts as usize))
+        }
+        _ => Err(SpriteError::git_with_source(
+            "Failed to merge file contents",
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into()),
+    }
+}
+
+/// Get the git directory shared by all worktrees of the repository at `path`.
+pub fn common_dir_at(path: &Path) -> Result<PathBuf> {
+    git_at(
+        path,
+        &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        "Failed to locate the git directory",
+    )
+    .map(PathBuf::from)
+}
+
+/// Get the git directory of the worktree at `path` (`.git/worktrees/<name>` for linked ones).
+pub fn git_dir_at(path: &Path) -> Result<PathBuf> {
+    git_at(
+        path,
+        &["rev-parse", "--absolute-git-dir"],
+        "Failed to locate the git directory",
+    )
+    .map(PathBuf::from)
+}
+
+/// Read a git config value as seen from `path`, optionally from one file (`--local`, `--worktree`).
+pub fn config_get_at(path: &Path, scope: Option<&str>, key: &str) -> Result<Option<String>> {
+    let mut command = Command::new("git");
+    command.current_dir(path).arg("config");
+    if let Some(scope) = scope {
+        command.arg(scope);
+    }
+    let output = command
+        .args(["--get", key])
+        .output()
+        .with_context(|| format!("Failed to read git config '{}'", key))?;
+
+    // Exit code 1 means the key is not set
+    match output.status.code() {
+        Some(0) => Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        Some(1) => Ok(None),
+        _ => Err(SpriteError::git_with_source(
+            format!("Failed to read git config '{}' at {}", key, path.display()),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into()),
+    }
+}
+
+/// Set (or with `None`, unset) a config value for the worktree at `path` only.
+///
+/// Turns on `extensions.worktreeConfig` first, which per-worktree settings need.
+pub fn set_worktree_config_at(path: &Path, key: &str, value: Option<&str>) -> Result<()> {
+    if config_get_at(path, Some("--local"), "extensions.worktreeConfig")?.as_deref() != Some("true")
+    {
+        git_at(
+            path,
+            &["config", "--local", "extensions.worktreeConfig", "true"],
+            "Failed to enable per-worktree configuration",
+        )?;
+        println!(
+            "ℹ️  Enabled extensions.worktreeConfig in this repository so agent worktrees can keep their own git settings"
+        );
+    }
+
+    match value {
+        Some(value) => git_at(
+            path,
+            &["config", "--worktree", key, value],
+            &format!("Failed to set git config '{}'", key),
+        )
+        .map(|_| ()),
+        None => {
+            if config_get_at(path, Some("--worktree"), key)?.is_none() {
+                return Ok(());
+            }
+            git_at(
+                path,
+                &["config", "--worktree", "--unset-all", key],
+                &format!("Failed to unset git config '{}'", key),
+            )
+            .map(|_| ())
+        }
+    }
+}
+
+/// Add a `Key: value` trailer to a commit message file, unless it is already there.
+pub fn add_trailer_at(path: &Path, message_file: &Path, trailer: &str) -> Result<()> {
+    // Without a final newline (as `git merge` writes them) interpret-trailers
+    // glues the trailer onto the last line, which may be the subject
+    let message = std::fs::read_to_string(message_file)
+        .map_err(|e| SpriteError::io(format!("read {}", message_file.display()), e))?;
+    if !message.is_empty() && !message.ends_with('\n') {
+        std::fs::write(message_file, format!("{}\n", message))
+            .map_err(|e| SpriteError::io(format!("write {}", message_file.display()), e))?;
+    }
+
+    let file = message_file.to_string_lossy();
+    git_at(
+        path,
+        &[
+            "interpret-trailers",
+            "--in-place",
+            "--if-exists",
+            "addIfDifferent",
+            "--trailer",
+            trailer,
+            &file,
+        ],
+        "Failed to add commit trailer",
+    )
+    .map(|_| ())
+}
+
+/// Run `git blame --line-porcelain` on a file at a revision.
+pub fn blame_porcelain_at(path: &Path, revision: &str, file: &str) -> Result<String> {
+    git_at(
+        path,
+        &["blame", "--line-porcelain", revision, "--", file],
+        &format!("Failed to blame '{}'", file),
+    )
+}
+
+/// Read one trailer from each of `commits`, as a map from commit to its values joined by `,`.
+pub fn commit_trailers_at(
+    path: &Path,
+    commits: &[String],
+    key: &str,
+) -> Result<HashMap<String, String>> {
+    if commits.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let format = format!(
+        "--format=%H%x00%(trailers:key={},valueonly,separator=%x2C)",
+        key
+    );
+    let mut child = Command::new("git")
+        .current_dir(path)
+        .args(["log", "--no-walk=unsorted", "--stdin", &format])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to read commit trailers at {}", path.display()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("{}\n", commits.join("\n")).as_bytes())
+            .context("Failed to read commit trailers")?;
+    }
+    let output = child
+        .wait_with_output()
+        .context("Failed to read commit trailers")?;
+    if !output.status.success() {
+        return Err(SpriteError::git_with_source(
+            format!("Failed to read commit trailers at {}", path.display()),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (commit, value) = line.split_once('\0')?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| (commit.to_string(), value.to_string()))
+        })
+        .collect())
+}
+
+/// Run `git log` over `revision` with a custom format followed by `--numstat` lines.
+pub fn log_numstat_at(path: &Path, revision: &str, format: &str) -> Result<String> {
+    let format = format!("--format={}", format);
+    git_at(
+        path,
+        &["log", "--numstat", "--no-renames", &format, revision, "--"],
+        &format!("Failed to read the history of '{}'", revision),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -816,6 +1946,191 @@ mod tests {
         assert!(worktrees[2].bare);
     }
 
+    #[test]
+    fn test_parse_worktree_list_porcelain() {
+        let input = "worktree /repo\nbare\n\nworktree /repo/agents/1\nHEAD def\nbranch refs/heads/agents/1\n\nworktree /tmp/my detached\nHEAD 123\ndetached\n";
+
+        let worktrees = parse_worktree_list(input).unwrap();
+        assert_eq!(worktrees.len(), 3);
+        assert!(worktrees[0].bare);
+        assert_eq!(worktrees[1].path, PathBuf::from("/repo/agents/1"));
+        assert_eq!(worktrees[1].branch, "agents/1");
+        assert_eq!(worktrees[1].id, None);
+        assert_eq!(worktrees[2].path, PathBuf::from("/tmp/my detached"));
+        assert!(worktrees[2].branch.is_empty());
+        assert_eq!(worktrees[2].id, Some("123".to_string()));
+    }
+
+    #[test]
+    fn test_parse_cherry_pick_trailers() {
+        let log = "Add search\n\n(cherry picked from commit abc123)\n\nFix typo\n    (cherry picked from commit def456)\nnot a trailer\n";
+        assert_eq!(parse_cherry_pick_trailers(log), vec!["abc123", "def456"]);
+    }
+
+    #[test]
+    fn test_snapshot_worktree_keeps_index_and_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(status.status.success(), "{:?}", status);
+            String::from_utf8_lossy(&status.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo.join("new.txt"), "untracked\n").unwrap();
+        let head = git(&["rev-parse", "HEAD"]);
+        let tree = snapshot_worktree_at(repo).unwrap();
+        let commit = commit_tree_at(repo, &tree, Some(&head), "checkpoint").unwrap();
+        update_ref_at(repo, "refs/sprite/checkpoints/1/a", &commit).unwrap();
+
+        // Nothing staged, HEAD unchanged, and the snapshot holds the untracked file
+        assert_eq!(git(&["status", "--porcelain"]), "M a.txt\n?? new.txt");
+        assert_eq!(git(&["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            diff_paths_at(repo, &head, &commit, "A").unwrap(),
+            vec!["new.txt"]
+        );
+
+        std::fs::write(repo.join("a.txt"), "broken\n").unwrap();
+        restore_worktree_at(repo, &commit).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "two\n"
+        );
+
+        let refs = list_refs_at(repo, "refs/sprite/").unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].commit, commit);
+        assert_eq!(refs[0].subject, "checkpoint");
+        delete_ref_at(repo, "refs/sprite/checkpoints/1/a").unwrap();
+        assert!(list_refs_at(repo, "refs/sprite/").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_add_trailer_without_final_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        Command::new("git")
+            .current_dir(repo)
+            .args(["init", "-q"])
+            .output()
+            .unwrap();
+        let message = repo.join("MERGE_MSG");
+        std::fs::write(&message, "Merge branch 'main' into agents/2").unwrap();
+
+        add_trailer_at(repo, &message, "Sprite-Agent: 2").unwrap();
+        let written = std::fs::read_to_string(&message).unwrap();
+        assert!(written.starts_with("Merge branch 'main' into agents/2\n\n"));
+        assert!(written.ends_with("Sprite-Agent: 2\n"));
+    }
+
+    #[test]
+    fn test_diff_paths_are_not_quoted() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        let base = git(&["rev-parse", "HEAD"]);
+        std::fs::write(repo.join("my notes ü.txt"), "one\n").unwrap();
+        std::fs::write(repo.join("say \"hi\".txt"), "two\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "odd names"]);
+
+        assert_eq!(
+            diff_paths_at(repo, &base, "HEAD", "A").unwrap(),
+            vec!["my notes ü.txt", "say \"hi\".txt"]
+        );
+        assert_eq!(
+            diff_name_status_at(repo, &base, "HEAD", &[]).unwrap(),
+            vec![
+                ('A', "my notes ü.txt".to_string()),
+                ('A', "say \"hi\".txt".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unquote_path() {
+        assert_eq!(unquote_path("b/src/lib.rs"), "b/src/lib.rs");
+        assert_eq!(unquote_path("b/my file.txt\t"), "b/my file.txt");
+        assert_eq!(unquote_path(r#""b/na\303\257ve.rs""#), "b/naïve.rs");
+        assert_eq!(unquote_path(r#""b/say \"hi\".rs"	"#), "b/say \"hi\".rs");
+        assert_eq!(unquote_path(r#""b/back\\slash""#), "b/back\\slash");
+    }
+
+    #[test]
+    fn test_default_branch_follows_origin_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .current_dir(repo)
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+        };
+        git(&["init", "-q", "-b", "master"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        assert_eq!(default_branch_at(repo).as_deref(), Some("master"));
+
+        git(&["update-ref", "refs/remotes/origin/develop", "HEAD"]);
+        git(&[
+            "symbolic-ref",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+        ]);
+        assert_eq!(default_branch_at(repo).as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_merge_file_contents() {
+        let labels = ["agent 3", "base", "agent 1"];
+        let base = b"one\ntwo\nthree\n";
+
+        let (merged, conflicts) =
+            merge_file_contents(b"ONE\ntwo\nthree\n", base, b"one\ntwo\nTHREE\n", labels).unwrap();
+        assert_eq!(conflicts, 0);
+        assert_eq!(merged, b"ONE\ntwo\nTHREE\n");
+
+        let (merged, conflicts) =
+            merge_file_contents(b"one\n2\nthree\n", base, b"one\nzwei\nthree\n", labels).unwrap();
+        assert_eq!(conflicts, 1);
+        let merged = String::from_utf8(merged).unwrap();
+        assert!(merged.contains("<<<<<<< agent 3\n2\n"));
+        assert!(merged.contains("zwei\n>>>>>>> agent 1\n"));
+    }
+
     #[test]
     fn test_parse_git_status() {
         let input = r#"
@@ -6,15 +6,27 @@
 //! - logging: Application logging infrastructure
 //! - security: Path validation and security checks
 //! - session_recovery: Session health monitoring and recovery
+//! - registry: Machine-wide registry of known Sprite projects
+//! - ports: Machine-wide port ranges allocated to agents
+//! - secrets: Secret references in agent environment variables
+//! - config_layers: Layered configuration merging and value origins
+//! - yaml_path: Dotted-path access into configuration value trees
+//! - transcript: Agent pane transcripts captured through pipe-pane
 //! - accessibility: Accessibility features and WCAG compliance
 
 pub mod accessibility;
+pub mod config_layers;
 pub mod git;
 pub mod help;
 pub mod logging;
+pub mod ports;
 pub mod project;
+pub mod registry;
+pub mod secrets;
 pub mod security;
 pub mod session_recovery;
 pub mod timeout;
 pub mod tmux;
+pub mod transcript;
 pub mod workspace;
+pub mod yaml_path;
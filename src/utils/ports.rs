@@ -0,0 +1,376 @@
+//! Port ranges allocated to agents on this machine.
+//!
+//! Each agent gets a block of `ports.range_size` consecutive ports, recorded in
+//! `~/.config/sprite/ports.lock` so it keeps the same ports across restarts and
+//! no two agents, of this or any other project, share a block. New blocks skip
+//! ports something is already listening on, as seen in `/proc/net/tcp`.
+
+use crate::commands::config::{PortsConfig, SpriteConfig};
+use crate::error::SpriteError;
+use crate::utils::registry::user_config_dir;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// File name of the lock file inside the user configuration directory
+pub const PORTS_FILE: &str = "ports.lock";
+
+/// File `flock`ed while the lock file is read, changed and written back. The
+/// lock file itself is replaced on every save, so it cannot carry the lock.
+const GUARD_FILE: &str = ".ports.lock.guard";
+
+/// The block of ports held by one agent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PortAllocation {
+    /// Absolute path to the project root
+    pub root: PathBuf,
+    pub agent: String,
+    /// First port of the block
+    pub base: u16,
+    /// Number of ports in the block
+    pub size: u16,
+}
+
+impl PortAllocation {
+    /// Last port of the block
+    pub fn last(&self) -> u16 {
+        self.base + (self.size - 1)
+    }
+
+    fn overlaps(&self, base: u16, size: u16) -> bool {
+        u32::from(self.base) < u32::from(base) + u32::from(size)
+            && u32::from(base) < u32::from(self.base) + u32::from(self.size)
+    }
+}
+
+/// Every port block allocated on this machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PortLock {
+    pub allocations: Vec<PortAllocation>,
+}
+
+/// Get the path of the port lock file.
+pub fn lock_path() -> Result<PathBuf> {
+    Ok(user_config_dir()?.join(PORTS_FILE))
+}
+
+impl PortLock {
+    /// Load the lock file from the default location (empty if it does not exist yet)
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&lock_path()?)
+    }
+
+    /// Load the lock file from a specific path
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read port lock file: {}", path.display()))?;
+
+        serde_yaml::from_str(&content).map_err(|e| {
+            SpriteError::config(format!(
+                "Failed to parse port lock file {}: {}",
+                path.display(),
+                e
+            ))
+            .into()
+        })
+    }
+
+    /// Save the lock file to a specific path
+    pub fn save_to_path(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+
+        let content = serde_yaml::to_string(self).map_err(|e| {
+            SpriteError::config(format!("Failed to serialize port lock file: {}", e))
+        })?;
+
+        // Write a sibling file and rename it over the old one, so a crash never
+        // leaves a truncated lock file behind
+        let temp_path = path.with_file_name(format!(".{}.{}.tmp", PORTS_FILE, std::process::id()));
+        std::fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write port lock file: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, path).with_context(|| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("Failed to replace port lock file: {}", path.display())
+        })?;
+
+        Ok(())
+    }
+
+    /// Load the lock file at `path`, let `update` change it and save it if it did,
+    /// holding an exclusive lock throughout so concurrent sprite runs, in any
+    /// project, never hand out the same block
+    pub fn update_at(path: &Path, update: impl FnOnce(&mut Self) -> Result<bool>) -> Result<()> {
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        let guard = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(dir.join(GUARD_FILE))
+            .with_context(|| format!("Failed to open port lock guard in {}", dir.display()))?;
+        lock_exclusive(&guard)?;
+
+        let mut lock = Self::load_from_path(path)?;
+        if update(&mut lock)? {
+            lock.save_to_path(path)?;
+        }
+        // Closing the guard releases the lock
+        drop(guard);
+        Ok(())
+    }
+
+    /// The block held by an agent of the project at `root`
+    pub fn find(&self, root: &Path, agent: &str) -> Option<&PortAllocation> {
+        self.allocations
+            .iter()
+            .find(|a| a.root == root && a.agent == agent)
+    }
+
+    /// Give every agent of the project at `root` a block, keeping the blocks they already
+    /// hold and releasing those of agents no longer configured. Returns whether anything changed.
+    pub fn allocate(
+        &mut self,
+        root: &Path,
+        agents: &[String],
+        ports: &PortsConfig,
+        listening: &HashSet<u16>,
+    ) -> Result<bool> {
+        if ports.range_size == 0 {
+            return Err(SpriteError::config("ports.range_size must be greater than 0").into());
+        }
+        let before = self.allocations.clone();
+
+        // Forget projects that are gone, and blocks that no longer fit the configured layout
+        self.allocations.retain(|a| {
+            if a.root != root {
+                return a.root.exists();
+            }
+            agents.contains(&a.agent)
+                && a.size == ports.range_size
+                && a.base >= ports.base
+                && (a.base - ports.base) % ports.range_size == 0
+        });
+
+        for agent in agents {
+            if self.find(root, agent).is_some() {
+                continue;
+            }
+            let base = self.free_block(ports, listening).ok_or_else(|| {
+                SpriteError::config(format!(
+                    "No free range of {} ports left above {} for agent {}",
+                    ports.range_size, ports.base, agent
+                ))
+            })?;
+            self.allocations.push(PortAllocation {
+                root: root.to_path_buf(),
+                agent: agent.clone(),
+                base,
+                size: ports.range_size,
+            });
+        }
+
+        self.allocations.sort_by_key(|a| a.base);
+        Ok(self.allocations != before)
+    }
+
+    /// Lowest block that overlaps no other allocation and no listening port
+    fn free_block(&self, ports: &PortsConfig, listening: &HashSet<u16>) -> Option<u16> {
+        let size = ports.range_size;
+        (0u32..)
+            .map(|slot| u32::from(ports.base) + slot * u32::from(size))
+            .take_while(|base| base + u32::from(size) <= 65536)
+            .map(|base| base as u16)
+            .find(|&base| {
+                !self.allocations.iter().any(|a| a.overlaps(base, size))
+                    && !(0..size).any(|offset| listening.contains(&(base + offset)))
+            })
+    }
+}
+
+#[cfg(unix)]
+fn lock_exclusive(file: &std::fs::File) -> Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // Blocks until no other sprite process holds the lock
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+        return Err(
+            SpriteError::io("lock the port lock file", std::io::Error::last_os_error()).into(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn lock_exclusive(_file: &std::fs::File) -> Result<()> {
+    Ok(())
+}
+
+/// Make sure every agent of the project has a port block, recording new ones in the lock file
+pub fn allocate_project(config: &SpriteConfig) -> Result<()> {
+    let root = project_root(config)?;
+    let agents: Vec<String> = config.agents.iter().map(|a| a.id.clone()).collect();
+    PortLock::update_at(&lock_path()?, |lock| {
+        lock.allocate(&root, &agents, &config.ports, &listening_ports())
+    })
+}
+
+/// Port variables for an agent: `SPRITE_PORT_BASE` and one `PORT_<NAME>` per named port.
+///
+/// Empty until the agent has a block. `lock` is loaded once by the caller for
+/// all the agents it needs ports for.
+pub fn agent_ports(config: &SpriteConfig, lock: &PortLock, agent_id: &str) -> Vec<(String, u16)> {
+    let allocation = match project_root(config) {
+        Ok(root) => match lock.find(&root, agent_id) {
+            Some(allocation) => allocation.clone(),
+            None => return Vec::new(),
+        },
+        Err(_) => return Vec::new(),
+    };
+
+    let mut ports = vec![("SPRITE_PORT_BASE".to_string(), allocation.base)];
+    ports.extend(
+        config
+            .ports
+            .named_ports()
+            .into_iter()
+            .filter(|(_, offset)| *offset < allocation.size)
+            .map(|(name, offset)| (PortsConfig::variable(&name), allocation.base + offset)),
+    );
+    ports
+}
+
+/// The project root as recorded in the lock file
+pub fn project_root(config: &SpriteConfig) -> Result<PathBuf> {
+    let root = config
+        .project_root()
+        .ok_or_else(|| SpriteError::config("Could not determine the project root"))?;
+    Ok(std::fs::canonicalize(&root).unwrap_or(root))
+}
+
+/// TCP ports with a listening socket on this machine (empty where `/proc` is unavailable)
+pub fn listening_ports() -> HashSet<u16> {
+    ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| parse_listening(&content))
+        .collect()
+}
+
+/// Local ports of the `LISTEN` sockets in a `/proc/net/tcp` table
+fn parse_listening(content: &str) -> Vec<u16> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let local = fields.nth(1)?;
+            let state = fields.nth(1)?;
+            // State 0A is TCP_LISTEN
+            if state != "0A" {
+                return None;
+            }
+            let (_, port) = local.rsplit_once(':')?;
+            u16::from_str_radix(port, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_allocate_keeps_and_skips_blocks() {
+        let temp_dir = TempDir::new().unwrap();
+        let alpha = temp_dir.path().join("alpha");
+        let beta = temp_dir.path().join("beta");
+        std::fs::create_dir_all(&alpha).unwrap();
+        std::fs::create_dir_all(&beta).unwrap();
+        let ports = PortsConfig::default();
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let mut lock = PortLock::default();
+        assert!(lock
+            .allocate(&alpha, &ids(&["1", "2"]), &ports, &HashSet::new())
+            .unwrap());
+        assert_eq!(lock.find(&alpha, "1").unwrap().base, 20000);
+        assert_eq!(lock.find(&alpha, "2").unwrap().last(), 20019);
+
+        // Another project skips taken blocks and blocks with a listening port
+        let listening: HashSet<u16> = [20025].into_iter().collect();
+        lock.allocate(&beta, &ids(&["1"]), &ports, &listening)
+            .unwrap();
+        assert_eq!(lock.find(&beta, "1").unwrap().base, 20030);
+
+        // Existing blocks are kept even while their ports are in use
+        let listening: HashSet<u16> = [20000].into_iter().collect();
+        assert!(!lock
+            .allocate(&alpha, &ids(&["1", "2"]), &ports, &listening)
+            .unwrap());
+
+        // Removed agents release their block, which the next agent reuses
+        lock.allocate(&alpha, &ids(&["2"]), &ports, &HashSet::new())
+            .unwrap();
+        lock.allocate(&alpha, &ids(&["2", "3"]), &ports, &HashSet::new())
+            .unwrap();
+        assert_eq!(lock.find(&alpha, "3").unwrap().base, 20000);
+
+        let path = temp_dir.path().join(PORTS_FILE);
+        lock.save_to_path(&path).unwrap();
+        assert_eq!(PortLock::load_from_path(&path).unwrap(), lock);
+    }
+
+    #[test]
+    fn test_concurrent_updates_keep_every_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(PORTS_FILE);
+        let roots: Vec<PathBuf> = (0..8)
+            .map(|i| {
+                let root = temp_dir.path().join(format!("project-{}", i));
+                std::fs::create_dir_all(&root).unwrap();
+                root
+            })
+            .collect();
+
+        std::thread::scope(|scope| {
+            for root in &roots {
+                let path = &path;
+                scope.spawn(move || {
+                    PortLock::update_at(path, |lock| {
+                        lock.allocate(
+                            root,
+                            &["1".to_string()],
+                            &PortsConfig::default(),
+                            &HashSet::new(),
+                        )
+                    })
+                    .unwrap();
+                });
+            }
+        });
+
+        let lock = PortLock::load_from_path(&path).unwrap();
+        let bases: HashSet<u16> = lock.allocations.iter().map(|a| a.base).collect();
+        assert_eq!(lock.allocations.len(), roots.len());
+        assert_eq!(bases.len(), roots.len());
+    }
+
+    #[test]
+    fn test_parse_listening() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 1 1 0000000000000000 100 0 0 10 0\n\
+   1: 0100007F:8CA2 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 2 1 0000000000000000 20 4 30 10 -1\n";
+        assert_eq!(parse_listening(table), vec![8080]);
+    }
+}
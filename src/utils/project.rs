@@ -5,8 +5,12 @@
 
 use crate::error::SpriteError;
 use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
+/// Legacy session name used before session names were namespaced by project.
+pub const LEGACY_SESSION_NAME: &str = "sprite-session";
+
 /// Walk up from a starting point looking for `agents/agents.yaml` and return the
 /// directory that contains it.
 fn search_candidate_for_root(start: &Path) -> Option<PathBuf> {
@@ -82,6 +86,40 @@ pub fn find_project_root() -> Result<PathBuf> {
     .into())
 }
 
+/// Derive a tmux session name that is unique to the project rooted at `root`.
+///
+/// The name combines the project directory name with a short hash of the
+/// canonical root path, so two checkouts with the same directory name on one
+/// machine still get distinct sessions (e.g. `sprite-myapp-3f9a1c`).
+pub fn derive_session_name(root: &Path) -> String {
+    let canonical = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+
+    let slug: String = canonical
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "project" } else { slug };
+
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+    let hash: String = digest
+        .iter()
+        .take(3)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    format!("sprite-{}-{}", slug, hash)
+}
+
 /// Change to the project root directory and return the original directory
 pub fn change_to_project_root() -> Result<PathBuf> {
     let original_dir = std::env::current_dir().context("Failed to get current directory")?;
@@ -185,6 +223,25 @@ mod tests {
         std::env::set_current_dir(original_dir).unwrap();
     }
 
+    #[test]
+    fn test_derive_session_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("My App");
+        let second = temp_dir.path().join("other").join("My App");
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        let first_name = derive_session_name(&first);
+        assert!(first_name.starts_with("sprite-my-app-"));
+        assert!(first_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        // Stable for the same root, distinct for same-named directories elsewhere
+        assert_eq!(first_name, derive_session_name(&first));
+        assert_ne!(first_name, derive_session_name(&second));
+    }
+
     #[test]
     fn test_find_project_root_not_found() {
         let _lock = TEST_MUTEX.lock().unwrap();
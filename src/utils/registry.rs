@@ -0,0 +1,174 @@
+//! Registry of Sprite projects known on this machine.
+//!
+//! Every project that runs `sprite init` or `sprite start` is recorded in
+//! `~/.config/sprite/projects.yaml` together with its tmux session name, so
+//! commands like `sprite projects list` can report on all of them at once.
+
+use crate::error::SpriteError;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File name of the registry inside the user configuration directory
+pub const REGISTRY_FILE: &str = "projects.yaml";
+
+/// A single registered project.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectEntry {
+    /// Display name (the project directory name)
+    pub name: String,
+    /// Absolute path to the project root
+    pub root: PathBuf,
+    /// Tmux session name used by this project
+    pub session_name: String,
+    /// Last time a sprite command registered this project
+    pub last_used: DateTime<Utc>,
+}
+
+/// All projects known on this machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectRegistry {
+    pub projects: Vec<ProjectEntry>,
+}
+
+/// Get the user-level sprite configuration directory.
+///
+/// Honors `XDG_CONFIG_HOME` and falls back to `~/.config/sprite`.
+pub fn user_config_dir() -> Result<PathBuf> {
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return Ok(PathBuf::from(xdg).join("sprite"));
+    }
+
+    let home = std::env::var_os("HOME")
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| SpriteError::config("Cannot determine home directory (HOME is not set)"))?;
+    Ok(PathBuf::from(home).join(".config").join("sprite"))
+}
+
+/// Get the path of the project registry file.
+pub fn registry_path() -> Result<PathBuf> {
+    Ok(user_config_dir()?.join(REGISTRY_FILE))
+}
+
+impl ProjectRegistry {
+    /// Load the registry from the default location (empty if it does not exist yet)
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&registry_path()?)
+    }
+
+    /// Load the registry from a specific path
+    pub fn load_from_path(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read project registry: {}", path.display()))?;
+
+        serde_yaml::from_str(&content).map_err(|e| {
+            SpriteError::config(format!(
+                "Failed to parse project registry {}: {}",
+                path.display(),
+                e
+            ))
+            .into()
+        })
+    }
+
+    /// Save the registry to the default location
+    pub fn save(&self) -> Result<()> {
+        self.save_to_path(&registry_path()?)
+    }
+
+    /// Save the registry to a specific path
+    pub fn save_to_path(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+
+        let content = serde_yaml::to_string(self).map_err(|e| {
+            SpriteError::config(format!("Failed to serialize project registry: {}", e))
+        })?;
+
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write project registry: {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// Add or refresh a project entry, keyed by its canonical root
+    pub fn register(&mut self, root: &Path, session_name: &str) {
+        let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| root.display().to_string());
+
+        // Projects whose directory is gone are forgotten on the way
+        self.projects.retain(|p| p.root != root && p.root.exists());
+        self.projects.push(ProjectEntry {
+            name,
+            root,
+            session_name: session_name.to_string(),
+            last_used: Utc::now(),
+        });
+        self.projects.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Find the project that owns a session name
+    pub fn find_by_session(&self, session_name: &str) -> Option<&ProjectEntry> {
+        self.projects
+            .iter()
+            .find(|p| p.session_name == session_name)
+    }
+}
+
+/// Record a project in the user registry.
+///
+/// Failures are reported as warnings only; the registry is a convenience and
+/// must never block the command that triggered the registration.
+pub fn register_project(root: &Path, session_name: &str) {
+    let result = ProjectRegistry::load().and_then(|mut registry| {
+        registry.register(root, session_name);
+        registry.save()
+    });
+
+    if let Err(e) = result {
+        eprintln!("⚠️  Warning: Failed to update project registry: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_register_and_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_file = temp_dir.path().join("sprite").join(REGISTRY_FILE);
+        let project_a = temp_dir.path().join("alpha");
+        let project_b = temp_dir.path().join("beta");
+        std::fs::create_dir_all(&project_a).unwrap();
+        std::fs::create_dir_all(&project_b).unwrap();
+
+        let mut registry = ProjectRegistry::load_from_path(&registry_file).unwrap();
+        assert!(registry.projects.is_empty());
+
+        registry.register(&project_b, "sprite-beta-000000");
+        registry.register(&project_a, "sprite-alpha-000000");
+        // Re-registering replaces the existing entry
+        registry.register(&project_a, "sprite-alpha-111111");
+        registry.save_to_path(&registry_file).unwrap();
+
+        let loaded = ProjectRegistry::load_from_path(&registry_file).unwrap();
+        assert_eq!(loaded.projects.len(), 2);
+        assert_eq!(loaded.projects[0].name, "alpha");
+        assert_eq!(loaded.projects[0].session_name, "sprite-alpha-111111");
+        assert!(loaded.find_by_session("sprite-beta-000000").is_some());
+        assert!(loaded.find_by_session("sprite-alpha-000000").is_none());
+    }
+}
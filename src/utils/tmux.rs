@@ -305,31 +305,89 @@ fn build_target_spec(session: &str, target: &str) -> String {
 
 #[allow(dead_code)]
 pub fn send_keys(session: &str, target: &str, command: &str) -> Result<()> {
-    let target_spec = build_target_spec(session, target);
-    let output = Command::new("tmux")
-        .args(["send-keys", "-t", &target_spec, command, "C-m"])
-        .output()
-        .with_context(|| {
-            format!(
-                "Failed to send keys to pane '{}' in session '{}'",
-                target, session
-            )
-        })?;
+    send_keys_with_delay(session, target, command, 0)
+}
 
+/// Deliver text to a pane through a named buffer and bracketed paste, then press Enter.
+///
+/// Unlike `send-keys`, the text is never interpreted as key names, so multi-line
+/// prompts, `;` and words like `Enter` or `C-c` arrive exactly as written.
+pub fn paste_text(pane: &str, text: &str, submit: bool) -> Result<()> {
+    use std::io::Write;
+    use std::process::Stdio;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static BUFFER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let buffer = format!(
+        "sprite-{}-{}",
+        std::process::id(),
+        BUFFER_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let mut child = Command::new("tmux")
+        .args(["load-buffer", "-b", &buffer, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run tmux load-buffer")?;
+    let written = match child.stdin.take() {
+        Some(mut stdin) => stdin.write_all(paste_payload(text).as_bytes()),
+        None => Ok(()),
+    };
+    let output = child
+        .wait_with_output()
+        .context("Failed to run tmux load-buffer")?;
+    if let Err(e) = written {
+        let _ = run_tmux(&["delete-buffer", "-b", &buffer]);
+        return Err(e).context("Failed to write text to tmux buffer");
+    }
     if !output.status.success() {
         return Err(SpriteError::tmux_with_source(
-            format!(
-                "Failed to send keys to pane '{}' in session '{}'",
-                target, session
-            ),
+            format!("Failed to load text for pane '{}'", pane),
             String::from_utf8_lossy(&output.stderr),
         )
         .into());
     }
 
+    // -d drops the buffer once pasted; -p wraps it in bracketed paste when the
+    // application asked for it, so embedded newlines do not submit early
+    if let Err(e) = run_tmux(&["paste-buffer", "-p", "-d", "-b", &buffer, "-t", pane]) {
+        // A failed paste keeps the buffer; don't leave it in the tmux server
+        let _ = run_tmux(&["delete-buffer", "-b", &buffer]);
+        return Err(e);
+    }
+
+    if submit {
+        // Give the application a moment to finish handling the paste
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        run_tmux(&["send-keys", "-t", pane, "Enter"])?;
+    }
+
     Ok(())
 }
 
+/// Normalise text for pasting: line endings become `\n` and trailing newlines are dropped,
+/// since submitting is a separate Enter.
+fn paste_payload(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+/// Quote a value for a POSIX shell command line.
+pub fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 /// Capture output from a pane.
 #[allow(dead_code)]
 pub fn capture_pane(session: &str, target: &str) -> Result<String> {
@@ -450,12 +508,7 @@ pub fn get_tmux_version() -> Result<String> {
 pub fn set_environment(session: &str, env_vars: &HashMap<String, String>) -> Result<()> {
     for (key, value) in env_vars {
         let output = Command::new("tmux")
-            .args([
-                "set-environment",
-                "-t",
-                session,
-                &format!("{}={}", key, value),
-            ])
+            .args(["set-environment", "-t", session, key, value])
             .output()
             .with_context(|| {
                 format!(
@@ -739,26 +792,12 @@ pub fn send_keys_with_delay(
     delay_ms: u64,
 ) -> Result<()> {
     let target_spec = build_target_spec(session, target);
-    let output = Command::new("tmux")
-        .args(["send-keys", "-t", &target_spec, command, "C-m"])
-        .output()
-        .with_context(|| {
-            format!(
-                "Failed to send keys to pane '{}' in session '{}'",
-                target, session
-            )
-        })?;
-
-    if !output.status.success() {
-        return Err(SpriteError::tmux_with_source(
-            format!(
-                "Failed to send keys to pane '{}' in session '{}'",
-                target, session
-            ),
-            String::from_utf8_lossy(&output.stderr),
+    paste_text(&target_spec, command, true).with_context(|| {
+        format!(
+            "Failed to send keys to pane '{}' in session '{}'",
+            target, session
         )
-        .into());
-    }
+    })?;
 
     // Add delay if requested
     if delay_ms > 0 {
@@ -841,6 +880,181 @@ pub fn rename_window(session: &str, window_index: &str, new_name: &str) -> Resul
     Ok(())
 }
 
+/// Pane option recording which agent a pane belongs to
+pub const AGENT_PANE_OPTION: &str = "@sprite_agent";
+
+/// A pane anywhere in a session, with the agent it was tagged for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentPane {
+    /// Tmux pane identifier (e.g. %1)
+    pub pane_id: String,
+    /// Agent ID from the pane's `@sprite_agent` option, if tagged
+    pub agent_id: Option<String>,
+    /// Current working directory
+    pub current_path: Option<String>,
+    /// Foreground command running in the pane
+    pub current_command: Option<String>,
+}
+
+impl AgentPane {
+    /// Whether the pane is sitting at a shell prompt rather than running something
+    pub fn is_idle(&self) -> bool {
+        matches!(
+            self.current_command.as_deref(),
+            None | Some("bash" | "zsh" | "fish" | "sh" | "dash" | "ksh")
+        )
+    }
+}
+
+/// List the panes of every window in a session, including their agent tags.
+pub fn list_agent_panes(session: &str) -> Result<Vec<AgentPane>> {
+    let format = format!(
+        "#{{pane_id}}|#{{{}}}|#{{pane_current_command}}|#{{pane_current_path}}",
+        AGENT_PANE_OPTION
+    );
+    let output = Command::new("tmux")
+        .args(["list-panes", "-s", "-t", session, "-F", &format])
+        .output()
+        .with_context(|| format!("Failed to list panes for session '{}'", session))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::tmux_with_source(
+            format!("Failed to list panes for session '{}'", session),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(parse_agent_panes(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_agent_panes(output: &str) -> Vec<AgentPane> {
+    let non_empty = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '|');
+            let pane_id = non_empty(parts.next()?)?;
+            Some(AgentPane {
+                pane_id,
+                agent_id: parts.next().and_then(non_empty),
+                current_command: parts.next().and_then(non_empty),
+                current_path: parts.next().and_then(non_empty),
+            })
+        })
+        .collect()
+}
+
+/// Tag a pane with its agent ID and set its title.
+pub fn tag_agent_pane(pane_id: &str, agent_id: &str, title: &str) -> Result<()> {
+    run_tmux(&[
+        "set-option",
+        "-p",
+        "-t",
+        pane_id,
+        AGENT_PANE_OPTION,
+        agent_id,
+    ])?;
+    run_tmux(&["select-pane", "-t", pane_id, "-T", title])
+}
+
+/// Open a new window for an agent and return its pane ID.
+pub fn create_agent_window(session: &str, window_name: &str, working_dir: &str) -> Result<String> {
+    let output = Command::new("tmux")
+        .args([
+            "new-window",
+            "-d",
+            "-P",
+            "-F",
+            "#{pane_id}",
+            "-t",
+            &format!("{}:", session),
+            "-n",
+            window_name,
+            "-c",
+            working_dir,
+        ])
+        .output()
+        .with_context(|| {
+            format!(
+                "Failed to create window '{}' in session '{}'",
+                window_name, session
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(SpriteError::tmux_with_source(
+            format!(
+                "Failed to create window '{}' in session '{}'",
+                window_name, session
+            ),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Close a single pane.
+pub fn kill_pane(pane_id: &str) -> Result<()> {
+    run_tmux(&["kill-pane", "-t", pane_id])
+}
+
+/// Replace the process running in a pane with `command`, started in `working_dir`.
+pub fn respawn_pane(pane_id: &str, working_dir: &str, command: &str) -> Result<()> {
+    run_tmux(&[
+        "respawn-pane",
+        "-k",
+        "-t",
+        pane_id,
+        "-c",
+        working_dir,
+        command,
+    ])
+}
+
+/// Pipe everything a pane prints into a shell command, replacing any earlier pipe.
+pub fn pipe_pane(pane_id: &str, command: &str) -> Result<()> {
+    run_tmux(&["pipe-pane", "-t", pane_id, command])
+}
+
+/// Show a message in the status line of the clients attached to a session.
+pub fn display_message(session: &str, message: &str) -> Result<()> {
+    // The message is a format string; escape '#' so it is shown as written
+    let message = message.replace('#', "##");
+    run_tmux(&["display-message", "-d", "10000", "-t", session, &message])
+}
+
+/// Rename a session.
+pub fn rename_session(old_name: &str, new_name: &str) -> Result<()> {
+    run_tmux(&["rename-session", "-t", old_name, new_name])
+}
+
+/// Remove a variable from a session's environment.
+pub fn unset_environment(session: &str, key: &str) -> Result<()> {
+    run_tmux(&["set-environment", "-u", "-t", session, key])
+}
+
+/// Run a tmux command that produces no output of interest
+fn run_tmux(args: &[&str]) -> Result<()> {
+    let output = Command::new("tmux")
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run tmux {}", args.join(" ")))?;
+
+    if !output.status.success() {
+        return Err(SpriteError::tmux_with_source(
+            format!("tmux {} failed", args.join(" ")),
+            String::from_utf8_lossy(&output.stderr),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
 /// Send a command to a specific pane (compatibility function for communication module).
 /// This function provides a simplified interface for the communication module.
 #[allow(dead_code)]
@@ -989,6 +1203,20 @@ fn parse_panes_details(output: &str) -> Result<Vec<PaneDetails>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_paste_payload_and_quoting() {
+        assert_eq!(
+            paste_payload("line one\r\nline two\n\n"),
+            "line one\nline two"
+        );
+        assert_eq!(paste_payload("echo a; echo b"), "echo a; echo b");
+
+        assert_eq!(shell_quote("agents/1"), "agents/1");
+        assert_eq!(shell_quote("two words"), "'two words'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
     #[test]
     fn test_parse_sessions_list() {
         let input = r#"
@@ -1008,6 +1236,18 @@ another-session: 1 windows (created Wed Oct 18 09:15:00 2023)
         assert!(!sessions[1].attached);
     }
 
+    #[test]
+    fn test_parse_agent_panes() {
+        let panes = parse_agent_panes("%0||bash|/repo\n%3|2|claude|/repo/agents/2\n\n");
+        assert_eq!(panes.len(), 2);
+        assert_eq!(panes[0].agent_id, None);
+        assert!(panes[0].is_idle());
+        assert_eq!(panes[1].pane_id, "%3");
+        assert_eq!(panes[1].agent_id.as_deref(), Some("2"));
+        assert_eq!(panes[1].current_path.as_deref(), Some("/repo/agents/2"));
+        assert!(!panes[1].is_idle());
+    }
+
     #[test]
     fn test_is_tmux_available() {
         // This test depends on tmux being installed
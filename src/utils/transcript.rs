@@ -0,0 +1,368 @@
+//! Agent pane transcripts.
+//!
+//! `tmux pipe-pane` streams everything an agent pane prints into `sprite logs --capture`,
+//! which strips terminal escape sequences and appends timestamped lines to
+//! `agents/logs/<agent>/<date>.log`. When a day's file grows past the configured size it
+//! is renamed to `<date>.<n>.log`, and only the newest `max_files` files are kept.
+
+use crate::commands::config::LogRotationConfig;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Directory holding the transcripts of one agent.
+pub fn agent_log_dir(root: &Path, agent_id: &str) -> PathBuf {
+    root.join("agents").join("logs").join(agent_id)
+}
+
+/// Remove ANSI escape sequences and control characters from a line of terminal output.
+///
+/// Carriage returns keep only the text written after the last one, as a terminal would
+/// show it, and backspaces erase the previous character.
+pub fn strip_ansi(line: &str) -> String {
+    let line = line.trim_end_matches('\r');
+    let line = line.rsplit('\r').next().unwrap_or(line);
+
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, DCS and friends run until BEL or ESC \
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Character set selection takes one more character
+                Some('(' | ')' | '*' | '+' | '#' | '%') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\x08' => {
+                out.pop();
+            }
+            '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Appends captured pane output to the agent's dated, size-rotated transcript files.
+pub struct TranscriptWriter {
+    dir: PathBuf,
+    rotation: LogRotationConfig,
+    current: Option<(NaiveDate, File)>,
+}
+
+impl TranscriptWriter {
+    pub fn new(dir: PathBuf, rotation: LogRotationConfig) -> Self {
+        Self {
+            dir,
+            rotation,
+            current: None,
+        }
+    }
+
+    /// Copy a byte stream into the transcript line by line until it ends.
+    pub fn capture(&mut self, mut input: impl BufRead) -> Result<()> {
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let read = input
+                .read_until(b'\n', &mut buffer)
+                .context("Failed to read pane output")?;
+            if read == 0 {
+                return Ok(());
+            }
+            let line = String::from_utf8_lossy(&buffer);
+            self.write_line(Local::now().naive_local(), line.trim_end_matches('\n'))?;
+        }
+    }
+
+    /// Append one line of raw pane output, stamped with `at`.
+    pub fn write_line(&mut self, at: NaiveDateTime, raw: &str) -> Result<()> {
+        let file = self.file_for(at.date())?;
+        writeln!(file, "[{}] {}", at.format("%H:%M:%S"), strip_ansi(raw))
+            .context("Failed to write transcript")?;
+        file.flush().context("Failed to write transcript")
+    }
+
+    /// The open file for `date`, rotating or starting a new day as needed.
+    fn file_for(&mut self, date: NaiveDate) -> Result<&mut File> {
+        let max_bytes = self.rotation.max_size_mb.max(1) * 1024 * 1024;
+        let path = self.dir.join(format!("{}.log", date));
+
+        let stale = match &self.current {
+            Some((open_date, _)) if *open_date == date => std::fs::metadata(&path)
+                .map(|meta| meta.len() >= max_bytes)
+                .unwrap_or(true),
+            _ => true,
+        };
+
+        if stale {
+            self.current = None;
+            std::fs::create_dir_all(&self.dir)
+                .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+            if std::fs::metadata(&path).is_ok_and(|meta| meta.len() >= max_bytes) {
+                self.rotate(date, &path)?;
+            }
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            self.current = Some((date, file));
+            self.prune()?;
+        }
+
+        Ok(&mut self.current.as_mut().expect("transcript file is open").1)
+    }
+
+    /// Move a full day file aside as the next numbered segment.
+    fn rotate(&self, date: NaiveDate, path: &Path) -> Result<()> {
+        let day = date.to_string();
+        let next = list_files(&self.dir)?
+            .iter()
+            .filter_map(|file| segment_key(file))
+            .filter(|(file_date, segment)| *file_date == day && *segment != u32::MAX)
+            .map(|(_, segment)| segment + 1)
+            .max()
+            .unwrap_or(1);
+        let rotated = self.dir.join(format!("{}.{}.log", day, next));
+        std::fs::rename(path, &rotated)
+            .with_context(|| format!("Failed to rotate {}", path.display()))
+    }
+
+    /// Delete the oldest files beyond `max_files`.
+    fn prune(&self) -> Result<()> {
+        let files = list_files(&self.dir)?;
+        let keep = self.rotation.max_files.max(1) as usize;
+        for file in files.iter().take(files.len().saturating_sub(keep)) {
+            std::fs::remove_file(file)
+                .with_context(|| format!("Failed to remove {}", file.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// A transcript line with the time it was captured
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptLine {
+    pub at: NaiveDateTime,
+    pub text: String,
+}
+
+/// Reads lines appended to an agent's transcript, following day changes and rotation.
+pub struct TranscriptTail {
+    dir: PathBuf,
+    current: Option<PathBuf>,
+    offset: u64,
+    pending: String,
+}
+
+impl TranscriptTail {
+    /// Start at the current end of the transcript, so only later output is returned.
+    pub fn at_end(dir: &Path) -> Result<Self> {
+        let current = list_files(dir)?.pop();
+        let offset = current
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map_or(0, |meta| meta.len());
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            current,
+            offset,
+            pending: String::new(),
+        })
+    }
+
+    /// Complete lines written since the last call.
+    pub fn read_new(&mut self) -> Result<Vec<TranscriptLine>> {
+        // The live file is always the last one; a new day starts a new file
+        let latest = list_files(&self.dir)?.pop();
+        if latest.is_some() && latest != self.current {
+            self.current = latest;
+            self.offset = 0;
+            self.pending.clear();
+        }
+
+        let path = match &self.current {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+        let len = std::fs::metadata(path).map_or(0, |meta| meta.len());
+        if len < self.offset {
+            // Rotated underneath us: the new file starts empty
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        let mut file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut chunk = Vec::new();
+        file.read_to_end(&mut chunk)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.offset += chunk.len() as u64;
+        self.pending.push_str(&String::from_utf8_lossy(&chunk));
+
+        match self.pending.rfind('\n') {
+            Some(end) => {
+                let complete: String = self.pending.drain(..=end).collect();
+                Ok(parse_lines(file_date(path), &complete))
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Transcript files of one agent, oldest first.
+pub fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| segment_key(path).is_some())
+        .collect();
+    files.sort_by_key(|path| segment_key(path));
+    Ok(files)
+}
+
+/// Date and segment of a transcript file name; the live `<date>.log` sorts last.
+fn segment_key(path: &Path) -> Option<(String, u32)> {
+    let name = path.file_name()?.to_str()?.strip_suffix(".log")?;
+    let (date, segment) = match name.split_once('.') {
+        Some((date, segment)) => (date, segment.parse().ok()?),
+        None => (name, u32::MAX),
+    };
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some((date.to_string(), segment))
+}
+
+/// Parse the lines of one transcript file. Lines without a timestamp inherit the previous one.
+pub fn read_file(path: &Path) -> Result<Vec<TranscriptLine>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(parse_lines(file_date(path), &content))
+}
+
+/// The day a transcript file covers, from its name.
+pub fn file_date(path: &Path) -> NaiveDate {
+    segment_key(path)
+        .and_then(|(date, _)| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| Local::now().date_naive())
+}
+
+/// Parse transcript text written on `date`.
+pub fn parse_lines(date: NaiveDate, content: &str) -> Vec<TranscriptLine> {
+    let mut at = date.and_time(NaiveTime::MIN);
+    content
+        .lines()
+        .map(|line| {
+            let stamped = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once("] "))
+                .and_then(|(time, text)| {
+                    Some((NaiveTime::parse_from_str(time, "%H:%M:%S").ok()?, text))
+                });
+            let text = match stamped {
+                Some((time, text)) => {
+                    at = date.and_time(time);
+                    text
+                }
+                None => line,
+            };
+            TranscriptLine {
+                at,
+                text: text.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m done"), "ok done");
+        assert_eq!(strip_ansi("\x1b]0;title\x07$ ls\r"), "$ ls");
+        assert_eq!(strip_ansi("10%\r50%\r100%"), "100%");
+        assert_eq!(strip_ansi("\x1b[?2004hgit sx\x08tatus"), "git status");
+        assert_eq!(strip_ansi("a\tb\x07"), "a\tb");
+    }
+
+    #[test]
+    fn test_writer_rotates_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = TranscriptWriter::new(
+            dir.path().to_path_buf(),
+            LogRotationConfig {
+                max_size_mb: 1,
+                max_files: 3,
+            },
+        );
+        let day = |d: u32| {
+            NaiveDate::from_ymd_opt(2026, 10, d)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap()
+        };
+
+        let big = "x".repeat(600 * 1024);
+        writer.write_line(day(17), "yesterday").unwrap();
+        writer.write_line(day(18), "\x1b[31mfirst\x1b[0m").unwrap();
+        writer.write_line(day(18), &big).unwrap();
+        writer.write_line(day(18), &big).unwrap();
+        writer.write_line(day(18), "after rotation").unwrap();
+
+        let names: Vec<String> = list_files(dir.path())
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["2026-10-17.log", "2026-10-18.1.log", "2026-10-18.log"]
+        );
+
+        let first = read_file(&dir.path().join("2026-10-18.1.log")).unwrap();
+        assert_eq!(first[0].text, "first");
+        assert_eq!(first[0].at, day(18));
+        let current = read_file(&dir.path().join("2026-10-18.log")).unwrap();
+        assert_eq!(current.last().unwrap().text, "after rotation");
+
+        writer.write_line(day(19), "next day").unwrap();
+        let files = list_files(dir.path()).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(!dir.path().join("2026-10-17.log").exists());
+    }
+}
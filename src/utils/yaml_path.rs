@@ -0,0 +1,306 @@
+//! Dotted-path access into YAML value trees.
+//!
+//! Paths like `agents.2.config.env_vars.RUST_LOG` walk maps by key and lists
+//! either by the `id` field of their elements or by numeric index, so agent
+//! entries can be addressed by agent ID.
+
+use crate::error::SpriteError;
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+
+/// Split a dotted path into its segments.
+pub fn split_path(path: &str) -> Result<Vec<String>> {
+    let segments: Vec<String> = path.split('.').map(|s| s.to_string()).collect();
+    if path.is_empty() || segments.iter().any(|s| s.is_empty()) {
+        return Err(SpriteError::config(format!("Invalid configuration key: '{}'", path)).into());
+    }
+    Ok(segments)
+}
+
+/// Parse a command-line value as a YAML scalar, list or map, falling back to a plain string.
+pub fn parse_value(raw: &str) -> Value {
+    match serde_yaml::from_str::<Value>(raw) {
+        Ok(Value::Null) if !raw.trim().is_empty() && raw.trim() != "null" && raw.trim() != "~" => {
+            Value::String(raw.to_string())
+        }
+        Ok(value) => value,
+        Err(_) => Value::String(raw.to_string()),
+    }
+}
+
+/// Render a value for display: scalars as-is, lists and maps as YAML.
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Look up the value at a path.
+pub fn get<'a>(root: &'a Value, path: &[String]) -> Result<&'a Value> {
+    let mut current = root;
+    for (depth, segment) in path.iter().enumerate() {
+        current = match current {
+            Value::Mapping(map) => map.get(segment),
+            Value::Sequence(seq) => find_in_sequence(seq, segment).map(|i| &seq[i]),
+            _ => None,
+        }
+        .ok_or_else(|| not_found(path, depth))?;
+    }
+    Ok(current)
+}
+
+/// Replace (or create) the value at a path. Missing intermediate maps are created.
+pub fn set(root: &mut Value, path: &[String], value: Value) -> Result<()> {
+    let (last, parent_path) = split_last(path)?;
+    let parent = navigate_mut(root, parent_path, true)?;
+    if parent.is_null() {
+        *parent = Value::Mapping(Mapping::new());
+    }
+
+    match parent {
+        Value::Mapping(map) => {
+            map.insert(Value::String(last.clone()), value);
+            Ok(())
+        }
+        Value::Sequence(seq) => {
+            let index = find_in_sequence(seq, last).ok_or_else(|| {
+                SpriteError::config(format!(
+                    "No list entry '{}' at '{}'. Use 'config add' to append.",
+                    last,
+                    parent_path.join(".")
+                ))
+            })?;
+            seq[index] = value;
+            Ok(())
+        }
+        _ => Err(not_container(parent_path)),
+    }
+}
+
+/// Remove the value at a path, returning it.
+pub fn unset(root: &mut Value, path: &[String]) -> Result<Value> {
+    let (last, parent_path) = split_last(path)?;
+    let parent = navigate_mut(root, parent_path, false)?;
+
+    let removed = match parent {
+        Value::Mapping(map) => map.remove(last),
+        Value::Sequence(seq) => find_in_sequence(seq, last).map(|i| seq.remove(i)),
+        _ => None,
+    };
+    removed.ok_or_else(|| not_found(path, path.len() - 1))
+}
+
+/// Append an item to the list at a path, creating the list if it is missing.
+pub fn add(root: &mut Value, path: &[String], item: Value) -> Result<()> {
+    let target = navigate_mut(root, path, true)?;
+    if target.is_null() {
+        *target = Value::Sequence(Vec::new());
+    }
+
+    match target {
+        Value::Sequence(seq) => {
+            seq.push(item);
+            Ok(())
+        }
+        _ => Err(SpriteError::config(format!("'{}' is not a list", path.join("."))).into()),
+    }
+}
+
+/// Remove every list item equal to `item` from the list at a path.
+///
+/// Returns how many items were removed.
+pub fn remove_item(root: &mut Value, path: &[String], item: &Value) -> Result<usize> {
+    let target = navigate_mut(root, path, false)?;
+    match target {
+        Value::Sequence(seq) => {
+            let before = seq.len();
+            seq.retain(|v| !values_match(v, item));
+            Ok(before - seq.len())
+        }
+        _ => Err(SpriteError::config(format!("'{}' is not a list", path.join("."))).into()),
+    }
+}
+
+/// Walk to the value at a path, optionally creating missing maps on the way
+fn navigate_mut<'a>(root: &'a mut Value, path: &[String], create: bool) -> Result<&'a mut Value> {
+    let mut current = root;
+    for (depth, segment) in path.iter().enumerate() {
+        if create && current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+
+        current = match current {
+            Value::Mapping(map) => {
+                let key = Value::String(segment.clone());
+                if create && !map.contains_key(&key) {
+                    map.insert(key.clone(), Value::Null);
+                }
+                map.get_mut(&key)
+            }
+            Value::Sequence(seq) => match find_in_sequence(seq, segment) {
+                Some(index) => seq.get_mut(index),
+                None => None,
+            },
+            _ => return Err(not_container(&path[..depth])),
+        }
+        .ok_or_else(|| not_found(path, depth))?;
+    }
+    Ok(current)
+}
+
+/// Find a list element by its `id` field, or by numeric index
+fn find_in_sequence(seq: &[Value], segment: &str) -> Option<usize> {
+    seq.iter()
+        .position(|item| {
+            item.get("id")
+                .map(|id| format_value(id) == segment)
+                .unwrap_or(false)
+        })
+        .or_else(|| segment.parse::<usize>().ok().filter(|&i| i < seq.len()))
+}
+
+/// Compare list items, treating `42` and `"42"` as equal
+fn values_match(a: &Value, b: &Value) -> bool {
+    a == b || format_value(a) == format_value(b)
+}
+
+fn split_last(path: &[String]) -> Result<(&String, &[String])> {
+    path.split_last()
+        .ok_or_else(|| SpriteError::config("Configuration key cannot be empty").into())
+}
+
+fn not_found(path: &[String], depth: usize) -> anyhow::Error {
+    SpriteError::config(format!(
+        "Configuration key '{}' not found (no '{}')",
+        path.join("."),
+        path[..=depth].join(".")
+    ))
+    .into()
+}
+
+fn not_container(path: &[String]) -> anyhow::Error {
+    SpriteError::config(format!("'{}' is not a map or list", path.join("."))).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        serde_yaml::from_str(
+            r#"
+session_name: demo
+agents:
+  - id: "1"
+    config:
+      env_vars: {}
+  - id: "2"
+    config:
+      env_vars:
+        RUST_LOG: info
+sync:
+  exclude_branches: [main]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn path(p: &str) -> Vec<String> {
+        split_path(p).unwrap()
+    }
+
+    #[test]
+    fn test_get_by_id_index_and_key() {
+        let root = sample();
+        assert_eq!(
+            format_value(get(&root, &path("agents.2.config.env_vars.RUST_LOG")).unwrap()),
+            "info"
+        );
+        // Numeric segments fall back to list indices when no id matches
+        assert_eq!(
+            format_value(get(&root, &path("sync.exclude_branches.0")).unwrap()),
+            "main"
+        );
+        assert!(get(&root, &path("agents.9")).is_err());
+        assert!(split_path("agents..id").is_err());
+    }
+
+    #[test]
+    fn test_set_unset_add_remove() {
+        let mut root = sample();
+
+        set(
+            &mut root,
+            &path("agents.1.config.env_vars.RUST_LOG"),
+            parse_value("debug"),
+        )
+        .unwrap();
+        set(
+            &mut root,
+            &path("settings.logging.level"),
+            parse_value("warn"),
+        )
+        .unwrap();
+        assert_eq!(
+            format_value(get(&root, &path("agents.1.config.env_vars.RUST_LOG")).unwrap()),
+            "debug"
+        );
+        assert_eq!(
+            format_value(get(&root, &path("settings.logging.level")).unwrap()),
+            "warn"
+        );
+
+        add(
+            &mut root,
+            &path("sync.exclude_branches"),
+            parse_value("release/*"),
+        )
+        .unwrap();
+        add(
+            &mut root,
+            &path("sync.pre_sync_hooks"),
+            parse_value("cargo fmt"),
+        )
+        .unwrap();
+        assert_eq!(
+            get(&root, &path("sync.exclude_branches")).unwrap(),
+            &parse_value("[main, release/*]")
+        );
+        assert_eq!(
+            remove_item(
+                &mut root,
+                &path("sync.exclude_branches"),
+                &parse_value("main")
+            )
+            .unwrap(),
+            1
+        );
+
+        let removed = unset(&mut root, &path("agents.2.config.env_vars.RUST_LOG")).unwrap();
+        assert_eq!(format_value(&removed), "info");
+        assert!(unset(&mut root, &path("agents.2.config.env_vars.RUST_LOG")).is_err());
+        assert!(set(&mut root, &path("session_name.inner"), parse_value("x")).is_err());
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("true"), Value::Bool(true));
+        assert_eq!(
+            parse_value("42"),
+            serde_yaml::from_str::<Value>("42").unwrap()
+        );
+        assert_eq!(
+            parse_value("release/*"),
+            Value::String("release/*".to_string())
+        );
+        assert_eq!(parse_value("*alias"), Value::String("*alias".to_string()));
+        assert!(parse_value("[a, b]").is_sequence());
+    }
+}
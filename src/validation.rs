@@ -8,7 +8,11 @@ use crate::models::{
     config::SyncHook, Agent, AgentStatus, ConflictResolution, LoggingConfig, PerformanceSettings,
     ProjectConfig, ProjectSettings, SecuritySettings, SyncConfig,
 };
+use crate::utils::yaml_path;
 use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+use std::collections::HashMap;
 
 /// Validate configuration with detailed error messages.
 ///
@@ -644,6 +648,497 @@ pub fn validate_config_with_git(config: &ProjectConfig) -> Result<()> {
     Ok(())
 }
 
+/// A problem found while checking a configuration document, with its position
+/// in the source file when known.
+#[derive(Debug)]
+pub struct ConfigIssue {
+    /// Dotted path of the offending value; list entries are addressed by `id`
+    pub field: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub error: SpriteError,
+}
+
+impl ConfigIssue {
+    fn new(field: &str, message: impl Into<String>, value: Option<&YamlValue>) -> Self {
+        let value = value
+            .filter(|v| !v.is_mapping() && !v.is_sequence())
+            .map(yaml_path::format_value);
+        Self {
+            field: field.to_string(),
+            line: None,
+            column: None,
+            error: SpriteError::validation(message, Some(field), value),
+        }
+    }
+
+    /// Place a validation error at the position of its field in `source_map`.
+    pub fn located(error: SpriteError, source_map: &SourceMap) -> Self {
+        let field = match &error {
+            SpriteError::Validation {
+                field: Some(field), ..
+            } => field.clone(),
+            _ => String::new(),
+        };
+        let position = source_map.position(&field);
+        Self {
+            field,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            error,
+        }
+    }
+
+    /// The problem description without the error kind prefix.
+    pub fn message(&self) -> String {
+        match &self.error {
+            SpriteError::Validation { message, .. }
+            | SpriteError::Config { message, .. }
+            | SpriteError::Yaml { message, .. } => message.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// The offending value, for scalar values.
+    pub fn value(&self) -> Option<&str> {
+        match &self.error {
+            SpriteError::Validation { value, .. } => value.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Check a YAML configuration document against a JSON Schema.
+///
+/// Every problem is reported rather than just the first one. `partial`
+/// documents, such as local overrides, may leave out required fields.
+pub fn check_document(content: &str, schema: &JsonValue, partial: bool) -> Vec<ConfigIssue> {
+    let document: YamlValue = match serde_yaml::from_str(content) {
+        Ok(document) => document,
+        Err(e) => {
+            let location = e.location();
+            let line = location.as_ref().map(|l| l.line());
+            let column = location.as_ref().map(|l| l.column());
+            return vec![ConfigIssue {
+                field: String::new(),
+                line,
+                column,
+                error: SpriteError::yaml(e.to_string(), line, column),
+            }];
+        }
+    };
+    if partial && document.is_null() {
+        return Vec::new();
+    }
+
+    let mut checker = SchemaChecker {
+        root: schema,
+        partial,
+        issues: Vec::new(),
+    };
+    checker.check(schema, &document, "");
+
+    let source_map = SourceMap::new(content, &document);
+    checker
+        .issues
+        .into_iter()
+        .map(|mut issue| {
+            if let Some((line, column)) = source_map.position(&issue.field) {
+                issue.line = Some(line);
+                issue.column = Some(column);
+            }
+            issue
+        })
+        .collect()
+}
+
+/// Walks a YAML value alongside the JSON Schema describing it.
+struct SchemaChecker<'a> {
+    root: &'a JsonValue,
+    partial: bool,
+    issues: Vec<ConfigIssue>,
+}
+
+impl<'a> SchemaChecker<'a> {
+    fn check(&mut self, schema: &'a JsonValue, value: &YamlValue, field: &str) {
+        if let YamlValue::Tagged(tagged) = value {
+            return self.check(schema, &tagged.value, field);
+        }
+        let schema = match schema {
+            JsonValue::Object(schema) => schema,
+            JsonValue::Bool(false) => {
+                self.issues.push(ConfigIssue::new(
+                    field,
+                    "value is not allowed here",
+                    Some(value),
+                ));
+                return;
+            }
+            _ => return,
+        };
+
+        if let Some(target) = schema.get("$ref").and_then(|r| r.as_str()) {
+            if let Some(resolved) = self.resolve(target) {
+                self.check(resolved, value, field);
+            }
+            return;
+        }
+        for option in schema
+            .get("allOf")
+            .and_then(|s| s.as_array())
+            .into_iter()
+            .flatten()
+        {
+            self.check(option, value, field);
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(options) = schema.get(keyword).and_then(|s| s.as_array()) {
+                if !options.iter().any(|option| self.accepts(option, value)) {
+                    if let Some(first) = options.first() {
+                        self.check(first, value, field);
+                    }
+                }
+            }
+        }
+
+        let expected: Vec<&str> = match schema.get("type") {
+            Some(JsonValue::String(kind)) => vec![kind.as_str()],
+            Some(JsonValue::Array(kinds)) => kinds.iter().filter_map(|k| k.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !expected.is_empty() && !expected.iter().any(|kind| type_matches(kind, value)) {
+            self.issues.push(ConfigIssue::new(
+                field,
+                format!(
+                    "expected {}, found {}",
+                    expected.join(" or "),
+                    describe_type(value)
+                ),
+                Some(value),
+            ));
+            return;
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+            let matches = serde_json::to_value(value)
+                .map(|json| allowed.contains(&json))
+                .unwrap_or(false);
+            if !matches {
+                let choices: Vec<String> = allowed.iter().map(|a| a.to_string()).collect();
+                self.issues.push(ConfigIssue::new(
+                    field,
+                    format!("must be one of {}", choices.join(", ")),
+                    Some(value),
+                ));
+            }
+        }
+        if let (Some(minimum), Some(number)) = (
+            schema.get("minimum").and_then(|m| m.as_f64()),
+            value.as_f64(),
+        ) {
+            if number < minimum {
+                self.issues.push(ConfigIssue::new(
+                    field,
+                    format!("must be at least {}", minimum),
+                    Some(value),
+                ));
+            }
+        }
+
+        match value {
+            YamlValue::Mapping(map) => self.check_object(schema, map, field),
+            YamlValue::Sequence(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(
+                            item_schema,
+                            item,
+                            &join_field(field, &entry_label(item, index)),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &'a serde_json::Map<String, JsonValue>,
+        map: &serde_yaml::Mapping,
+        field: &str,
+    ) {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        let mut misspelled = Vec::new();
+
+        for (key, item) in map {
+            let name = yaml_path::format_value(key);
+            let child = join_field(field, &name);
+            if let Some(property) = properties.and_then(|p| p.get(&name)) {
+                self.check(property, item, &child);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(JsonValue::Bool(false)) => {
+                    let mut message = format!("unknown field '{}'", name);
+                    if let Some(close) = properties.and_then(|p| closest_name(&name, p.keys())) {
+                        message.push_str(&format!(", did you mean '{}'?", close));
+                        misspelled.push(close);
+                    }
+                    self.issues
+                        .push(ConfigIssue::new(&child, message, Some(item)));
+                }
+                Some(additional) => self.check(additional, item, &child),
+                None => {}
+            }
+        }
+
+        // A misspelled key already explains why the real one is missing
+        if !self.partial {
+            for name in schema
+                .get("required")
+                .and_then(|r| r.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r.as_str())
+                .filter(|name| !misspelled.contains(name))
+            {
+                if !map.contains_key(name) {
+                    self.issues.push(ConfigIssue::new(
+                        &join_field(field, name),
+                        format!("missing required field '{}'", name),
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Whether `value` satisfies `schema` without reporting anything.
+    fn accepts(&self, schema: &'a JsonValue, value: &YamlValue) -> bool {
+        let mut probe = SchemaChecker {
+            root: self.root,
+            partial: self.partial,
+            issues: Vec::new(),
+        };
+        probe.check(schema, value, "");
+        probe.issues.is_empty()
+    }
+
+    fn resolve(&self, target: &str) -> Option<&'a JsonValue> {
+        let pointer = target.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+}
+
+fn type_matches(kind: &str, value: &YamlValue) -> bool {
+    match kind {
+        "null" => value.is_null(),
+        "boolean" => value.is_bool(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_sequence(),
+        "object" => value.is_mapping(),
+        _ => true,
+    }
+}
+
+fn describe_type(value: &YamlValue) -> &'static str {
+    match value {
+        YamlValue::Null => "null",
+        YamlValue::Bool(_) => "boolean",
+        YamlValue::Number(n) if n.is_f64() => "number",
+        YamlValue::Number(_) => "integer",
+        YamlValue::String(_) => "string",
+        YamlValue::Sequence(_) => "array",
+        YamlValue::Mapping(_) => "object",
+        YamlValue::Tagged(_) => "tagged value",
+    }
+}
+
+fn join_field(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+/// Label of a list entry: its `id` (or `name`) when it has one, otherwise its index.
+fn entry_label(item: &YamlValue, index: usize) -> String {
+    match item.get("id").or_else(|| item.get("name")) {
+        Some(id) if !id.is_mapping() && !id.is_sequence() => yaml_path::format_value(id),
+        _ => index.to_string(),
+    }
+}
+
+/// The known name closest to a misspelled one, if any is close enough.
+fn closest_name<'a>(name: &str, known: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 4).max(2);
+    known
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Line and column (both 1-based) of each key and list entry in a
+/// block-style YAML document, addressed by dotted field path.
+///
+/// Values written in flow style (`{a: 1}`, `[x, y]`) are not broken down;
+/// lookups for them fall back to the enclosing key.
+pub struct SourceMap {
+    positions: HashMap<String, (usize, usize)>,
+}
+
+struct Frame<'a> {
+    indent: usize,
+    field: String,
+    node: Option<&'a YamlValue>,
+    item: bool,
+    next_index: usize,
+}
+
+impl SourceMap {
+    /// Map the keys of `content`, using the parsed `document` to label list
+    /// entries by their `id`.
+    pub fn new(content: &str, document: &YamlValue) -> Self {
+        let mut positions = HashMap::new();
+        let mut stack = vec![Frame {
+            indent: 0,
+            field: String::new(),
+            node: Some(document),
+            item: false,
+            next_index: 0,
+        }];
+        let mut block_scalar_indent: Option<usize> = None;
+
+        for (number, raw) in content.lines().enumerate() {
+            let trimmed = raw.trim_start();
+            let mut indent = raw.len() - trimmed.len();
+            if let Some(block_indent) = block_scalar_indent {
+                if trimmed.is_empty() || indent > block_indent {
+                    continue;
+                }
+                block_scalar_indent = None;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+                continue;
+            }
+
+            let line = number + 1;
+            let mut rest = trimmed;
+            while rest == "-" || rest.starts_with("- ") {
+                while stack.len() > 1 {
+                    let top = &stack[stack.len() - 1];
+                    if top.indent > indent || (top.indent == indent && top.item) {
+                        stack.pop();
+                    } else {
+                        break;
+                    }
+                }
+                let parent = stack.last_mut().expect("root frame");
+                let index = parent.next_index;
+                parent.next_index += 1;
+                let node = parent
+                    .node
+                    .and_then(|n| n.as_sequence())
+                    .and_then(|items| items.get(index));
+                let label = node
+                    .map(|n| entry_label(n, index))
+                    .unwrap_or_else(|| index.to_string());
+                let field = join_field(&parent.field, &label);
+                positions.entry(field.clone()).or_insert((line, indent + 1));
+                stack.push(Frame {
+                    indent,
+                    field,
+                    node,
+                    item: true,
+                    next_index: 0,
+                });
+
+                let after = rest[1..].trim_start();
+                indent += rest.len() - after.len();
+                rest = after;
+            }
+
+            let (key, value) = match split_key(rest) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            while stack.len() > 1 && stack[stack.len() - 1].indent >= indent {
+                stack.pop();
+            }
+            let parent = stack.last().expect("root frame");
+            let node = parent.node.and_then(|n| n.get(key.as_str()));
+            let field = join_field(&parent.field, &key);
+            positions.entry(field.clone()).or_insert((line, indent + 1));
+            stack.push(Frame {
+                indent,
+                field,
+                node,
+                item: false,
+                next_index: 0,
+            });
+
+            if value.starts_with('|') || value.starts_with('>') {
+                block_scalar_indent = Some(indent);
+            }
+        }
+
+        Self { positions }
+    }
+
+    /// Position of `field`, or of its closest parent that appears in the file.
+    pub fn position(&self, field: &str) -> Option<(usize, usize)> {
+        let mut current = field;
+        loop {
+            if let Some(position) = self.positions.get(current) {
+                return Some(*position);
+            }
+            current = &current[..current.rfind('.')?];
+        }
+    }
+}
+
+/// Split `key: value` into its key (unquoted) and the remaining value text.
+fn split_key(text: &str) -> Option<(String, &str)> {
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = text[1..].find(quote)? + 1;
+        let after = text[end + 1..].strip_prefix(':')?;
+        if !after.is_empty() && !after.starts_with(' ') {
+            return None;
+        }
+        return Some((text[1..end].to_string(), after.trim()));
+    }
+    if text.starts_with('{') || text.starts_with('[') {
+        return None;
+    }
+
+    let bytes = text.as_bytes();
+    let colon = (0..bytes.len())
+        .find(|&i| bytes[i] == b':' && (i + 1 == bytes.len() || bytes[i + 1] == b' '))?;
+    Some((
+        text[..colon].trim_end().to_string(),
+        text[colon + 1..].trim(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -694,4 +1189,53 @@ mod tests {
         let security = SecuritySettings::default();
         assert!(validate_security_settings(&security).is_ok());
     }
+
+    #[test]
+    fn test_source_map_positions() {
+        let content = "# comment\nagents:\n- id: '7'\n  branch: a\n  notes: |\n    key: not a key\n- branch: b\nsync:\n  hooks: [a, b]\n";
+        let document: YamlValue = serde_yaml::from_str(content).unwrap();
+        let map = SourceMap::new(content, &document);
+
+        assert_eq!(map.position("agents"), Some((2, 1)));
+        assert_eq!(map.position("agents.7"), Some((3, 1)));
+        assert_eq!(map.position("agents.7.id"), Some((3, 3)));
+        assert_eq!(map.position("agents.7.branch"), Some((4, 3)));
+        assert_eq!(map.position("agents.7.notes.key"), Some((5, 3)));
+        assert_eq!(map.position("agents.1.branch"), Some((7, 3)));
+        assert_eq!(map.position("sync.hooks.1"), Some((9, 3)));
+        assert_eq!(map.position("missing"), None);
+    }
+
+    #[test]
+    fn test_check_document_collects_every_issue() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["name", "limits"],
+            "additionalProperties": false,
+            "properties": {
+                "name": { "type": "string" },
+                "limits": { "$ref": "#/definitions/Limits" }
+            },
+            "definitions": {
+                "Limits": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "max_mb": { "type": "integer", "minimum": 0.0 }
+                    }
+                }
+            }
+        });
+
+        let issues = check_document("nmae: x\nlimits:\n  max_mb: -1\n", &schema, false);
+        let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["nmae", "limits.max_mb"]);
+        assert!(issues[0].message().contains("did you mean 'name'?"));
+        assert_eq!((issues[1].line, issues[1].column), (Some(3), Some(3)));
+
+        let issues = check_document("name: [unclosed\n", &schema, false);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].error, SpriteError::Yaml { .. }));
+        assert!(issues[0].line.is_some());
+    }
 }
@@ -40,16 +40,22 @@ mod cli_parsing_tests {
                     agent_id,
                     branch,
                     workspace,
+                    base,
+                    task,
                     model,
                     description,
+                    from_template,
                     no_workspace,
                 },
             } => {
                 assert_eq!(agent_id, "5");
                 assert_eq!(branch, Some("feature/test".to_string()));
                 assert_eq!(workspace, Some("custom/path".to_string()));
-                assert_eq!(model, "claude-sonnet-4");
+                assert_eq!(base, "main");
+                assert_eq!(task, None);
+                assert_eq!(model, Some("claude-sonnet-4".to_string()));
                 assert_eq!(description, Some("Test agent".to_string()));
+                assert_eq!(from_template, None);
                 assert!(no_workspace);
             }
             _ => panic!("Expected Agents::Create command"),
@@ -76,7 +82,7 @@ mod cli_parsing_tests {
             match cli.command {
                 Commands::Config { command } => {
                     match expected_subcommand {
-                        "show" => assert!(matches!(command, ConfigCommands::Show)),
+                        "show" => assert!(matches!(command, ConfigCommands::Show { .. })),
                         "set" => assert!(matches!(command, ConfigCommands::Set { .. })),
                         "get" => assert!(matches!(command, ConfigCommands::Get { .. })),
                         _ => panic!("Unexpected subcommand: {}", expected_subcommand),